@@ -19,6 +19,16 @@ type DownloadProgress = {
   percentage: number;
 };
 
+type ModIssue = {
+  kind: string;
+  id: string;
+  [key: string]: unknown;
+};
+
+type LaunchResult =
+  | { status: 'exited'; message: string }
+  | { status: 'mod_issues'; report: { issues: ModIssue[] } };
+
 type DownloadStatus = {
   [versionId: string]: {
     status: 'idle' | 'downloading' | 'complete' | 'error';
@@ -249,15 +259,36 @@ export default function DevLaunchContext() {
     setLoading(true);
     setMessage(null);
     try {
-      const result = await invoke<string>('launch_game', {
+      let result = await invoke<LaunchResult>('launch_game', {
         username,
         versionId: selectedVersion,
         gameDir: gameDirectory
       });
-      setMessage({
-        text: result,
-        type: 'success'
-      });
+      // mod 检查发现问题时由用户决定是否仍然启动
+      if (result.status === 'mod_issues') {
+        const issues = result.report.issues
+          .map(issue => `${issue.kind}: ${issue.id}`)
+          .join('\n');
+        if (!window.confirm(`mod 检查发现以下问题，是否仍然启动？\n${issues}`)) {
+          setMessage({
+            text: `mod 检查发现 ${result.report.issues.length} 个问题，已取消启动`,
+            type: 'error'
+          });
+          return;
+        }
+        result = await invoke<LaunchResult>('launch_game', {
+          username,
+          versionId: selectedVersion,
+          gameDir: gameDirectory,
+          settings: { ignore_mod_issues: true }
+        });
+      }
+      if (result.status === 'exited') {
+        setMessage({
+          text: result.message,
+          type: 'success'
+        });
+      }
     } catch (error) {
       setMessage({
         text: `${error}`,
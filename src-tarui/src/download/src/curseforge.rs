@@ -0,0 +1,677 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use model::curseforge::{
+    File, FileResponse, FilesResponse, FingerprintMatch, FingerprintResponse, Manifest, Mod,
+    ModResponse, ModsResponse, SearchResponse,
+};
+use parse::Parse;
+use serde::Serialize;
+use serde_json::json;
+use zip::ZipArchive;
+
+use crate::{loader::Loader, mrpack};
+
+pub const CURSEFORGE_API: &str = "https://api.curseforge.com";
+pub const MINECRAFT_GAME_ID: u32 = 432;
+// Minecraft 下 mod 分类的 classId
+pub const MODS_CLASS_ID: u32 = 6;
+
+// 依赖关系中的必需前置
+const REQUIRED_DEPENDENCY: u32 = 3;
+
+// 作者不允许第三方下载的文件，需要用户手动下载后放到 path
+#[derive(Serialize)]
+pub struct BlockedFile {
+    pub project_id: u32,
+    pub file_id: u32,
+    pub file_name: String,
+    pub path: PathBuf,
+    // 手动下载的页面
+    pub url: Option<String>,
+}
+
+// 整合包导入结果
+#[derive(Serialize)]
+pub struct ModpackImport {
+    pub id: String,
+    pub blocked: Vec<BlockedFile>,
+}
+
+// 读取并校验整合包中的 manifest.json
+pub fn read_manifest(pack: &Path) -> Result<Manifest, Box<dyn std::error::Error>> {
+    let mut archive = ZipArchive::new(std::fs::File::open(pack)?)?;
+    let mut text = String::new();
+    archive
+        .by_name("manifest.json")
+        .map_err(|_| {
+            format!(
+                "{} is not a CurseForge modpack: missing manifest.json",
+                pack.display()
+            )
+        })?
+        .read_to_string(&mut text)?;
+
+    let manifest = Manifest::parse(&text)?;
+    if manifest.manifest_type != "minecraftModpack" {
+        return Err(format!("Unsupported manifestType: {}", manifest.manifest_type).into());
+    }
+    if manifest.manifest_version != 1 {
+        return Err(format!("Unsupported manifestVersion: {}", manifest.manifest_version).into());
+    }
+    Ok(manifest)
+}
+
+// modLoaders 中的加载器，优先取 primary，例如 forge-47.2.0 -> (Forge, 47.2.0)
+pub fn manifest_loader(manifest: &Manifest) -> Option<(Loader, String)> {
+    let loaders = &manifest.minecraft.mod_loaders;
+    let loader = loaders.iter().find(|l| l.primary).or(loaders.first())?;
+    let (name, version) = loader.id.split_once('-')?;
+    Some((name.parse::<Loader>().ok()?, version.to_string()))
+}
+
+// 按 classId 决定文件放在实例的哪个目录
+fn class_directory(class_id: Option<u32>) -> &'static str {
+    match class_id {
+        Some(12) => "resourcepacks",
+        Some(6552) => "shaderpacks",
+        _ => "mods",
+    }
+}
+
+// CurseForge v1 接口，需要用户提供 API key，base_url 可替换为本地服务用于测试
+pub struct CurseForge {
+    pub base_url: String,
+    pub api_key: String,
+}
+
+// 搜索条件，为空的条件不参与过滤
+#[derive(Default)]
+pub struct SearchQuery {
+    pub query: String,
+    pub game_version: Option<String>,
+    pub loader: Option<String>,
+    pub category_id: Option<u32>,
+    pub index: u32,
+    pub page_size: u32,
+}
+
+// CurseForge 的 modLoaderType
+pub fn mod_loader_type(loader: &str) -> Option<u32> {
+    match loader.to_lowercase().as_str() {
+        "forge" => Some(1),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
+}
+
+// CurseForge 使用的 murmur2 指纹：去掉空白字符 (9, 10, 13, 32) 后以 seed 1 计算
+pub fn fingerprint(bytes: &[u8]) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let data: Vec<u8> = bytes
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, 9 | 10 | 13 | 32))
+        .collect();
+
+    let mut hash = 1 ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        hash = hash.wrapping_mul(M);
+        hash ^= k;
+    }
+
+    let rest = chunks.remainder();
+    if !rest.is_empty() {
+        for (i, b) in rest.iter().enumerate() {
+            hash ^= (*b as u32) << (8 * i);
+        }
+        hash = hash.wrapping_mul(M);
+    }
+
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(M);
+    hash ^= hash >> 15;
+    hash
+}
+
+pub fn fingerprint_file<P: AsRef<Path>>(path: P) -> Result<u32, std::io::Error> {
+    Ok(fingerprint(&std::fs::read(path)?))
+}
+
+impl CurseForge {
+    pub fn new(base_url: &str, api_key: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    pub fn with_key(api_key: &str) -> Self {
+        Self::new(CURSEFORGE_API, api_key)
+    }
+
+    fn get(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!("{}/v1{}", self.base_url, path);
+        Ok(crate::client()?
+            .get(&url)
+            .header("x-api-key", &self.api_key)
+            .query(query)
+            .send()?
+            .error_for_status()?
+            .text()?)
+    }
+
+    fn post(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!("{}/v1{}", self.base_url, path);
+        Ok(crate::client()?
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .json(body)
+            .send()?
+            .error_for_status()?
+            .text()?)
+    }
+
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<SearchResponse, Box<dyn std::error::Error>> {
+        let mut params = vec![
+            ("gameId", MINECRAFT_GAME_ID.to_string()),
+            ("classId", MODS_CLASS_ID.to_string()),
+            ("searchFilter", query.query.clone()),
+            ("index", query.index.to_string()),
+            ("pageSize", query.page_size.clamp(1, 50).to_string()),
+        ];
+        if let Some(game_version) = &query.game_version {
+            params.push(("gameVersion", game_version.clone()));
+        }
+        if let Some(loader) = query.loader.as_deref().and_then(mod_loader_type) {
+            params.push(("modLoaderType", loader.to_string()));
+        }
+        if let Some(category_id) = query.category_id {
+            params.push(("categoryId", category_id.to_string()));
+        }
+        Ok(SearchResponse::parse(&self.get("/mods/search", &params)?)?)
+    }
+
+    pub fn get_mod(&self, mod_id: u32) -> Result<Mod, Box<dyn std::error::Error>> {
+        Ok(ModResponse::parse(&self.get(&format!("/mods/{}", mod_id), &[])?)?.data)
+    }
+
+    // mod 的文件列表，最新的在前
+    pub fn files(
+        &self,
+        mod_id: u32,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<File>, Box<dyn std::error::Error>> {
+        let mut params = Vec::new();
+        if let Some(game_version) = game_version {
+            params.push(("gameVersion", game_version.to_string()));
+        }
+        if let Some(loader) = loader.and_then(mod_loader_type) {
+            params.push(("modLoaderType", loader.to_string()));
+        }
+        let text = self.get(&format!("/mods/{}/files", mod_id), &params)?;
+        Ok(FilesResponse::parse(&text)?.data)
+    }
+
+    pub fn file(&self, mod_id: u32, file_id: u32) -> Result<File, Box<dyn std::error::Error>> {
+        let text = self.get(&format!("/mods/{}/files/{}", mod_id, file_id), &[])?;
+        Ok(FileResponse::parse(&text)?.data)
+    }
+
+    // 按 file id 批量获取文件
+    pub fn files_by_id(&self, file_ids: &[u32]) -> Result<Vec<File>, Box<dyn std::error::Error>> {
+        let text = self.post("/mods/files", &json!({ "fileIds": file_ids }))?;
+        Ok(FilesResponse::parse(&text)?.data)
+    }
+
+    // 按指纹批量识别文件，未收录的指纹不会出现在结果中
+    pub fn fingerprints(
+        &self,
+        fingerprints: &[u32],
+    ) -> Result<Vec<FingerprintMatch>, Box<dyn std::error::Error>> {
+        let text = self.post(
+            &format!("/fingerprints/{}", MINECRAFT_GAME_ID),
+            &json!({ "fingerprints": fingerprints }),
+        )?;
+        Ok(FingerprintResponse::parse(&text)?.data.exact_matches)
+    }
+
+    // 识别本地的 mod 文件，返回能在 CurseForge 上找到的文件及匹配结果
+    pub fn identify(
+        &self,
+        paths: &[PathBuf],
+    ) -> Result<Vec<(PathBuf, FingerprintMatch)>, Box<dyn std::error::Error>> {
+        let mut fingerprints = Vec::new();
+        for path in paths {
+            fingerprints.push(fingerprint_file(path)?);
+        }
+        if fingerprints.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let matches = self.fingerprints(&fingerprints)?;
+        Ok(paths
+            .iter()
+            .zip(fingerprints)
+            .filter_map(|(path, fingerprint)| {
+                matches
+                    .iter()
+                    .find(|m| m.file.file_fingerprint == fingerprint)
+                    .map(|m| (path.clone(), m.clone()))
+            })
+            .collect())
+    }
+
+    // 找出需要一起安装的必需前置，结果中第一个为所选文件本身
+    pub fn resolve(
+        &self,
+        file: File,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<File>, Box<dyn std::error::Error>> {
+        let mut mods = HashSet::new();
+        mods.insert(file.mod_id);
+        let mut resolved = vec![file];
+
+        let mut index = 0;
+        while index < resolved.len() {
+            let dependencies = resolved[index].dependencies.clone();
+            index += 1;
+
+            for dependency in dependencies {
+                if dependency.relation_type != REQUIRED_DEPENDENCY
+                    || mods.contains(&dependency.mod_id)
+                {
+                    continue;
+                }
+
+                let file = self
+                    .files(dependency.mod_id, game_version, loader)?
+                    .into_iter()
+                    .next()
+                    .ok_or(format!(
+                        "No file of mod {} found for {} {}",
+                        dependency.mod_id,
+                        loader.unwrap_or("any loader"),
+                        game_version.unwrap_or("any game version")
+                    ))?;
+
+                mods.insert(file.mod_id);
+                resolved.push(file);
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    // 下载文件到目录并校验 sha1，已存在且校验通过时跳过
+    pub fn download_file(
+        &self,
+        file: &File,
+        dir: &Path,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        // 文件名来自服务端，只保留最后一段，避免写到目录之外
+        let filename = Path::new(&file.file_name)
+            .file_name()
+            .ok_or(format!("Invalid file name: {}", file.file_name))?;
+        let path = dir.join(filename);
+        if path.exists()
+            && file
+                .sha1()
+                .is_some_and(|sha1| crate::sha1(&path).is_ok_and(|h| h.eq(sha1)))
+        {
+            return Ok(path);
+        }
+
+        let url = file.download_url.as_ref().ok_or(format!(
+            "File {} of mod {} can not be downloaded by third-party launchers",
+            file.file_name, file.mod_id
+        ))?;
+
+        if !dir.exists() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        println!("下载 {}", file.file_name);
+        let bytes = crate::client()?
+            .get(url)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+        let part = dir.join(format!("{}.part", filename.to_string_lossy()));
+        std::fs::write(&part, &bytes)?;
+
+        if let Some(sha1) = file.sha1() {
+            let hash = crate::sha1(&part)?;
+            if !hash.eq(sha1) {
+                std::fs::remove_file(&part)?;
+                return Err(format!(
+                    "File {} sha1 mismatch: expected {}, got {}",
+                    file.file_name, sha1, hash
+                )
+                .into());
+            }
+        }
+
+        std::fs::rename(&part, &path)?;
+        Ok(path)
+    }
+
+    // 按 mod id 批量获取 mod
+    pub fn mods_by_id(&self, mod_ids: &[u32]) -> Result<Vec<Mod>, Box<dyn std::error::Error>> {
+        let text = self.post("/mods", &json!({ "modIds": mod_ids }))?;
+        Ok(ModsResponse::parse(&text)?.data)
+    }
+
+    // 下载整合包中必需的文件并应用 overrides，返回不允许第三方下载的文件
+    pub fn install_modpack_files(
+        &self,
+        pack: &Path,
+        manifest: &Manifest,
+        instance_dir: &Path,
+    ) -> Result<Vec<BlockedFile>, Box<dyn std::error::Error>> {
+        let required: Vec<_> = manifest.files.iter().filter(|f| f.required).collect();
+        let mut blocked = Vec::new();
+
+        if !required.is_empty() {
+            let mod_ids: Vec<u32> = required.iter().map(|f| f.project_id).collect();
+            let mods: HashMap<u32, Mod> = self
+                .mods_by_id(&mod_ids)?
+                .into_iter()
+                .map(|m| (m.id, m))
+                .collect();
+            let file_ids: Vec<u32> = required.iter().map(|f| f.file_id).collect();
+            let files: HashMap<u32, File> = self
+                .files_by_id(&file_ids)?
+                .into_iter()
+                .map(|f| (f.id, f))
+                .collect();
+
+            for entry in required {
+                let file = files.get(&entry.file_id).ok_or(format!(
+                    "File {} of mod {} not found",
+                    entry.file_id, entry.project_id
+                ))?;
+                let project = mods.get(&entry.project_id);
+                let dir = instance_dir.join(class_directory(project.and_then(|m| m.class_id)));
+
+                if file.download_url.is_none() {
+                    blocked.push(BlockedFile {
+                        project_id: entry.project_id,
+                        file_id: entry.file_id,
+                        file_name: file.file_name.clone(),
+                        path: dir.join(&file.file_name),
+                        url: project
+                            .and_then(|m| m.links.as_ref()?.website_url.clone())
+                            .map(|url| {
+                                format!("{}/files/{}", url.trim_end_matches('/'), entry.file_id)
+                            }),
+                    });
+                    continue;
+                }
+                self.download_file(file, &dir)?;
+            }
+        }
+
+        let overrides = manifest.overrides.as_deref().unwrap_or("overrides");
+        let mut archive = ZipArchive::new(std::fs::File::open(pack)?)?;
+        mrpack::extract_directory(
+            &mut archive,
+            &format!("{}/", overrides.trim_end_matches('/')),
+            instance_dir,
+        )?;
+
+        Ok(blocked)
+    }
+
+    // 导入 CurseForge 整合包：安装游戏与加载器，创建以整合包命名的版本并写入文件
+    pub fn import_modpack(
+        &self,
+        game_dir: &Path,
+        pack: &Path,
+        name: Option<&str>,
+        java: &str,
+    ) -> Result<ModpackImport, Box<dyn std::error::Error>> {
+        let manifest = read_manifest(pack)?;
+        let game_version = &manifest.minecraft.version;
+
+        let name = name.unwrap_or(&manifest.name);
+        let id = path::instance_id(name);
+        if game_dir.join("versions").join(&id).exists() {
+            return Err(format!("Version: {} already exists", id).into());
+        }
+
+        println!("导入整合包 {}", manifest.name);
+        crate::version::install(game_dir, game_version)?;
+        let loader = manifest_loader(&manifest);
+        let base_version = match &loader {
+            Some((loader, loader_version)) => {
+                loader.install(game_dir, game_version, Some(loader_version), java)?
+            }
+            None => game_version.clone(),
+        };
+
+        let instance = mrpack::create_instance(game_dir, name, &base_version, loader.as_ref())?;
+        let instance_dir = instance.game_dir(game_dir);
+        match self.install_modpack_files(pack, &manifest, &instance_dir) {
+            Ok(blocked) => Ok(ModpackImport { id, blocked }),
+            Err(err) => {
+                // 导入失败时不留下不完整的版本
+                let _ = std::fs::remove_dir_all(&instance_dir);
+                Err(err)
+            }
+        }
+    }
+
+    // 安装指定文件及其必需前置到 mods 目录，返回下载的文件
+    pub fn install(
+        &self,
+        mods_dir: &Path,
+        mod_id: u32,
+        file_id: u32,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let file = self.file(mod_id, file_id)?;
+        let mut paths = Vec::new();
+        for file in self.resolve(file, game_version, loader)? {
+            paths.push(self.download_file(&file, mods_dir)?);
+        }
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use sha1::{Digest, Sha1};
+
+    use super::*;
+
+    fn file(
+        id: u32,
+        mod_id: u32,
+        url: Option<String>,
+        content: &[u8],
+        dependencies: &str,
+    ) -> String {
+        format!(
+            r#"{{"id": {id}, "modId": {mod_id}, "displayName": "{mod_id}", "fileName": "{mod_id}.jar", "releaseType": 1, "fileDate": "2023-08-20T22:31:42.453Z", "fileLength": {}, "downloadUrl": {}, "gameVersions": ["1.20.1", "Forge"], "hashes": [{{"value": "{:x}", "algo": 1}}], "dependencies": [{dependencies}], "fileFingerprint": {}}}"#,
+            content.len(),
+            url.map_or("null".to_string(), |url| format!(r#""{}""#, url)),
+            Sha1::digest(content),
+            fingerprint(content)
+        )
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        assert_eq!(fingerprint(b""), 0x5bd1_5e36);
+        // 空白字符不参与计算
+        assert_eq!(fingerprint(b"a b\r\nc\td"), fingerprint(b"abcd"));
+        assert_ne!(fingerprint(b"abcd"), fingerprint(b"abce"));
+    }
+
+    #[test]
+    fn test_install() {
+        let fixture = crate::fixture::Fixture::new();
+        let address = fixture.address.clone();
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v1/mods/2/files/20".to_string(),
+            format!(
+                r#"{{"data": {}}}"#,
+                file(
+                    20,
+                    2,
+                    Some(format!("{}/files/2.jar", address)),
+                    b"jei",
+                    r#"{"modId": 3, "relationType": 3}, {"modId": 4, "relationType": 2}"#
+                )
+            )
+            .into_bytes(),
+        );
+        routes.insert(
+            "/v1/mods/3/files".to_string(),
+            format!(
+                r#"{{"data": [{}], "pagination": {{"index": 0, "pageSize": 50, "resultCount": 1, "totalCount": 1}}}}"#,
+                file(30, 3, None, b"restricted", "")
+            )
+            .into_bytes(),
+        );
+        routes.insert(
+            "/v1/fingerprints/432".to_string(),
+            format!(
+                r#"{{"data": {{"exactMatches": [{{"id": 2, "file": {}, "latestFiles": []}}], "unmatchedFingerprints": []}}}}"#,
+                file(20, 2, None, b"jei", "")
+            )
+            .into_bytes(),
+        );
+        routes.insert("/files/2.jar".to_string(), b"jei".to_vec());
+        let curseforge = CurseForge::new(&fixture.serve(routes), "key");
+
+        let mods_dir = &std::env::temp_dir().join("mcl-download-test-curseforge");
+        let _ = std::fs::remove_dir_all(mods_dir);
+
+        // 前置不允许第三方下载
+        let err = curseforge
+            .install(mods_dir, 2, 20, Some("1.20.1"), Some("forge"))
+            .unwrap_err();
+        assert!(err.to_string().contains("third-party"));
+        assert!(mods_dir.join("2.jar").exists());
+
+        let identified = curseforge
+            .identify(&[mods_dir.join("2.jar")])
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(1, identified.len());
+        assert_eq!(2, identified[0].1.id);
+    }
+
+    #[test]
+    fn test_install_modpack_files() {
+        use std::io::Write;
+
+        let fixture = crate::fixture::Fixture::new();
+        let address = fixture.address.clone();
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v1/mods".to_string(),
+            br#"{"data": [
+                {"id": 2, "gameId": 432, "name": "JEI", "slug": "jei", "summary": "", "downloadCount": 1, "classId": 6, "links": {"websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/jei"}},
+                {"id": 3, "gameId": 432, "name": "Pack", "slug": "pack", "summary": "", "downloadCount": 1, "classId": 12, "links": {"websiteUrl": "https://www.curseforge.com/minecraft/texture-packs/pack"}},
+                {"id": 4, "gameId": 432, "name": "Restricted", "slug": "restricted", "summary": "", "downloadCount": 1, "classId": 6, "links": {"websiteUrl": "https://www.curseforge.com/minecraft/mc-mods/restricted/"}}
+            ]}"#
+            .to_vec(),
+        );
+        routes.insert(
+            "/v1/mods/files".to_string(),
+            format!(
+                r#"{{"data": [{}, {}, {}]}}"#,
+                file(20, 2, Some(format!("{}/files/2.jar", address)), b"jei", ""),
+                file(30, 3, Some(format!("{}/files/3.jar", address)), b"pack", ""),
+                file(40, 4, None, b"restricted", "")
+            )
+            .into_bytes(),
+        );
+        routes.insert("/files/2.jar".to_string(), b"jei".to_vec());
+        routes.insert("/files/3.jar".to_string(), b"pack".to_vec());
+        let curseforge = CurseForge::new(&fixture.serve(routes), "key");
+
+        let root = std::env::temp_dir().join("mcl-download-test-curseforge-modpack");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        let manifest = r#"{
+            "minecraft": {"version": "1.20.1", "modLoaders": [{"id": "forge-47.2.0", "primary": true}]},
+            "manifestType": "minecraftModpack",
+            "manifestVersion": 1,
+            "name": "Example Pack",
+            "files": [
+                {"projectID": 2, "fileID": 20, "required": true},
+                {"projectID": 3, "fileID": 30},
+                {"projectID": 4, "fileID": 40, "required": true},
+                {"projectID": 5, "fileID": 50, "required": false}
+            ],
+            "overrides": "overrides"
+        }"#;
+        let pack = root.join("pack.zip");
+        let mut writer = zip::ZipWriter::new(std::fs::File::create(&pack).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("manifest.json", options).unwrap();
+        writer.write_all(manifest.as_bytes()).unwrap();
+        writer
+            .start_file("overrides/config/jei.toml", options)
+            .unwrap();
+        writer.write_all(b"enabled = true").unwrap();
+        writer.finish().unwrap();
+
+        let manifest = read_manifest(&pack).unwrap();
+        let (loader, version) = manifest_loader(&manifest).unwrap();
+        assert_eq!("forge", loader.name());
+        assert_eq!("47.2.0", version);
+
+        let instance_dir = root.join("instance");
+        let blocked = curseforge
+            .install_modpack_files(&pack, &manifest, &instance_dir)
+            .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert!(instance_dir.join("mods/2.jar").exists());
+        // 省略 required 的文件视为必需
+        assert!(instance_dir.join("resourcepacks/3.jar").exists());
+        assert!(instance_dir.join("config/jei.toml").exists());
+        assert_eq!(1, blocked.len());
+        assert_eq!(40, blocked[0].file_id);
+        assert_eq!(instance_dir.join("mods/4.jar"), blocked[0].path);
+        assert_eq!(
+            Some("https://www.curseforge.com/minecraft/mc-mods/restricted/files/40"),
+            blocked[0].url.as_deref()
+        );
+    }
+}
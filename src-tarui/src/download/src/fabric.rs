@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use model::{fabric::LoaderVersion, version::PartialVersion};
+use parse::Parse;
+
+use crate::{get, Download};
+
+pub const FABRIC_META: &str = "https://meta.fabricmc.net";
+
+// Fabric meta 接口，base_url 可替换为本地服务用于测试
+pub struct FabricMeta {
+    pub base_url: String,
+}
+
+impl Default for FabricMeta {
+    fn default() -> Self {
+        Self::new(FABRIC_META)
+    }
+}
+
+impl FabricMeta {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // 获取与游戏版本兼容的加载器版本列表
+    pub fn loader_versions(
+        &self,
+        game_version: &str,
+    ) -> Result<Vec<LoaderVersion>, Box<dyn std::error::Error>> {
+        let url = format!("{}/v2/versions/loader/{}", self.base_url, game_version);
+        let text = get(&url)?.error_for_status()?.text()?;
+        Ok(Vec::<LoaderVersion>::parse(&text)?)
+    }
+
+    fn profile_text(
+        &self,
+        game_version: &str,
+        loader_version: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/v2/versions/loader/{}/{}/profile/json",
+            self.base_url, game_version, loader_version
+        );
+        Ok(get(&url)?.error_for_status()?.text()?)
+    }
+
+    // 安装加载器：写入继承原版的版本配置并下载其 library，返回新版本的 id
+    // 原版本体需要另外安装
+    pub fn install(
+        &self,
+        game_dir: &Path,
+        game_version: &str,
+        loader_version: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        println!("安装 Fabric {}", loader_version);
+        install_profile(game_dir, &self.profile_text(game_version, loader_version)?)
+    }
+}
+
+// Fabric 与 Quilt 的 profile 结构相同，写入版本配置并下载 library
+pub(crate) fn install_profile(
+    game_dir: &Path,
+    text: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let profile = PartialVersion::parse(text)?;
+
+    let version_dir = &game_dir.join("versions").join(&profile.id);
+
+    if !version_dir.exists() {
+        std::fs::create_dir_all(version_dir)?;
+    }
+
+    std::fs::write(version_dir.join(format!("{}.json", profile.id)), text)?;
+
+    profile.libraries.download(game_dir)?;
+
+    Ok(profile.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_install() {
+        let fixture = crate::fixture::Fixture::new();
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v2/versions/loader/1.21".to_string(),
+            br#"[{"loader": {"separator": ".", "build": 11, "maven": "net.fabricmc:fabric-loader:0.15.11", "version": "0.15.11", "stable": true}, "intermediary": {"maven": "net.fabricmc:intermediary:1.21", "version": "1.21", "stable": true}}]"#.to_vec(),
+        );
+        routes.insert(
+            "/v2/versions/loader/1.21/0.15.11/profile/json".to_string(),
+            format!(
+                r#"{{"id": "fabric-loader-0.15.11-1.21", "inheritsFrom": "1.21", "releaseTime": "2024-06-13T08:24:03+00:00", "time": "2024-06-13T08:24:03+00:00", "type": "release", "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient", "libraries": [{{"name": "net.fabricmc:fabric-loader:0.15.11", "url": "{}/maven/"}}]}}"#,
+                fixture.address
+            )
+            .into_bytes(),
+        );
+        routes.insert(
+            "/maven/net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar".to_string(),
+            b"fabric-loader".to_vec(),
+        );
+        let address = fixture.serve(routes);
+
+        let meta = FabricMeta::new(&address);
+
+        let versions = meta
+            .loader_versions("1.21")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!("0.15.11", versions[0].loader.version);
+
+        let game_dir = &std::env::temp_dir().join("mcl-download-test-fabric");
+        let _ = std::fs::remove_dir_all(game_dir);
+
+        let id = meta
+            .install(game_dir, "1.21", "0.15.11")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!("fabric-loader-0.15.11-1.21", id);
+        assert!(game_dir
+            .join("versions")
+            .join(&id)
+            .join(format!("{}.json", id))
+            .exists());
+        assert_eq!(
+            b"fabric-loader".to_vec(),
+            std::fs::read(
+                game_dir
+                    .join("libraries")
+                    .join("net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar")
+            )
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,78 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread,
+};
+
+// 测试用的本地 HTTP 服务，按请求路径返回预先准备好的内容
+pub struct Fixture {
+    listener: TcpListener,
+    pub address: String,
+}
+
+impl Fixture {
+    // 先绑定端口，便于在准备内容时引用服务地址
+    pub fn new() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = format!("http://{}", listener.local_addr().unwrap());
+        Self { listener, address }
+    }
+
+    pub fn serve(self, routes: HashMap<String, Vec<u8>>) -> String {
+        let listener = self.listener;
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+
+                // 读完请求头和请求体
+                let mut content_length = 0;
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok() && line != "\r\n" && !line.is_empty() {
+                    if let Some((key, value)) = line.split_once(':') {
+                        if key.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                    }
+                    line.clear();
+                }
+                let mut body = vec![0; content_length];
+                let _ = reader.read_exact(&mut body);
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+                // 找不到完整路径时忽略查询参数再匹配一次
+                let route = routes
+                    .get(path)
+                    .or_else(|| routes.get(path.split('?').next().unwrap_or(path)));
+                let response = match route {
+                    Some(body) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend(body);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+
+                let _ = stream.write_all(&response);
+            }
+        });
+
+        self.address
+    }
+}
@@ -0,0 +1,480 @@
+use std::{
+    collections::HashMap,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use model::{forge::InstallProfile, library::maven_path, version::PartialVersion};
+use parse::Parse;
+
+use crate::{get, Download};
+
+pub const FORGE_MAVEN: &str = "https://maven.minecraftforge.net";
+pub const NEOFORGE_MAVEN: &str = "https://maven.neoforged.net/releases";
+
+// Forge 与 NeoForge 都通过安装器安装，区别只在 maven 仓库和坐标
+pub struct ForgeMaven {
+    pub base_url: String,
+    pub group: String,
+    pub artifact: String,
+}
+
+impl ForgeMaven {
+    pub fn new(base_url: &str, group: &str, artifact: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            group: group.to_string(),
+            artifact: artifact.to_string(),
+        }
+    }
+
+    pub fn forge() -> Self {
+        Self::new(FORGE_MAVEN, "net.minecraftforge", "forge")
+    }
+
+    pub fn neoforge() -> Self {
+        Self::new(NEOFORGE_MAVEN, "net.neoforged", "neoforge")
+    }
+
+    fn artifact_url(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            self.base_url,
+            self.group.replace('.', "/"),
+            self.artifact
+        )
+    }
+
+    // Forge 的 maven 版本号带有游戏版本前缀，例如 1.21-51.0.33
+    fn maven_version(&self, game_version: &str, loader_version: &str) -> String {
+        if self.artifact == "forge" {
+            format!("{}-{}", game_version, loader_version)
+        } else {
+            loader_version.to_string()
+        }
+    }
+
+    // 获取与游戏版本兼容的加载器版本列表，最新的在前
+    pub fn loader_versions(
+        &self,
+        game_version: &str,
+    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+        let url = format!("{}/maven-metadata.xml", self.artifact_url());
+        let text = get(&url)?.error_for_status()?.text()?;
+
+        let prefix = format!("{}-", game_version);
+        let mut versions: Vec<String> = text
+            .split("<version>")
+            .skip(1)
+            .filter_map(|s| s.split("</version>").next())
+            .filter_map(|version| {
+                if self.artifact == "forge" {
+                    version.strip_prefix(&prefix).map(str::to_string)
+                } else if neoforge_game_version(version) == game_version {
+                    Some(version.to_string())
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        versions.reverse();
+        Ok(versions)
+    }
+
+    // 下载安装器并安装，原版本体需要另外安装
+    pub fn install(
+        &self,
+        game_dir: &Path,
+        game_version: &str,
+        loader_version: &str,
+        java: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let version = self.maven_version(game_version, loader_version);
+        let file = format!("{}-{}-installer.jar", self.artifact, version);
+        let url = format!("{}/{}/{}", self.artifact_url(), version, file);
+
+        println!("下载安装器: {}", url);
+
+        let installer = std::env::temp_dir().join(&file);
+        std::fs::write(&installer, get(&url)?.error_for_status()?.bytes()?)?;
+
+        let result = install_installer(game_dir, &installer, java);
+        let _ = std::fs::remove_file(&installer);
+        result
+    }
+}
+
+// NeoForge 的版本号由游戏版本得出，例如 21.0.167 -> 1.21，20.4.237 -> 1.20.4
+pub fn neoforge_game_version(version: &str) -> String {
+    let mut parts = version.split('.');
+    let major = parts.next().unwrap_or_default();
+    match parts.next() {
+        Some("0") | None => format!("1.{}", major),
+        Some(minor) => format!("1.{}.{}", major, minor),
+    }
+}
+
+// 不运行图形安装器，直接按照 install_profile.json 完成安装，返回新版本的 id
+pub fn install_installer(
+    game_dir: &Path,
+    installer: &Path,
+    java: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(installer)?)?;
+
+    let profile = InstallProfile::parse(&read_entry(&mut archive, "install_profile.json")?)
+        .map_err(|err| format!("Unsupported installer: {}", err))?;
+
+    // 处理器需要原版本体
+    let minecraft_jar = game_dir
+        .join("versions")
+        .join(&profile.minecraft)
+        .join(format!("{}.jar", profile.minecraft));
+    if !minecraft_jar.is_file() {
+        return Err(format!(
+            "Minecraft {} is not installed, install the vanilla version first",
+            profile.minecraft
+        )
+        .into());
+    }
+
+    let version_text = read_entry(&mut archive, profile.json.trim_start_matches('/'))?;
+    let version = PartialVersion::parse(&version_text)?;
+
+    println!("安装 {} -> {}", profile.version, version.id);
+
+    let version_dir = &game_dir.join("versions").join(&version.id);
+
+    if !version_dir.exists() {
+        std::fs::create_dir_all(version_dir)?;
+    }
+
+    std::fs::write(
+        version_dir.join(format!("{}.json", version.id)),
+        &version_text,
+    )?;
+
+    // 安装器自带的 maven 仓库直接解压到 libraries
+    let libraries_dir = &game_dir.join("libraries");
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(name) = entry.enclosed_name() else {
+            continue;
+        };
+        let Ok(name) = name.strip_prefix("maven") else {
+            continue;
+        };
+
+        if entry.is_file() {
+            let path = libraries_dir.join(name);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::io::copy(&mut entry, &mut std::fs::File::create(&path)?)?;
+        }
+    }
+
+    profile.libraries.download(game_dir)?;
+    version.libraries.download(game_dir)?;
+
+    // 安装器中的数据文件解压到临时目录，处理器执行完后删除
+    let data_dir = &std::env::temp_dir().join(format!("mcl-installer-{}", version.id));
+
+    let mut data = HashMap::new();
+    data.insert("SIDE".to_string(), "client".to_string());
+    data.insert(
+        "MINECRAFT_JAR".to_string(),
+        minecraft_jar.to_string_lossy().to_string(),
+    );
+    data.insert("MINECRAFT_VERSION".to_string(), profile.minecraft.clone());
+    data.insert("ROOT".to_string(), game_dir.to_string_lossy().to_string());
+    data.insert(
+        "INSTALLER".to_string(),
+        installer.to_string_lossy().to_string(),
+    );
+    data.insert(
+        "LIBRARY_DIR".to_string(),
+        libraries_dir.to_string_lossy().to_string(),
+    );
+
+    for (key, entry) in &profile.data {
+        let value = match entry.client.strip_prefix('/') {
+            Some(name) => {
+                let path = data_dir.join(name);
+                std::fs::create_dir_all(path.parent().unwrap())?;
+                std::fs::write(&path, read_entry_bytes(&mut archive, name)?)?;
+                path.to_string_lossy().to_string()
+            }
+            None => entry.client.clone(),
+        };
+        data.insert(key.clone(), value);
+    }
+
+    let result = run_processors(&profile, &data, libraries_dir, java);
+    let _ = std::fs::remove_dir_all(data_dir);
+    result?;
+
+    Ok(version.id)
+}
+
+fn run_processors(
+    profile: &InstallProfile,
+    data: &HashMap<String, String>,
+    libraries_dir: &Path,
+    java: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let separator = if cfg!(windows) { ";" } else { ":" };
+
+    for processor in &profile.processors {
+        if let Some(sides) = &processor.sides {
+            if !sides.iter().any(|side| side == "client") {
+                continue;
+            }
+        }
+
+        let outputs: Vec<(String, String)> = processor
+            .outputs
+            .iter()
+            .map(|(path, sha1)| {
+                (
+                    resolve_argument(path, data, libraries_dir),
+                    resolve_argument(sha1, data, libraries_dir),
+                )
+            })
+            .collect();
+
+        // 产物已存在且校验通过时跳过
+        if !outputs.is_empty()
+            && outputs.iter().all(|(path, sha1)| {
+                Path::new(path).exists() && crate::sha1(path).is_ok_and(|hash| hash.eq(sha1))
+            })
+        {
+            continue;
+        }
+
+        let jar = library_path(&processor.jar, libraries_dir)?;
+        let main_class = main_class(&jar)?;
+
+        let mut classpath = vec![jar.to_string_lossy().to_string()];
+        for library in &processor.classpath {
+            classpath.push(
+                library_path(library, libraries_dir)?
+                    .to_string_lossy()
+                    .to_string(),
+            );
+        }
+
+        let args: Vec<String> = processor
+            .args
+            .iter()
+            .map(|arg| resolve_argument(arg, data, libraries_dir))
+            .collect();
+
+        println!("执行处理器: {}", processor.jar);
+
+        let status = std::process::Command::new(java)
+            .arg("-cp")
+            .arg(classpath.join(separator))
+            .arg(main_class)
+            .args(args)
+            .status()?;
+
+        if !status.success() {
+            return Err(format!(
+                "Processor {} exited with code: {:?}",
+                processor.jar,
+                status.code()
+            )
+            .into());
+        }
+
+        for (path, sha1) in outputs {
+            let hash = crate::sha1(&path)?;
+            if !hash.eq(&sha1) {
+                return Err(format!(
+                    "Processor output {} sha1 mismatch: expected {}, got {}",
+                    path, sha1, hash
+                )
+                .into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// 参数可以引用 data 中的值：{KEY}；data 的值和参数本身可以是 [maven 坐标] 或 '字面量'
+pub fn resolve_argument(arg: &str, data: &HashMap<String, String>, libraries_dir: &Path) -> String {
+    let value = match arg
+        .strip_prefix('{')
+        .and_then(|arg| arg.strip_suffix('}'))
+        .and_then(|key| data.get(key))
+    {
+        Some(value) => value.as_str(),
+        None => arg,
+    };
+
+    if let Some(coordinate) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        if let Some(path) = maven_path(coordinate) {
+            return libraries_dir.join(path).to_string_lossy().to_string();
+        }
+    }
+
+    if let Some(literal) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        return literal.to_string();
+    }
+
+    // 处理 {ROOT}/xxx 这类内嵌引用
+    let mut value = value.to_string();
+    for (key, replacement) in data {
+        value = value.replace(&format!("{{{}}}", key), replacement);
+    }
+    value
+}
+
+fn library_path(name: &str, libraries_dir: &Path) -> Result<PathBuf, String> {
+    maven_path(name)
+        .map(|path| libraries_dir.join(path))
+        .ok_or(format!("Invalid library name: {}", name))
+}
+
+// 从 jar 的 META-INF/MANIFEST.MF 中读取 Main-Class
+fn main_class(jar: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(jar)?)?;
+    let manifest = read_entry(&mut archive, "META-INF/MANIFEST.MF")?;
+
+    manifest
+        .lines()
+        .find_map(|line| line.strip_prefix("Main-Class:"))
+        .map(|main_class| main_class.trim().to_string())
+        .ok_or(format!("No Main-Class in {}", jar.display()).into())
+}
+
+fn read_entry_bytes(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    name: &str,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut entry = archive.by_name(name)?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_entry(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    name: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(String::from_utf8(read_entry_bytes(archive, name)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_neoforge_game_version() {
+        assert_eq!("1.21", neoforge_game_version("21.0.167"));
+        assert_eq!("1.21.1", neoforge_game_version("21.1.5"));
+        assert_eq!("1.20.4", neoforge_game_version("20.4.237"));
+    }
+
+    #[test]
+    fn test_resolve_argument() {
+        let libraries_dir = Path::new("/libraries");
+        let mut data = HashMap::new();
+        data.insert("SIDE".to_string(), "client".to_string());
+        data.insert("ROOT".to_string(), "/game".to_string());
+        data.insert(
+            "MC_SRG".to_string(),
+            "[net.minecraft:client:1.21-20240613.152323:srg]".to_string(),
+        );
+        data.insert("PATCHED_SHA".to_string(), "'abc'".to_string());
+
+        assert_eq!("client", resolve_argument("{SIDE}", &data, libraries_dir));
+        assert_eq!(
+            "abc",
+            resolve_argument("{PATCHED_SHA}", &data, libraries_dir)
+        );
+        assert_eq!(
+            Path::new("/libraries/net/minecraft/client/1.21-20240613.152323/client-1.21-20240613.152323-srg.jar")
+                .to_string_lossy(),
+            resolve_argument("{MC_SRG}", &data, libraries_dir)
+        );
+        assert_eq!(
+            "/game/mods",
+            resolve_argument("{ROOT}/mods", &data, libraries_dir)
+        );
+        assert_eq!("--task", resolve_argument("--task", &data, libraries_dir));
+    }
+
+    #[test]
+    fn test_install_installer() {
+        let dir = &std::env::temp_dir().join("mcl-download-test-forge");
+        let _ = std::fs::remove_dir_all(dir);
+        let game_dir = &dir.join(".minecraft");
+        std::fs::create_dir_all(game_dir).unwrap();
+
+        // 产物已经存在且校验通过，处理器会被跳过
+        let output = dir.join("patched.jar");
+        std::fs::write(&output, b"patched").unwrap();
+        let output_sha1 = crate::sha1(&output).unwrap();
+
+        let installer = dir.join("forge-1.21-51.0.33-installer.jar");
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&installer).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+
+        zip.start_file("install_profile.json", options).unwrap();
+        zip.write_all(
+            format!(
+                r#"{{"spec": 1, "profile": "forge", "version": "1.21-forge-51.0.33", "minecraft": "1.21", "json": "/version.json", "data": {{"BINPATCH": {{"client": "/data/client.lzma", "server": "/data/server.lzma"}}, "PATCHED": {{"client": "'{}'", "server": "''"}}, "PATCHED_SHA": {{"client": "'{}'", "server": "''"}}}}, "processors": [{{"sides": ["client"], "jar": "net.minecraftforge:binarypatcher:1.1.1:fatjar", "args": ["--apply", "{{BINPATCH}}", "--output", "{{PATCHED}}"], "outputs": {{"{{PATCHED}}": "{{PATCHED_SHA}}"}}}}, {{"sides": ["server"], "jar": "net.minecraftforge:installertools:1.4.1:fatjar", "args": []}}], "libraries": []}}"#,
+                output.to_string_lossy().replace('\\', "\\\\"),
+                output_sha1
+            )
+            .as_bytes(),
+        )
+        .unwrap();
+
+        zip.start_file("version.json", options).unwrap();
+        zip.write_all(br#"{"id": "1.21-forge-51.0.33", "inheritsFrom": "1.21", "type": "release", "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher", "libraries": [{"name": "net.minecraftforge:forge:1.21-51.0.33:universal", "downloads": {"artifact": {"path": "net/minecraftforge/forge/1.21-51.0.33/forge-1.21-51.0.33-universal.jar", "url": "", "sha1": "", "size": 0}}}]}"#).unwrap();
+
+        zip.start_file("data/client.lzma", options).unwrap();
+        zip.write_all(b"binpatch").unwrap();
+
+        zip.start_file(
+            "maven/net/minecraftforge/forge/1.21-51.0.33/forge-1.21-51.0.33-universal.jar",
+            options,
+        )
+        .unwrap();
+        zip.write_all(b"universal").unwrap();
+        zip.finish().unwrap();
+
+        // 原版本体不存在时不写入任何文件
+        assert!(install_installer(game_dir, &installer, "java").is_err());
+        assert!(!game_dir.join("versions/1.21-forge-51.0.33").exists());
+
+        let client_dir = game_dir.join("versions/1.21");
+        std::fs::create_dir_all(&client_dir).unwrap();
+        std::fs::write(client_dir.join("1.21.jar"), b"client").unwrap();
+        let id = install_installer(game_dir, &installer, "java")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!("1.21-forge-51.0.33", id);
+        assert!(game_dir
+            .join("versions")
+            .join(&id)
+            .join(format!("{}.json", id))
+            .exists());
+        assert_eq!(
+            b"universal".to_vec(),
+            std::fs::read(game_dir.join(
+                "libraries/net/minecraftforge/forge/1.21-51.0.33/forge-1.21-51.0.33-universal.jar"
+            ))
+            .unwrap()
+        );
+        assert!(!std::env::temp_dir()
+            .join(format!("mcl-installer-{}", id))
+            .exists());
+    }
+}
@@ -1,9 +1,21 @@
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
 use sha1::{Digest, Sha1};
+use sha2::Sha512;
 
 pub mod asset;
+pub mod curseforge;
+pub mod fabric;
+pub mod forge;
+#[cfg(test)]
+mod fixture;
 pub mod library;
+pub mod loader;
+pub mod modrinth;
+pub mod mrpack;
+pub mod multimc;
+pub mod optifine;
+pub mod quilt;
 pub mod version;
 
 pub trait Download {
@@ -18,11 +30,47 @@ pub fn get<T: reqwest::IntoUrl>(url: T) -> reqwest::Result<reqwest::blocking::Re
         .send()
 }
 
+// Modrinth、CurseForge 等接口要求请求带上可识别的 User-Agent
+const USER_AGENT: &str = "rmcl/0.1.0";
+
+pub(crate) fn client() -> reqwest::Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .timeout(None)
+        .user_agent(USER_AGENT)
+        .build()
+}
+
 pub fn sha1<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error> {
     let mut hasher = Sha1::new();
     file_hashing::get_hash_file(path, &mut hasher)
 }
 
+pub fn sha512<P: AsRef<Path>>(path: P) -> Result<String, std::io::Error> {
+    let mut hasher = Sha512::new();
+    file_hashing::get_hash_file(path, &mut hasher)
+}
+
 pub trait LibaryAllowed {
     fn allowed(&self) -> bool;
 }
+
+// 拼接压缩包或整合包中给出的相对路径，拒绝绝对路径和 .. 以免写到 root 之外
+pub fn safe_join(root: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    let mut path = root.to_path_buf();
+    let mut empty = true;
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => {
+                path.push(part);
+                empty = false;
+            }
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if empty {
+        return None;
+    }
+    Some(path)
+}
@@ -8,17 +8,21 @@ impl LibaryAllowed for library::Library {
     fn allowed(&self) -> bool {
         let mut allowed = true;
 
-        if self.rules.is_some() {
-            for rule in self.rules.as_ref().unwrap() {
-                if rule.os.name == "osx" && !cfg!(target_os = "macos") {
-                    allowed = false;
-                    break;
-                } else if rule.os.name == "linux" && !cfg!(target_os = "linux") {
-                    allowed = false;
-                    break;
-                } else if rule.os.name == "windows" && !cfg!(target_os = "windows") {
-                    allowed = false;
-                    break;
+        // 以最后一条匹配的规则为准，没有 os 的规则对所有系统生效
+        if let Some(rules) = &self.rules {
+            allowed = rules.is_empty();
+            for rule in rules {
+                let matched = match &rule.os {
+                    Some(os) => match os.name.as_str() {
+                        "osx" => cfg!(target_os = "macos"),
+                        "linux" => cfg!(target_os = "linux"),
+                        "windows" => cfg!(target_os = "windows"),
+                        _ => false,
+                    },
+                    None => true,
+                };
+                if matched {
+                    allowed = rule.action == "allow";
                 }
             }
         }
@@ -51,6 +55,11 @@ impl Download for Libraries {
                 continue;
             }
 
+            // url 为空的 library 由 Forge 安装器自带或由处理器生成
+            if library.downloads.artifact.url.is_empty() {
+                continue;
+            }
+
             let library_file = &library.downloads.artifact.path;
 
             let library_path = &libraries_dir.join(library_file);
@@ -60,7 +69,10 @@ impl Download for Libraries {
             }
 
             if library_path.exists() {
-                if crate::sha1(library_path)?.eq(&library.downloads.artifact.sha1) {
+                // 加载器的 library 可能不提供 sha1，已存在即视为完整
+                if library.downloads.artifact.sha1.is_empty()
+                    || crate::sha1(library_path)?.eq(&library.downloads.artifact.sha1)
+                {
                     continue;
                 } else {
                     std::fs::remove_file(library_path)?;
@@ -84,6 +96,26 @@ mod tests {
     use super::*;
     use model::version::Version;
 
+    #[test]
+    fn test_allowed() {
+        let library_with_rules = |rules: &str| {
+            serde_json::from_str::<library::Library>(&format!(
+                r#"{{"name": "org.lwjgl.lwjgl:lwjgl:2.9.4", "downloads": {{"artifact": {{"path": "lwjgl.jar", "sha1": "", "size": 0, "url": ""}}}}, "rules": {}}}"#,
+                rules
+            ))
+            .unwrap()
+        };
+
+        // 1.12.2 等旧版本的 lwjgl：除 macOS 外都允许
+        let except_osx = library_with_rules(
+            r#"[{"action": "allow"}, {"action": "disallow", "os": {"name": "osx"}}]"#,
+        );
+        assert_eq!(!cfg!(target_os = "macos"), except_osx.allowed());
+        let only_osx = library_with_rules(r#"[{"action": "allow", "os": {"name": "osx"}}]"#);
+        assert_eq!(cfg!(target_os = "macos"), only_osx.allowed());
+        assert!(library_with_rules("[]").allowed());
+    }
+
     #[test]
     fn test_download() {
         let game = reqwest::blocking::get("https://piston-meta.mojang.com/v1/packages/177e49d3233cb6eac42f0495c0a48e719870c2ae/1.21.json")
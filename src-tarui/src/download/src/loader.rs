@@ -0,0 +1,198 @@
+use std::{path::Path, str::FromStr};
+
+use model::version::Version;
+
+use crate::{fabric::FabricMeta, forge::ForgeMaven, quilt::QuiltMeta};
+
+// 安装原版时可选的加载器
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Loader {
+    Fabric,
+    Quilt,
+    Forge,
+    NeoForge,
+}
+
+impl FromStr for Loader {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "fabric" => Ok(Loader::Fabric),
+            "quilt" => Ok(Loader::Quilt),
+            "forge" => Ok(Loader::Forge),
+            "neoforge" => Ok(Loader::NeoForge),
+            _ => Err(format!("Unknown loader: {}", value)),
+        }
+    }
+}
+
+// 可供安装的加载器版本
+pub struct AvailableVersion {
+    pub version: String,
+    pub stable: bool,
+}
+
+impl Loader {
+    // 小写名称，与 FromStr 对应
+    pub fn name(&self) -> &'static str {
+        match self {
+            Loader::Fabric => "fabric",
+            Loader::Quilt => "quilt",
+            Loader::Forge => "forge",
+            Loader::NeoForge => "neoforge",
+        }
+    }
+
+    // 从合并后的版本中识别加载器及其版本
+    pub fn detect(version: &Version) -> Option<(Loader, String)> {
+        for library in &version.libraries {
+            let mut parts = library.name.split(':');
+            let (Some(group), Some(artifact), Some(loader_version)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+
+            let loader = match (group, artifact) {
+                ("net.fabricmc", "fabric-loader") => Loader::Fabric,
+                ("org.quiltmc", "quilt-loader") => Loader::Quilt,
+                ("net.neoforged", "neoforge") => Loader::NeoForge,
+                ("net.minecraftforge", "forge") => Loader::Forge,
+                _ => continue,
+            };
+
+            // Forge 的版本号带有游戏版本前缀，例如 1.21-51.0.33
+            let loader_version = match loader {
+                Loader::Forge => loader_version
+                    .split_once('-')
+                    .map_or(loader_version, |(_, forge)| forge),
+                _ => loader_version,
+            };
+
+            return Some((loader, loader_version.to_string()));
+        }
+
+        None
+    }
+
+    pub fn loader_versions(
+        &self,
+        game_version: &str,
+    ) -> Result<Vec<AvailableVersion>, Box<dyn std::error::Error>> {
+        let versions = match self {
+            Loader::Fabric => FabricMeta::default().loader_versions(game_version)?,
+            Loader::Quilt => QuiltMeta::default().loader_versions(game_version)?,
+            Loader::Forge => {
+                return Ok(ForgeMaven::forge()
+                    .loader_versions(game_version)?
+                    .into_iter()
+                    .map(|version| AvailableVersion {
+                        version,
+                        stable: true,
+                    })
+                    .collect())
+            }
+            Loader::NeoForge => {
+                return Ok(ForgeMaven::neoforge()
+                    .loader_versions(game_version)?
+                    .into_iter()
+                    .map(|version| AvailableVersion {
+                        stable: !version.contains("beta"),
+                        version,
+                    })
+                    .collect())
+            }
+        };
+
+        Ok(versions
+            .into_iter()
+            .map(|v| AvailableVersion {
+                version: v.loader.version,
+                stable: v.loader.stable.unwrap_or(true),
+            })
+            .collect())
+    }
+
+    // 安装加载器，未指定版本时优先选择最新的稳定版，java 用于运行 Forge 与 NeoForge 安装器的处理器
+    pub fn install(
+        &self,
+        game_dir: &Path,
+        game_version: &str,
+        loader_version: Option<&str>,
+        java: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let loader_version = match loader_version {
+            Some(loader_version) => loader_version.to_string(),
+            None => {
+                let versions = self.loader_versions(game_version)?;
+                versions
+                    .iter()
+                    .find(|v| v.stable)
+                    .or(versions.first())
+                    .map(|v| v.version.clone())
+                    .ok_or(format!("No {:?} loader for {}", self, game_version))?
+            }
+        };
+
+        match self {
+            Loader::Fabric => {
+                FabricMeta::default().install(game_dir, game_version, &loader_version)
+            }
+            Loader::Quilt => QuiltMeta::default().install(game_dir, game_version, &loader_version),
+            Loader::Forge | Loader::NeoForge => {
+                // 处理器需要原版本体，未安装时先安装原版
+                let client = game_dir
+                    .join("versions")
+                    .join(game_version)
+                    .join(format!("{}.jar", game_version));
+                if !client.is_file() {
+                    crate::version::install(game_dir, game_version)?;
+                }
+                let maven = match self {
+                    Loader::Forge => ForgeMaven::forge(),
+                    _ => ForgeMaven::neoforge(),
+                };
+                maven.install(game_dir, game_version, &loader_version, java)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parse::Parse;
+
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let version = |libraries: &str| {
+            Version::parse(&format!(
+                r#"{{"assetIndex": {{"id": "17", "sha1": "", "size": 0, "totalSize": 0, "url": ""}}, "downloads": {{"client": {{"sha1": "", "size": 0, "url": ""}}}}, "id": "test", "libraries": [{}], "mainClass": "", "releaseTime": "", "time": "", "type": "release"}}"#,
+                libraries
+            ))
+            .unwrap_or_else(|err| panic!("{:?}", err))
+        };
+
+        assert_eq!(None, Loader::detect(&version("")));
+        assert_eq!(
+            Some((Loader::Fabric, "0.15.11".to_string())),
+            Loader::detect(&version(
+                r#"{"name": "org.ow2.asm:asm:9.6"}, {"name": "net.fabricmc:fabric-loader:0.15.11"}"#
+            ))
+        );
+        assert_eq!(
+            Some((Loader::Forge, "51.0.33".to_string())),
+            Loader::detect(&version(
+                r#"{"name": "net.minecraftforge:forge:1.21-51.0.33:universal"}"#
+            ))
+        );
+        assert_eq!(
+            Some((Loader::NeoForge, "21.0.167".to_string())),
+            Loader::detect(&version(
+                r#"{"name": "net.neoforged:neoforge:21.0.167:universal"}"#
+            ))
+        );
+    }
+}
@@ -0,0 +1,627 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use model::modrinth::{Project, ProjectVersion, SearchResponse, VersionFile};
+use parse::Parse;
+use serde::Serialize;
+use serde_json::json;
+
+pub const MODRINTH_API: &str = "https://api.modrinth.com";
+
+// Modrinth v2 接口，base_url 可替换为本地服务用于测试
+pub struct Modrinth {
+    pub base_url: String,
+}
+
+impl Default for Modrinth {
+    fn default() -> Self {
+        Self::new(MODRINTH_API)
+    }
+}
+
+// 可更新的 mod，latest.changelog 为新版本的更新日志
+#[derive(Serialize)]
+pub struct ModUpdate {
+    pub path: PathBuf,
+    pub current: ProjectVersion,
+    pub latest: ProjectVersion,
+}
+
+// 搜索条件，为空的条件不参与过滤
+#[derive(Default)]
+pub struct SearchQuery {
+    pub query: String,
+    pub game_version: Option<String>,
+    pub loader: Option<String>,
+    pub categories: Vec<String>,
+    // mod / modpack / resourcepack / shader
+    pub project_type: Option<String>,
+    pub offset: u32,
+    pub limit: u32,
+}
+
+impl SearchQuery {
+    // facets 外层为与，内层为或
+    fn facets(&self) -> String {
+        let mut facets = Vec::new();
+        if let Some(project_type) = &self.project_type {
+            facets.push(vec![format!("project_type:{}", project_type)]);
+        }
+        if let Some(game_version) = &self.game_version {
+            facets.push(vec![format!("versions:{}", game_version)]);
+        }
+        if let Some(loader) = &self.loader {
+            facets.push(vec![format!("categories:{}", loader)]);
+        }
+        for category in &self.categories {
+            facets.push(vec![format!("categories:{}", category)]);
+        }
+        serde_json::to_string(&facets).unwrap_or_default()
+    }
+}
+
+impl Modrinth {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    fn get(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!("{}/v2{}", self.base_url, path);
+        Ok(crate::client()?
+            .get(&url)
+            .query(query)
+            .send()?
+            .error_for_status()?
+            .text()?)
+    }
+
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<SearchResponse, Box<dyn std::error::Error>> {
+        let mut params = vec![
+            ("query", query.query.clone()),
+            ("offset", query.offset.to_string()),
+            ("limit", query.limit.max(1).to_string()),
+        ];
+        let facets = query.facets();
+        if facets != "[]" {
+            params.push(("facets", facets));
+        }
+        Ok(SearchResponse::parse(&self.get("/search", &params)?)?)
+    }
+
+    // id 或 slug 都可以
+    pub fn project(&self, id: &str) -> Result<Project, Box<dyn std::error::Error>> {
+        Ok(Project::parse(
+            &self.get(&format!("/project/{}", id), &[])?,
+        )?)
+    }
+
+    // 项目的版本列表，最新的在前
+    pub fn versions(
+        &self,
+        project: &str,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<ProjectVersion>, Box<dyn std::error::Error>> {
+        let mut params = Vec::new();
+        if let Some(game_version) = game_version {
+            params.push(("game_versions", serde_json::to_string(&[game_version])?));
+        }
+        if let Some(loader) = loader {
+            params.push(("loaders", serde_json::to_string(&[loader])?));
+        }
+        let text = self.get(&format!("/project/{}/version", project), &params)?;
+        Ok(Vec::<ProjectVersion>::parse(&text)?)
+    }
+
+    pub fn version(&self, id: &str) -> Result<ProjectVersion, Box<dyn std::error::Error>> {
+        Ok(ProjectVersion::parse(
+            &self.get(&format!("/version/{}", id), &[])?,
+        )?)
+    }
+
+    // 找出需要一起安装的必需前置，结果中第一个为所选版本本身
+    pub fn resolve(
+        &self,
+        version: ProjectVersion,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<ProjectVersion>, Box<dyn std::error::Error>> {
+        let mut projects = HashSet::new();
+        projects.insert(version.project_id.clone());
+        let mut resolved = vec![version];
+
+        let mut index = 0;
+        while index < resolved.len() {
+            let dependencies = resolved[index].dependencies.clone();
+            index += 1;
+
+            for dependency in dependencies {
+                if dependency.dependency_type != "required" {
+                    continue;
+                }
+                if dependency
+                    .project_id
+                    .as_ref()
+                    .is_some_and(|p| projects.contains(p))
+                {
+                    continue;
+                }
+
+                let version = match (&dependency.version_id, &dependency.project_id) {
+                    (Some(version_id), _) => self.version(version_id)?,
+                    (None, Some(project_id)) => self
+                        .versions(project_id, game_version, loader)?
+                        .into_iter()
+                        .next()
+                        .ok_or(format!(
+                            "No version of {} found for {} {}",
+                            project_id,
+                            loader.unwrap_or("any loader"),
+                            game_version.unwrap_or("any game version")
+                        ))?,
+                    (None, None) => continue,
+                };
+
+                if projects.insert(version.project_id.clone()) {
+                    resolved.push(version);
+                }
+            }
+        }
+
+        Ok(resolved)
+    }
+
+    // 下载文件到目录并校验 sha512，已存在且校验通过时跳过
+    pub fn download_file(
+        &self,
+        file: &VersionFile,
+        dir: &Path,
+    ) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        // 文件名来自服务端，只保留最后一段，避免写到目录之外
+        let filename = Path::new(&file.filename)
+            .file_name()
+            .ok_or(format!("Invalid file name: {}", file.filename))?;
+        let path = dir.join(filename);
+        if path.exists() && crate::sha512(&path)?.eq(&file.hashes.sha512) {
+            return Ok(path);
+        }
+
+        if !dir.exists() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        println!("下载 {}", file.filename);
+        let bytes = crate::client()?
+            .get(&file.url)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+        let part = dir.join(format!("{}.part", filename.to_string_lossy()));
+        std::fs::write(&part, &bytes)?;
+
+        let hash = crate::sha512(&part)?;
+        if !hash.eq(&file.hashes.sha512) {
+            std::fs::remove_file(&part)?;
+            return Err(format!(
+                "File {} sha512 mismatch: expected {}, got {}",
+                file.filename, file.hashes.sha512, hash
+            )
+            .into());
+        }
+
+        std::fs::rename(&part, &path)?;
+        Ok(path)
+    }
+
+    // 安装指定版本及其必需前置到 mods 目录，返回下载的文件
+    pub fn install(
+        &self,
+        mods_dir: &Path,
+        version_id: &str,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let version = self.version(version_id)?;
+        let mut paths = Vec::new();
+        for version in self.resolve(version, game_version, loader)? {
+            let file = version
+                .primary_file()
+                .ok_or(format!("Version: {} has no files", version.id))?;
+            paths.push(self.download_file(file, mods_dir)?);
+        }
+        Ok(paths)
+    }
+
+    fn post(
+        &self,
+        path: &str,
+        body: &serde_json::Value,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!("{}/v2{}", self.base_url, path);
+        Ok(crate::client()?
+            .post(&url)
+            .json(body)
+            .send()?
+            .error_for_status()?
+            .text()?)
+    }
+
+    // 按文件 hash 批量查询对应的版本，algorithm 为 sha1 或 sha512，未收录的 hash 不会出现在结果中
+    pub fn version_files(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+    ) -> Result<HashMap<String, ProjectVersion>, Box<dyn std::error::Error>> {
+        let body = json!({ "hashes": hashes, "algorithm": algorithm });
+        Ok(HashMap::<String, ProjectVersion>::parse(
+            &self.post("/version_files", &body)?,
+        )?)
+    }
+
+    // 按文件 hash 批量查询适用于加载器和游戏版本的最新版本
+    pub fn latest_versions(
+        &self,
+        hashes: &[String],
+        algorithm: &str,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<HashMap<String, ProjectVersion>, Box<dyn std::error::Error>> {
+        let mut body = json!({ "hashes": hashes, "algorithm": algorithm });
+        if let Some(game_version) = game_version {
+            body["game_versions"] = json!([game_version]);
+        }
+        if let Some(loader) = loader {
+            body["loaders"] = json!([loader]);
+        }
+        Ok(HashMap::<String, ProjectVersion>::parse(
+            &self.post("/version_files/update", &body)?,
+        )?)
+    }
+
+    // 检查 mod 文件是否有更新，不在 Modrinth 上的文件会被忽略
+    pub fn check_updates(
+        &self,
+        paths: &[PathBuf],
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<Vec<ModUpdate>, Box<dyn std::error::Error>> {
+        let mut hashes = Vec::new();
+        for path in paths {
+            hashes.push(crate::sha1(path)?);
+        }
+        if hashes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut current = self.version_files(&hashes, "sha1")?;
+        let mut latest = self.latest_versions(&hashes, "sha1", game_version, loader)?;
+
+        let mut updates = Vec::new();
+        for (path, hash) in paths.iter().zip(&hashes) {
+            let (Some(current), Some(latest)) = (current.remove(hash), latest.remove(hash)) else {
+                continue;
+            };
+            if current.id != latest.id {
+                updates.push(ModUpdate {
+                    path: path.clone(),
+                    current,
+                    latest,
+                });
+            }
+        }
+        Ok(updates)
+    }
+
+    // 下载更新到 mod 目录下的临时目录，返回需要执行的替换
+    // 被禁用的 mod 更新后仍保持禁用
+    fn stage_update(&self, update: &ModUpdate) -> Result<StagedUpdate, Box<dyn std::error::Error>> {
+        let dir = update
+            .path
+            .parent()
+            .ok_or(format!("Invalid mod path: {}", update.path.display()))?;
+        let file = update
+            .latest
+            .primary_file()
+            .ok_or(format!("Version: {} has no files", update.latest.id))?;
+
+        let staging = dir.join(".update");
+        let downloaded = self.download_file(file, &staging)?;
+
+        let file_name = update
+            .path
+            .file_name()
+            .ok_or(format!("Invalid mod path: {}", update.path.display()))?
+            .to_string_lossy()
+            .to_string();
+        let mut target_name = downloaded
+            .file_name()
+            .ok_or(format!("Invalid file name: {}", file.filename))?
+            .to_string_lossy()
+            .to_string();
+        if file_name.ends_with(".disabled") {
+            target_name.push_str(".disabled");
+        }
+
+        Ok(StagedUpdate {
+            source: update.path.clone(),
+            backup: dir.join(format!("{}.bak", file_name)),
+            target: dir.join(target_name),
+            downloaded,
+            staging,
+        })
+    }
+
+    // 应用更新：先下载并校验全部新文件，任何一个失败都不改动现有文件；
+    // 再逐个替换，替换失败时撤销已完成的替换。旧文件重命名为 .bak 保留，返回新文件路径
+    pub fn apply_updates(
+        &self,
+        updates: &[ModUpdate],
+    ) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+        let mut staged = Vec::new();
+        for update in updates {
+            match self.stage_update(update) {
+                Ok(update) => staged.push(update),
+                Err(err) => {
+                    remove_staging(updates);
+                    return Err(err);
+                }
+            }
+        }
+
+        for (i, update) in staged.iter().enumerate() {
+            if let Err(err) = update.apply() {
+                for applied in staged[..i].iter().rev() {
+                    applied.revert()?;
+                }
+                remove_staging(updates);
+                return Err(err);
+            }
+        }
+        for update in &staged {
+            let _ = std::fs::remove_dir(&update.staging);
+        }
+
+        Ok(staged.into_iter().map(|update| update.target).collect())
+    }
+}
+
+// 已下载到临时目录、等待替换的更新
+struct StagedUpdate {
+    source: PathBuf,
+    backup: PathBuf,
+    target: PathBuf,
+    downloaded: PathBuf,
+    staging: PathBuf,
+}
+
+impl StagedUpdate {
+    fn apply(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::rename(&self.source, &self.backup)?;
+        if let Err(err) = std::fs::rename(&self.downloaded, &self.target) {
+            // 恢复旧文件
+            std::fs::rename(&self.backup, &self.source)?;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    fn revert(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::remove_file(&self.target)?;
+        std::fs::rename(&self.backup, &self.source)?;
+        Ok(())
+    }
+}
+
+// 删除更新用的临时目录及其中已下载的文件
+fn remove_staging(updates: &[ModUpdate]) {
+    for update in updates {
+        if let Some(dir) = update.path.parent() {
+            let _ = std::fs::remove_dir_all(dir.join(".update"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use sha2::{Digest, Sha512};
+
+    use super::*;
+
+    fn version(
+        id: &str,
+        project_id: &str,
+        url: &str,
+        content: &[u8],
+        dependencies: &str,
+    ) -> String {
+        format!(
+            r#"{{"id": "{id}", "project_id": "{project_id}", "name": "{id}", "version_number": "1.0.0", "changelog": null, "date_published": "2024-07-09T02:20:30.471Z", "version_type": "release", "game_versions": ["1.21"], "loaders": ["fabric"], "files": [{{"hashes": {{"sha1": "", "sha512": "{:x}"}}, "url": "{url}", "filename": "{project_id}.jar", "primary": true, "size": {}}}], "dependencies": [{dependencies}]}}"#,
+            Sha512::digest(content),
+            content.len()
+        )
+    }
+
+    #[test]
+    fn test_install() {
+        let fixture = crate::fixture::Fixture::new();
+        let address = fixture.address.clone();
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v2/version/sodium-1".to_string(),
+            version(
+                "sodium-1",
+                "sodium",
+                &format!("{}/files/sodium.jar", address),
+                b"sodium",
+                r#"{"version_id": null, "project_id": "fabric-api", "file_name": null, "dependency_type": "required"}, {"version_id": null, "project_id": "modmenu", "file_name": null, "dependency_type": "optional"}"#,
+            )
+            .into_bytes(),
+        );
+        routes.insert(
+            "/v2/project/fabric-api/version".to_string(),
+            format!(
+                "[{}]",
+                version(
+                    "fabric-api-1",
+                    "fabric-api",
+                    &format!("{}/files/fabric-api.jar", address),
+                    b"fabric-api",
+                    r#"{"version_id": "sodium-1", "project_id": "sodium", "file_name": null, "dependency_type": "required"}"#,
+                )
+            )
+            .into_bytes(),
+        );
+        routes.insert(
+            "/v2/version/broken-1".to_string(),
+            version(
+                "broken-1",
+                "broken",
+                &format!("{}/files/broken.jar", address),
+                b"expected",
+                "",
+            )
+            .into_bytes(),
+        );
+        routes.insert("/files/sodium.jar".to_string(), b"sodium".to_vec());
+        routes.insert("/files/fabric-api.jar".to_string(), b"fabric-api".to_vec());
+        routes.insert("/files/broken.jar".to_string(), b"tampered".to_vec());
+        let modrinth = Modrinth::new(&fixture.serve(routes));
+
+        let mods_dir = &std::env::temp_dir().join("mcl-download-test-modrinth");
+        let _ = std::fs::remove_dir_all(mods_dir);
+
+        let paths = modrinth
+            .install(mods_dir, "sodium-1", Some("1.21"), Some("fabric"))
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(
+            vec![mods_dir.join("sodium.jar"), mods_dir.join("fabric-api.jar")],
+            paths
+        );
+        assert_eq!(b"fabric-api".to_vec(), std::fs::read(&paths[1]).unwrap());
+
+        assert!(modrinth.install(mods_dir, "broken-1", None, None).is_err());
+        assert!(!mods_dir.join("broken.jar").exists());
+        assert!(!mods_dir.join("broken.jar.part").exists());
+    }
+
+    #[test]
+    fn test_update() {
+        let fixture = crate::fixture::Fixture::new();
+        let address = fixture.address.clone();
+
+        let mods_dir = &std::env::temp_dir().join("mcl-download-test-modrinth-update");
+        let _ = std::fs::remove_dir_all(mods_dir);
+        std::fs::create_dir_all(mods_dir).unwrap();
+        let old = mods_dir.join("sodium-old.jar.disabled");
+        std::fs::write(&old, b"sodium-old").unwrap();
+        let unknown = mods_dir.join("unknown.jar");
+        std::fs::write(&unknown, b"unknown").unwrap();
+        let hash = format!("{:x}", sha1::Sha1::digest(b"sodium-old"));
+
+        let current = version("sodium-1", "sodium", "", b"sodium-old", "");
+        let latest = version(
+            "sodium-2",
+            "sodium",
+            &format!("{}/files/sodium.jar", address),
+            b"sodium-new",
+            "",
+        );
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v2/version_files".to_string(),
+            format!(r#"{{"{}": {}}}"#, hash, current).into_bytes(),
+        );
+        routes.insert(
+            "/v2/version_files/update".to_string(),
+            format!(r#"{{"{}": {}}}"#, hash, latest).into_bytes(),
+        );
+        routes.insert("/files/sodium.jar".to_string(), b"sodium-new".to_vec());
+        routes.insert("/files/lithium.jar".to_string(), b"lithium-new".to_vec());
+        let modrinth = Modrinth::new(&fixture.serve(routes));
+
+        let updates = modrinth
+            .check_updates(&[old.clone(), unknown], Some("1.21"), Some("fabric"))
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(1, updates.len());
+        assert_eq!("sodium-2", updates[0].latest.id);
+
+        // 任何一个更新下载失败时不改动现有文件
+        let lithium = mods_dir.join("lithium-old.jar");
+        std::fs::write(&lithium, b"lithium-old").unwrap();
+        let lithium_update = |file: &str| ModUpdate {
+            path: lithium.clone(),
+            current: serde_json::from_str(&version("lithium-1", "lithium", "", b"lithium-old", ""))
+                .unwrap(),
+            latest: serde_json::from_str(&version(
+                "lithium-2",
+                "lithium",
+                &format!("{}/files/{}", address, file),
+                b"lithium-new",
+                "",
+            ))
+            .unwrap(),
+        };
+        let mut updates = updates;
+        updates.push(lithium_update("missing.jar"));
+        assert!(modrinth.apply_updates(&updates).is_err());
+        assert_eq!(b"sodium-old".to_vec(), std::fs::read(&old).unwrap());
+        assert!(!mods_dir.join(".update").exists());
+
+        // 替换失败时撤销已完成的替换
+        updates[1] = lithium_update("lithium.jar");
+        std::fs::create_dir_all(mods_dir.join("lithium.jar/blocked")).unwrap();
+        assert!(modrinth.apply_updates(&updates).is_err());
+        assert_eq!(b"sodium-old".to_vec(), std::fs::read(&old).unwrap());
+        assert_eq!(b"lithium-old".to_vec(), std::fs::read(&lithium).unwrap());
+        assert!(!mods_dir.join("sodium.jar.disabled").exists());
+        assert!(!mods_dir.join(".update").exists());
+        std::fs::remove_dir_all(mods_dir.join("lithium.jar")).unwrap();
+
+        let updated = modrinth
+            .apply_updates(&updates)
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(
+            vec![
+                mods_dir.join("sodium.jar.disabled"),
+                mods_dir.join("lithium.jar")
+            ],
+            updated
+        );
+        assert_eq!(b"lithium-new".to_vec(), std::fs::read(&updated[1]).unwrap());
+        let updated = &updated[0];
+        assert_eq!(b"sodium-new".to_vec(), std::fs::read(&updated).unwrap());
+        assert!(!old.exists());
+        assert_eq!(
+            b"sodium-old".to_vec(),
+            std::fs::read(mods_dir.join("sodium-old.jar.disabled.bak")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_facets() {
+        let query = SearchQuery {
+            game_version: Some("1.21".to_string()),
+            loader: Some("fabric".to_string()),
+            categories: vec!["optimization".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            r#"[["versions:1.21"],["categories:fabric"],["categories:optimization"]]"#,
+            query.facets()
+        );
+    }
+}
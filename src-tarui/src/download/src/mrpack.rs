@@ -0,0 +1,582 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{Read, Seek, Write},
+    path::{Path, PathBuf},
+};
+
+use glob::Pattern;
+use model::mrpack::{Index, IndexFile};
+use parse::Parse;
+use path::Instance;
+use serde::Serialize;
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+use crate::{loader::Loader, modrinth::Modrinth, safe_join};
+
+// 读取并校验 modrinth.index.json
+pub fn read_index(pack: &Path) -> Result<Index, Box<dyn std::error::Error>> {
+    let mut archive = ZipArchive::new(File::open(pack)?)?;
+    let mut text = String::new();
+    archive
+        .by_name("modrinth.index.json")
+        .map_err(|_| {
+            format!(
+                "{} is not a mrpack: missing modrinth.index.json",
+                pack.display()
+            )
+        })?
+        .read_to_string(&mut text)?;
+
+    let index = Index::parse(&text)?;
+    if index.format_version != 1 {
+        return Err(format!("Unsupported mrpack formatVersion: {}", index.format_version).into());
+    }
+    if index.game != "minecraft" {
+        return Err(format!("Unsupported mrpack game: {}", index.game).into());
+    }
+    Ok(index)
+}
+
+// dependencies 中声明的游戏版本
+pub fn game_version(index: &Index) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(index
+        .dependencies
+        .get("minecraft")
+        .ok_or("mrpack does not declare a minecraft dependency")?
+        .clone())
+}
+
+// dependencies 中声明的加载器及其版本
+pub fn loader(index: &Index) -> Option<(Loader, String)> {
+    [
+        ("fabric-loader", Loader::Fabric),
+        ("quilt-loader", Loader::Quilt),
+        ("forge", Loader::Forge),
+        ("neoforge", Loader::NeoForge),
+    ]
+    .into_iter()
+    .find_map(|(key, loader)| Some((loader, index.dependencies.get(key)?.clone())))
+}
+
+// 创建继承基础版本的实例并记录加载器，实例的文件放在 versions/<id> 下
+pub(crate) fn create_instance(
+    game_dir: &Path,
+    name: &str,
+    base_version: &str,
+    loader: Option<&(Loader, String)>,
+) -> Result<Instance, Box<dyn std::error::Error>> {
+    let mut instance = path::create_instance(game_dir, name, base_version)?;
+    if let Some((loader, loader_version)) = loader {
+        instance.loader = Some(loader.name().to_string());
+        instance.loader_version = Some(loader_version.clone());
+        instance.save(game_dir)?;
+    }
+    Ok(instance)
+}
+
+// 把压缩包中 prefix 目录下的内容解压到 target，路径越界的条目会导致失败
+pub(crate) fn extract_directory<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    prefix: &str,
+    target: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let Some(relative) = entry.name().strip_prefix(prefix) else {
+            continue;
+        };
+        let relative = relative.to_string();
+        if relative.is_empty() || relative.ends_with('/') {
+            continue;
+        }
+
+        let path = safe_join(target, &relative)
+            .ok_or(format!("Unsafe path in archive: {}", entry.name()))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = File::create(&path)?;
+        std::io::copy(&mut entry, &mut file)?;
+    }
+    Ok(())
+}
+
+// 下载中的临时文件，在完整文件名后加 .part，避免 a.jar 与 a.zip 使用同一个临时文件
+fn part_path(path: &Path) -> PathBuf {
+    let mut part = path.as_os_str().to_os_string();
+    part.push(".part");
+    PathBuf::from(part)
+}
+
+// 依次尝试下载地址，校验 sha512，没有时校验 sha1
+fn download_file(file: &IndexFile, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let expected = |algorithm: &str| file.hashes.get(algorithm).map(|h| h.to_lowercase());
+    let verify = |path: &Path| -> Result<bool, std::io::Error> {
+        if let Some(sha512) = expected("sha512") {
+            return Ok(crate::sha512(path)?.eq(&sha512));
+        }
+        if let Some(sha1) = expected("sha1") {
+            return Ok(crate::sha1(path)?.eq(&sha1));
+        }
+        Ok(true)
+    };
+
+    if path.exists() && verify(path)? {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let part = part_path(path);
+    let mut last_error = format!("File {} has no downloads", file.path);
+    for url in &file.downloads {
+        println!("下载 {}", file.path);
+        let bytes = match crate::client()?
+            .get(url)
+            .send()
+            .and_then(|r| r.error_for_status())
+        {
+            Ok(response) => response.bytes()?,
+            Err(err) => {
+                last_error = err.to_string();
+                continue;
+            }
+        };
+        std::fs::write(&part, &bytes)?;
+        if verify(&part)? {
+            std::fs::rename(&part, path)?;
+            return Ok(());
+        }
+        std::fs::remove_file(&part)?;
+        last_error = format!("File {} hash mismatch from {}", file.path, url);
+    }
+    Err(last_error.into())
+}
+
+// 下载整合包文件并应用 overrides，不支持客户端的文件会被跳过
+pub fn install_files(
+    pack: &Path,
+    index: &Index,
+    instance_dir: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    for file in &index.files {
+        if file
+            .env
+            .as_ref()
+            .is_some_and(|env| env.client == "unsupported")
+        {
+            continue;
+        }
+        let path = safe_join(instance_dir, &file.path)
+            .ok_or(format!("Unsafe path in mrpack: {}", file.path))?;
+        download_file(file, &path)?;
+    }
+
+    // client-overrides 在 overrides 之后应用，同名文件以其为准
+    let mut archive = ZipArchive::new(File::open(pack)?)?;
+    extract_directory(&mut archive, "overrides/", instance_dir)?;
+    extract_directory(&mut archive, "client-overrides/", instance_dir)?;
+    Ok(())
+}
+
+// 导入 .mrpack：安装游戏与加载器，创建以整合包命名的版本并写入文件，返回版本 id
+pub fn import(
+    game_dir: &Path,
+    pack: &Path,
+    name: Option<&str>,
+    java: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let index = read_index(pack)?;
+    let game_version = game_version(&index)?;
+
+    let name = name.unwrap_or(&index.name);
+    let id = path::instance_id(name);
+    if game_dir.join("versions").join(&id).exists() {
+        return Err(format!("Version: {} already exists", id).into());
+    }
+
+    println!("导入整合包 {} {}", index.name, index.version_id);
+    crate::version::install(game_dir, &game_version)?;
+    let loader = loader(&index);
+    let base_version = match &loader {
+        Some((loader, loader_version)) => {
+            loader.install(game_dir, &game_version, Some(loader_version), java)?
+        }
+        None => game_version,
+    };
+
+    let instance = create_instance(game_dir, name, &base_version, loader.as_ref())?;
+    let instance_dir = instance.game_dir(game_dir);
+    if let Err(err) = install_files(pack, &index, &instance_dir) {
+        // 导入失败时不留下不完整的版本
+        let _ = std::fs::remove_dir_all(&instance_dir);
+        return Err(err);
+    }
+    Ok(instance.id)
+}
+
+// 导出选项，include / exclude 为相对于实例目录的 glob
+pub struct ExportOptions {
+    pub name: String,
+    pub version_id: String,
+    pub summary: Option<String>,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl ExportOptions {
+    pub fn new(name: &str, version_id: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            version_id: version_id.to_string(),
+            summary: None,
+            include: vec![
+                "mods/**".to_string(),
+                "config/**".to_string(),
+                "resourcepacks/**".to_string(),
+                "shaderpacks/**".to_string(),
+            ],
+            exclude: Vec::new(),
+        }
+    }
+}
+
+// 导出结果：在 Modrinth 上识别到的文件与写入 overrides 的文件
+#[derive(Serialize)]
+pub struct ExportSummary {
+    pub files: Vec<String>,
+    pub overrides: Vec<String>,
+}
+
+// 递归列出目录下的文件，返回以 / 分隔的相对路径
+fn list_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), std::io::Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            list_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<_> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            files.push(parts.join("/"));
+        }
+    }
+    Ok(())
+}
+
+// 从版本的 inheritsFrom 链得到 dependencies
+pub fn version_dependencies(
+    game_dir: &Path,
+    version_id: &str,
+) -> Result<HashMap<String, String>, Box<dyn std::error::Error>> {
+    let version = parse::version::load(&game_dir.join("versions"), version_id)?;
+    let mut dependencies = HashMap::new();
+    dependencies.insert(
+        "minecraft".to_string(),
+        version.jar.clone().unwrap_or(version.id.clone()),
+    );
+    if let Some((loader, loader_version)) = Loader::detect(&version) {
+        let key = match loader {
+            Loader::Fabric => "fabric-loader",
+            Loader::Quilt => "quilt-loader",
+            Loader::Forge => "forge",
+            Loader::NeoForge => "neoforge",
+        };
+        dependencies.insert(key.to_string(), loader_version);
+    }
+    Ok(dependencies)
+}
+
+// 把实例目录写成 .mrpack，能在 Modrinth 上按 hash 找到的 jar / zip 写入 files，其余放进 overrides
+pub fn write_pack(
+    instance_dir: &Path,
+    output: &Path,
+    dependencies: HashMap<String, String>,
+    options: &ExportOptions,
+    modrinth: &Modrinth,
+) -> Result<ExportSummary, Box<dyn std::error::Error>> {
+    let include = options
+        .include
+        .iter()
+        .map(|p| Pattern::new(p))
+        .collect::<Result<Vec<_>, _>>()?;
+    let exclude = options
+        .exclude
+        .iter()
+        .map(|p| Pattern::new(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut paths = Vec::new();
+    list_files(instance_dir, instance_dir, &mut paths)?;
+    paths.sort();
+    paths.retain(|path| {
+        include.iter().any(|p| p.matches(path)) && !exclude.iter().any(|p| p.matches(path))
+    });
+
+    // 只有 jar 和 zip 可能是 Modrinth 上的项目
+    let mut hashes = HashMap::new();
+    for path in &paths {
+        if path.ends_with(".jar") || path.ends_with(".zip") {
+            hashes.insert(path.clone(), crate::sha1(instance_dir.join(path))?);
+        }
+    }
+    let versions = if hashes.is_empty() {
+        HashMap::new()
+    } else {
+        modrinth.version_files(&hashes.values().cloned().collect::<Vec<_>>(), "sha1")?
+    };
+
+    let mut files = Vec::new();
+    let mut overrides = Vec::new();
+    for path in paths {
+        let file = hashes.get(&path).and_then(|hash| {
+            versions
+                .get(hash)?
+                .files
+                .iter()
+                .find(|f| f.hashes.sha1.eq(hash))
+        });
+        match file {
+            Some(file) => files.push(IndexFile {
+                path: path.clone(),
+                hashes: HashMap::from([
+                    ("sha1".to_string(), file.hashes.sha1.clone()),
+                    ("sha512".to_string(), file.hashes.sha512.clone()),
+                ]),
+                env: None,
+                downloads: vec![file.url.clone()],
+                file_size: file.size,
+            }),
+            None => overrides.push(path),
+        }
+    }
+
+    let index = Index {
+        format_version: 1,
+        game: "minecraft".to_string(),
+        version_id: options.version_id.clone(),
+        name: options.name.clone(),
+        summary: options.summary.clone(),
+        files,
+        dependencies,
+    };
+
+    let mut writer = ZipWriter::new(File::create(output)?);
+    writer.start_file("modrinth.index.json", SimpleFileOptions::default())?;
+    writer.write_all(serde_json::to_string_pretty(&index)?.as_bytes())?;
+    for path in &overrides {
+        writer.start_file(format!("overrides/{}", path), SimpleFileOptions::default())?;
+        std::io::copy(&mut File::open(instance_dir.join(path))?, &mut writer)?;
+    }
+    writer.finish()?;
+
+    Ok(ExportSummary {
+        files: index.files.into_iter().map(|f| f.path).collect(),
+        overrides,
+    })
+}
+
+// 导出 versions/<id> 为 .mrpack，游戏版本与加载器取自版本的 inheritsFrom 链
+pub fn export(
+    game_dir: &Path,
+    version_id: &str,
+    output: &Path,
+    options: &ExportOptions,
+    modrinth: &Modrinth,
+) -> Result<ExportSummary, Box<dyn std::error::Error>> {
+    let dependencies = version_dependencies(game_dir, version_id)?;
+    let instance_dir = game_dir.join("versions").join(version_id);
+    write_pack(&instance_dir, output, dependencies, options, modrinth)
+}
+
+#[cfg(test)]
+mod tests {
+    use sha2::{Digest, Sha512};
+
+    use super::*;
+
+    fn pack(name: &str, index: &str, entries: &[(&str, &[u8])]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("mcl-download-test-{}.mrpack", name));
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        writer
+            .start_file("modrinth.index.json", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(index.as_bytes()).unwrap();
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_read_index() {
+        let path = pack(
+            "mrpack-format",
+            r#"{"formatVersion": 2, "game": "minecraft", "versionId": "1", "name": "Pack", "files": [], "dependencies": {"minecraft": "1.21"}}"#,
+            &[],
+        );
+        assert!(read_index(&path).is_err());
+
+        let path = pack(
+            "mrpack-index",
+            r#"{"formatVersion": 1, "game": "minecraft", "versionId": "1", "name": "Pack", "files": [], "dependencies": {"minecraft": "1.21", "fabric-loader": "0.15.11"}}"#,
+            &[],
+        );
+        let index = read_index(&path).unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!("1.21", game_version(&index).unwrap());
+        assert_eq!(
+            Some((Loader::Fabric, "0.15.11".to_string())),
+            loader(&index)
+        );
+    }
+
+    #[test]
+    fn test_install_files() {
+        let fixture = crate::fixture::Fixture::new();
+        let address = fixture.address.clone();
+        let mut routes = HashMap::new();
+        routes.insert("/sodium.jar".to_string(), b"sodium".to_vec());
+        routes.insert("/server.jar".to_string(), b"server".to_vec());
+        fixture.serve(routes);
+
+        let index = format!(
+            r#"{{"formatVersion": 1, "game": "minecraft", "versionId": "1", "name": "Pack", "dependencies": {{"minecraft": "1.21"}}, "files": [
+                {{"path": "mods/sodium.jar", "hashes": {{"sha1": "", "sha512": "{:x}"}}, "env": {{"client": "required", "server": "required"}}, "downloads": ["{address}/missing.jar", "{address}/sodium.jar"], "fileSize": 6}},
+                {{"path": "mods/server.jar", "hashes": {{"sha512": ""}}, "env": {{"client": "unsupported", "server": "required"}}, "downloads": ["{address}/server.jar"], "fileSize": 6}}
+            ]}}"#,
+            Sha512::digest(b"sodium")
+        );
+        let path = pack(
+            "mrpack-files",
+            &index,
+            &[
+                ("overrides/config/a.txt", b"overrides"),
+                ("overrides/options.txt", b"options"),
+                ("client-overrides/config/a.txt", b"client"),
+            ],
+        );
+        let index = read_index(&path).unwrap();
+
+        let instance_dir = &std::env::temp_dir().join("mcl-download-test-mrpack");
+        let _ = std::fs::remove_dir_all(instance_dir);
+        install_files(&path, &index, instance_dir).unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!(
+            b"sodium".to_vec(),
+            std::fs::read(instance_dir.join("mods/sodium.jar")).unwrap()
+        );
+        assert!(!instance_dir.join("mods/server.jar").exists());
+        assert_eq!(
+            b"client".to_vec(),
+            std::fs::read(instance_dir.join("config/a.txt")).unwrap()
+        );
+        assert_eq!(
+            b"options".to_vec(),
+            std::fs::read(instance_dir.join("options.txt")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            Path::new("mods/foo.jar.part"),
+            part_path(Path::new("mods/foo.jar"))
+        );
+        assert_ne!(
+            part_path(Path::new("mods/foo.jar")),
+            part_path(Path::new("mods/foo.zip"))
+        );
+    }
+
+    #[test]
+    fn test_path_traversal() {
+        let index = r#"{"formatVersion": 1, "game": "minecraft", "versionId": "1", "name": "Pack", "dependencies": {"minecraft": "1.21"}, "files": [{"path": "../../escape.jar", "hashes": {}, "downloads": [], "fileSize": 0}]}"#;
+        let path = pack("mrpack-traversal", index, &[]);
+        let index = read_index(&path).unwrap();
+        let instance_dir = &std::env::temp_dir().join("mcl-download-test-mrpack-traversal");
+        let err = install_files(&path, &index, instance_dir).unwrap_err();
+        assert!(err.to_string().contains("Unsafe path"));
+
+        let index = r#"{"formatVersion": 1, "game": "minecraft", "versionId": "1", "name": "Pack", "dependencies": {"minecraft": "1.21"}, "files": []}"#;
+        let path = pack(
+            "mrpack-traversal-overrides",
+            index,
+            &[("overrides/../../escape.txt", b"x")],
+        );
+        let index = read_index(&path).unwrap();
+        assert!(install_files(&path, &index, instance_dir).is_err());
+
+        assert!(safe_join(Path::new("/a"), "/etc/passwd").is_none());
+        assert!(safe_join(Path::new("/a"), "b/../../c").is_none());
+        assert_eq!(
+            Some(Path::new("/a/b/c").to_path_buf()),
+            safe_join(Path::new("/a"), "./b/c")
+        );
+    }
+
+    #[test]
+    fn test_write_pack() {
+        let instance_dir = &std::env::temp_dir().join("mcl-download-test-mrpack-export");
+        let _ = std::fs::remove_dir_all(instance_dir);
+        for (path, content) in [
+            ("mods/sodium.jar", &b"sodium"[..]),
+            ("mods/private.jar", b"private"),
+            ("config/sodium.json", b"{}"),
+            ("config/secret.txt", b"secret"),
+            ("saves/world/level.dat", b"level"),
+        ] {
+            let path = instance_dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let sha1 = format!("{:x}", sha1::Sha1::digest(b"sodium"));
+        let fixture = crate::fixture::Fixture::new();
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v2/version_files".to_string(),
+            format!(
+                r#"{{"{sha1}": {{"id": "v1", "project_id": "sodium", "name": "Sodium", "version_number": "0.5.11", "changelog": null, "date_published": "", "version_type": "release", "files": [{{"hashes": {{"sha1": "{sha1}", "sha512": "abc"}}, "url": "https://cdn.modrinth.com/sodium.jar", "filename": "sodium.jar", "primary": true, "size": 6}}]}}}}"#
+            )
+            .into_bytes(),
+        );
+        let modrinth = Modrinth::new(&fixture.serve(routes));
+
+        let output = std::env::temp_dir().join("mcl-download-test-export.mrpack");
+        let mut options = ExportOptions::new("Team Pack", "1.0.0");
+        options.exclude.push("config/secret.*".to_string());
+        let dependencies = HashMap::from([
+            ("minecraft".to_string(), "1.21".to_string()),
+            ("fabric-loader".to_string(), "0.15.11".to_string()),
+        ]);
+        let summary = write_pack(instance_dir, &output, dependencies, &options, &modrinth)
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(vec!["mods/sodium.jar"], summary.files);
+        assert_eq!(
+            vec!["config/sodium.json", "mods/private.jar"],
+            summary.overrides
+        );
+
+        let index = read_index(&output).unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!("Team Pack", index.name);
+        assert_eq!(
+            Some((Loader::Fabric, "0.15.11".to_string())),
+            loader(&index)
+        );
+        assert_eq!(
+            "https://cdn.modrinth.com/sodium.jar",
+            index.files[0].downloads[0]
+        );
+        let mut archive = ZipArchive::new(File::open(&output).unwrap()).unwrap();
+        assert!(archive.by_name("overrides/mods/private.jar").is_ok());
+        assert!(archive.by_name("overrides/config/secret.txt").is_err());
+        assert!(archive.by_name("overrides/saves/world/level.dat").is_err());
+    }
+}
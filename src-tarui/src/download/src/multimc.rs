@@ -0,0 +1,361 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use model::multimc::Pack;
+use parse::Parse;
+use serde::Serialize;
+use zip::ZipArchive;
+
+use crate::{loader::Loader, mrpack};
+
+// 游戏与 LWJGL 由原版配置提供，这些组件无需单独处理
+const KNOWN_COMPONENTS: [&str; 5] = [
+    "net.minecraft",
+    "net.fabricmc.intermediary",
+    "org.quiltmc.hashed",
+    "org.lwjgl",
+    "org.lwjgl3",
+];
+
+// 组件 uid 对应的加载器
+const LOADER_COMPONENTS: [(&str, Loader); 4] = [
+    ("net.fabricmc.fabric-loader", Loader::Fabric),
+    ("org.quiltmc.quilt-loader", Loader::Quilt),
+    ("net.minecraftforge", Loader::Forge),
+    ("net.neoforged", Loader::NeoForge),
+];
+
+// instance.cfg 中与启动相关的设置，内存单位为 MB
+#[derive(Serialize, Default, Debug, PartialEq)]
+pub struct InstanceConfig {
+    pub name: Option<String>,
+    pub java_path: Option<String>,
+    pub jvm_args: Vec<String>,
+    pub min_memory: Option<u32>,
+    pub max_memory: Option<u32>,
+}
+
+// 导入结果，config 中的启动设置已保存到实例配置
+#[derive(Serialize)]
+pub struct InstanceImport {
+    pub id: String,
+    pub game_version: String,
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+    pub lwjgl_version: Option<String>,
+    pub config: InstanceConfig,
+    // 无法导入的组件，例如 LiteLoader 或 jar mod
+    pub unsupported: Vec<String>,
+}
+
+// 解析 QSettings 格式的 instance.cfg，Override* 为 false 时忽略对应设置
+pub fn read_instance_config(text: &str) -> InstanceConfig {
+    let values: HashMap<&str, String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';', '[']))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), unquote(value.trim())))
+        .collect();
+
+    let value = |key: &str| values.get(key).filter(|v| !v.is_empty()).cloned();
+    let overridden = |flag: &str| values.get(flag).is_none_or(|v| v == "true");
+
+    let mut config = InstanceConfig {
+        name: value("name"),
+        ..Default::default()
+    };
+    if overridden("OverrideJavaLocation") {
+        config.java_path = value("JavaPath");
+    }
+    if overridden("OverrideJavaArgs") {
+        config.jvm_args = value("JvmArgs")
+            .map(|args| split_arguments(&args))
+            .unwrap_or_default();
+    }
+    if overridden("OverrideMemory") {
+        config.min_memory = value("MinMemAlloc").and_then(|v| v.parse().ok());
+        config.max_memory = value("MaxMemAlloc").and_then(|v| v.parse().ok());
+    }
+    config
+}
+
+// 去掉 QSettings 给含特殊字符的值加上的引号
+fn unquote(value: &str) -> String {
+    match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(value) => value.replace("\\\"", "\"").replace("\\\\", "\\"),
+        None => value.to_string(),
+    }
+}
+
+// 按空白分割 JVM 参数，引号内的空白不分割
+fn split_arguments(args: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in args.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    arguments.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        arguments.push(current);
+    }
+    arguments
+}
+
+// net.minecraft 组件声明的游戏版本
+pub fn game_version(pack: &Pack) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(pack
+        .component("net.minecraft")
+        .and_then(|c| c.version())
+        .ok_or("mmc-pack.json does not declare net.minecraft")?
+        .to_string())
+}
+
+// 组件中的加载器及其版本
+pub fn loader(pack: &Pack) -> Option<(Loader, String)> {
+    LOADER_COMPONENTS
+        .into_iter()
+        .find_map(|(uid, loader)| Some((loader, pack.component(uid)?.version()?.to_string())))
+}
+
+pub fn lwjgl_version(pack: &Pack) -> Option<String> {
+    ["org.lwjgl3", "org.lwjgl"]
+        .into_iter()
+        .find_map(|uid| Some(pack.component(uid)?.version()?.to_string()))
+}
+
+// 既不是游戏、LWJGL 也不是加载器的组件
+pub fn unsupported_components(pack: &Pack) -> Vec<String> {
+    pack.components
+        .iter()
+        .filter(|c| {
+            !KNOWN_COMPONENTS.contains(&c.uid.as_str())
+                && !LOADER_COMPONENTS.iter().any(|(uid, _)| *uid == c.uid)
+        })
+        .map(|c| c.cached_name.clone().unwrap_or(c.uid.clone()))
+        .collect()
+}
+
+// 实例根目录是包含 mmc-pack.json 的目录，导出的压缩包通常多套一层实例名目录
+pub fn find_instance_root(dir: &Path) -> Option<PathBuf> {
+    if dir.join("mmc-pack.json").is_file() {
+        return Some(dir.to_path_buf());
+    }
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.join("mmc-pack.json").is_file())
+}
+
+// 实例中的游戏目录，Prism 使用 minecraft，MultiMC 使用 .minecraft
+pub fn minecraft_dir(root: &Path) -> Option<PathBuf> {
+    [".minecraft", "minecraft"]
+        .into_iter()
+        .map(|name| root.join(name))
+        .find(|path| path.is_dir())
+}
+
+// 导入 MultiMC / Prism 实例目录或导出的 zip：安装游戏与加载器，创建版本并复制游戏目录
+pub fn import(
+    game_dir: &Path,
+    source: &Path,
+    name: Option<&str>,
+    java: &str,
+) -> Result<InstanceImport, Box<dyn std::error::Error>> {
+    if source.is_dir() {
+        return import_directory(game_dir, source, name, java);
+    }
+
+    // 压缩包先解压到临时目录
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+    let temp = std::env::temp_dir().join(format!("rmcl-import-{}", millis));
+    let result = ZipArchive::new(File::open(source)?)
+        .map_err(|e| e.into())
+        .and_then(|mut archive| mrpack::extract_directory(&mut archive, "", &temp))
+        .and_then(|_| import_directory(game_dir, &temp, name, java));
+    let _ = std::fs::remove_dir_all(&temp);
+    result
+}
+
+fn import_directory(
+    game_dir: &Path,
+    dir: &Path,
+    name: Option<&str>,
+    java: &str,
+) -> Result<InstanceImport, Box<dyn std::error::Error>> {
+    let root = find_instance_root(dir).ok_or(format!(
+        "{} is not a MultiMC instance: missing mmc-pack.json",
+        dir.display()
+    ))?;
+    let pack = Pack::parse(&std::fs::read_to_string(root.join("mmc-pack.json"))?)?;
+    let config = match std::fs::read_to_string(root.join("instance.cfg")) {
+        Ok(text) => read_instance_config(&text),
+        Err(_) => InstanceConfig::default(),
+    };
+
+    let folder_name = root
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let name = name.or(config.name.as_deref()).unwrap_or(&folder_name);
+    let id = path::instance_id(name);
+    if game_dir.join("versions").join(&id).exists() {
+        return Err(format!("Version: {} already exists", id).into());
+    }
+
+    let game_version = game_version(&pack)?;
+    let loader = loader(&pack);
+
+    println!("导入实例 {}", id);
+    crate::version::install(game_dir, &game_version)?;
+    let base_version = match &loader {
+        Some((loader, loader_version)) => {
+            loader.install(game_dir, &game_version, Some(loader_version), java)?
+        }
+        None => game_version.clone(),
+    };
+
+    let mut instance = mrpack::create_instance(game_dir, name, &base_version, loader.as_ref())?;
+    let instance_dir = instance.game_dir(game_dir);
+    // 启动设置保存到实例配置中
+    instance.java = config.java_path.clone();
+    instance.jvm_args = config.jvm_args.clone();
+    instance.min_memory = config.min_memory;
+    instance.max_memory = config.max_memory;
+    let result =
+        instance
+            .save(game_dir)
+            .map_err(|e| e.into())
+            .and_then(|_| match minecraft_dir(&root) {
+                Some(minecraft_dir) => {
+                    path::copy_dir(&minecraft_dir, &instance_dir, &[]).map_err(|e| e.into())
+                }
+                None => Ok(()),
+            });
+    if let Err(err) = result {
+        // 导入失败时不留下不完整的版本
+        let _ = std::fs::remove_dir_all(&instance_dir);
+        return Err(err);
+    }
+
+    Ok(InstanceImport {
+        id,
+        game_version,
+        loader: loader.as_ref().map(|(loader, _)| loader.name().to_string()),
+        loader_version: loader.map(|(_, version)| version),
+        lwjgl_version: lwjgl_version(&pack),
+        unsupported: unsupported_components(&pack),
+        config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+
+    #[test]
+    fn test_read_instance_config() {
+        let config = read_instance_config(
+            r#"[General]
+ConfigVersion=1.2
+InstanceType=OneSix
+name=Fabulously Optimized
+iconKey=default
+OverrideJavaLocation=true
+JavaPath=/usr/lib/jvm/java-17/bin/java
+OverrideJavaArgs=true
+JvmArgs="-XX:+UseG1GC -Dfoo=\"a b\""
+OverrideMemory=false
+MinMemAlloc=512
+MaxMemAlloc=4096
+"#,
+        );
+        assert_eq!(
+            InstanceConfig {
+                name: Some("Fabulously Optimized".to_string()),
+                java_path: Some("/usr/lib/jvm/java-17/bin/java".to_string()),
+                jvm_args: vec!["-XX:+UseG1GC".to_string(), "-Dfoo=a b".to_string()],
+                min_memory: None,
+                max_memory: None,
+            },
+            config
+        );
+
+        // 旧版 MultiMC 没有 Override* 时直接使用设置
+        let config = read_instance_config("MaxMemAlloc=2048\nJavaPath=\n");
+        assert_eq!(Some(2048), config.max_memory);
+        assert_eq!(None, config.java_path);
+    }
+
+    #[test]
+    fn test_components() {
+        let pack = Pack::parse(
+            r#"{"formatVersion": 1, "components": [
+                {"uid": "org.lwjgl3", "version": "3.3.1", "dependencyOnly": true},
+                {"uid": "net.minecraft", "version": "1.20.1"},
+                {"uid": "net.fabricmc.intermediary", "version": "1.20.1", "dependencyOnly": true},
+                {"uid": "net.fabricmc.fabric-loader", "version": "0.15.11"},
+                {"uid": "org.multimc.jarmod.1234", "cachedName": "OptiFine.jar"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!("1.20.1", game_version(&pack).unwrap());
+        assert_eq!(Some((Loader::Fabric, "0.15.11".to_string())), loader(&pack));
+        assert_eq!(Some("3.3.1".to_string()), lwjgl_version(&pack));
+        assert_eq!(vec!["OptiFine.jar"], unsupported_components(&pack));
+    }
+
+    #[test]
+    fn test_find_instance_root() {
+        let root = std::env::temp_dir().join("mcl-download-test-multimc");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+
+        // 导出的压缩包中多套一层实例名目录
+        let path = root.join("instance.zip");
+        let mut writer = ZipWriter::new(File::create(&path).unwrap());
+        for (name, content) in [
+            ("Pack/mmc-pack.json", &b"{}"[..]),
+            ("Pack/instance.cfg", b"name=Pack"),
+            ("Pack/.minecraft/mods/sodium.jar", b"sodium"),
+        ] {
+            writer
+                .start_file(name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let extracted = root.join("extracted");
+        let mut archive = ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        mrpack::extract_directory(&mut archive, "", &extracted).unwrap();
+
+        let instance_root = find_instance_root(&extracted).unwrap();
+        assert_eq!(extracted.join("Pack"), instance_root);
+        let minecraft_dir = minecraft_dir(&instance_root).unwrap();
+
+        let instance_dir = root.join("versions/Pack");
+        path::copy_dir(&minecraft_dir, &instance_dir, &[]).unwrap();
+        assert_eq!(
+            b"sodium".to_vec(),
+            std::fs::read(instance_dir.join("mods/sodium.jar")).unwrap()
+        );
+    }
+}
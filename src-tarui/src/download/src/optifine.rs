@@ -0,0 +1,428 @@
+use std::{io::Read, path::Path};
+
+use crate::loader::Loader;
+
+pub const TWEAK_CLASS: &str = "optifine.OptiFineTweaker";
+pub const LAUNCH_WRAPPER_MAIN: &str = "net.minecraft.launchwrapper.Launch";
+
+// 从 OptiFine jar 中读取的版本信息
+#[derive(Debug, PartialEq)]
+pub struct OptiFine {
+    // 对应的游戏版本，例如 1.20.1
+    pub minecraft: String,
+    // 例如 HD_U_I6
+    pub edition: String,
+}
+
+impl OptiFine {
+    // 读取 net/optifine/Config.class 常量池中的 MC_VERSION、OF_EDITION、OF_RELEASE，
+    // 读取失败时退回到文件名 OptiFine_1.20.1_HD_U_I6.jar
+    pub fn open(jar: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(jar)?)?;
+
+        let mut class = Vec::new();
+        for name in ["net/optifine/Config.class", "Config.class"] {
+            if let Ok(mut entry) = archive.by_name(name) {
+                entry.read_to_end(&mut class)?;
+                break;
+            }
+        }
+
+        if let Some(optifine) = Self::from_constants(&class_strings(&class)) {
+            return Ok(optifine);
+        }
+
+        let stem = jar
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Self::from_file_name(&stem).ok_or(format!("Not an OptiFine jar: {}", jar.display()).into())
+    }
+
+    fn from_constants(strings: &[String]) -> Option<Self> {
+        let minecraft = strings.iter().find(|s| is_game_version(s))?;
+        let edition = strings.iter().find(|s| s.starts_with("HD_"))?;
+        let release = strings.iter().find(|s| is_release(s))?;
+
+        Some(Self {
+            minecraft: minecraft.clone(),
+            edition: format!("{}_{}", edition, release),
+        })
+    }
+
+    fn from_file_name(stem: &str) -> Option<Self> {
+        let stem = stem.trim_start_matches("preview_");
+        let rest = stem.strip_prefix("OptiFine_")?;
+        let (minecraft, edition) = rest.split_once('_')?;
+
+        if !is_game_version(minecraft) || !edition.starts_with("HD_") {
+            return None;
+        }
+
+        Some(Self {
+            minecraft: minecraft.to_string(),
+            edition: edition.to_string(),
+        })
+    }
+
+    pub fn version_id(&self) -> String {
+        format!("{}-OptiFine_{}", self.minecraft, self.edition)
+    }
+
+    pub fn library_name(&self) -> String {
+        format!("optifine:OptiFine:{}_{}", self.minecraft, self.edition)
+    }
+}
+
+fn is_game_version(value: &str) -> bool {
+    let parts: Vec<&str> = value.split('.').collect();
+    parts.len() >= 2
+        && parts[0] == "1"
+        && parts
+            .iter()
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+// 例如 I6、M5、G8_pre1
+fn is_release(value: &str) -> bool {
+    let base = value.split("_pre").next().unwrap_or_default();
+    let mut chars = base.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_uppercase())
+        && base.len() > 1
+        && chars.all(|c| c.is_ascii_digit())
+}
+
+// 读取 class 文件常量池中的所有 UTF8 字符串
+fn class_strings(class: &[u8]) -> Vec<String> {
+    let mut strings = Vec::new();
+
+    if class.len() < 10 || class[0..4] != [0xCA, 0xFE, 0xBA, 0xBE] {
+        return strings;
+    }
+
+    let read_u16 = |at: usize| -> Option<usize> {
+        Some(u16::from_be_bytes([*class.get(at)?, *class.get(at + 1)?]) as usize)
+    };
+
+    let Some(count) = read_u16(8) else {
+        return strings;
+    };
+    let mut offset = 10;
+    let mut index = 1;
+
+    while index < count {
+        let Some(&tag) = class.get(offset) else {
+            break;
+        };
+
+        let size = match tag {
+            1 => {
+                let Some(length) = read_u16(offset + 1) else {
+                    break;
+                };
+                if let Some(bytes) = class.get(offset + 3..offset + 3 + length) {
+                    strings.push(String::from_utf8_lossy(bytes).to_string());
+                }
+                3 + length
+            }
+            7 | 8 | 16 | 19 | 20 => 3,
+            15 => 4,
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => 5,
+            // long 和 double 占用两个常量池位置
+            5 | 6 => {
+                index += 1;
+                9
+            }
+            _ => break,
+        };
+
+        offset += size;
+        index += 1;
+    }
+
+    strings
+}
+
+// 检查 OptiFine 能否安装到使用指定加载器的目标版本上
+pub fn check_compatibility(
+    optifine: &OptiFine,
+    game_version: &str,
+    loader: Option<Loader>,
+) -> Result<(), String> {
+    if optifine.minecraft != game_version {
+        return Err(format!(
+            "OptiFine {} is for Minecraft {}, not {}",
+            optifine.edition, optifine.minecraft, game_version
+        ));
+    }
+
+    match loader {
+        Some(loader @ (Loader::Fabric | Loader::Quilt)) => Err(format!(
+            "OptiFine cannot run on {:?} without OptiFabric",
+            loader
+        )),
+        Some(Loader::NeoForge) => Err("OptiFine does not support NeoForge".to_string()),
+        _ => Ok(()),
+    }
+}
+
+// 安装 OptiFine：
+// 原版上生成继承原版的独立版本；Forge 版本上作为 mod 放入该版本游戏目录下的 mods 目录，
+// 版本隔离时为 versions/<id>/mods，不影响同一根目录下的其他版本
+pub fn install_optifine(
+    game_dir: &Path,
+    optifine_jar: &Path,
+    base_version: &str,
+    java: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let versions_dir = &game_dir.join("versions");
+    let optifine = OptiFine::open(optifine_jar)?;
+    let base = parse::version::load(versions_dir, base_version)?;
+
+    let game_version = base.jar.clone().unwrap_or(base.id.clone());
+    let loader = Loader::detect(&base).map(|(loader, _)| loader);
+
+    check_compatibility(&optifine, &game_version, loader)?;
+
+    if loader == Some(Loader::Forge) {
+        let mods_dir = path::game_directory(game_dir, &base.id).join("mods");
+        std::fs::create_dir_all(&mods_dir)?;
+
+        let file = optifine_jar
+            .file_name()
+            .ok_or("Invalid OptiFine jar path")?;
+        std::fs::copy(optifine_jar, mods_dir.join(file))?;
+
+        return Ok(base.id);
+    }
+
+    let minecraft_jar = versions_dir
+        .join(&game_version)
+        .join(format!("{}.jar", game_version));
+
+    if !minecraft_jar.exists() {
+        return Err(format!("Version: {} not found", game_version).into());
+    }
+
+    let libraries_dir = &game_dir.join("libraries");
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(optifine_jar)?)?;
+
+    // 新版本的 OptiFine 自带 launchwrapper-of，旧版本使用 Mojang 的 launchwrapper
+    let launch_wrapper = match archive.by_name("launchwrapper-of.txt") {
+        Ok(mut entry) => {
+            let mut version = String::new();
+            entry.read_to_string(&mut version)?;
+            Some(version.trim().to_string())
+        }
+        Err(_) => None,
+    };
+
+    let launch_wrapper = match launch_wrapper {
+        Some(version) => {
+            let name = format!("launchwrapper-of-{}.jar", version);
+            let path = libraries_dir
+                .join("optifine")
+                .join("launchwrapper-of")
+                .join(&version)
+                .join(&name);
+            std::fs::create_dir_all(path.parent().unwrap())?;
+            std::io::copy(
+                &mut archive.by_name(&name)?,
+                &mut std::fs::File::create(&path)?,
+            )?;
+            format!("optifine:launchwrapper-of:{}", version)
+        }
+        None => "net.minecraft:launchwrapper:1.12".to_string(),
+    };
+
+    let library_path = libraries_dir.join(
+        model::library::maven_path(&optifine.library_name())
+            .ok_or("Invalid OptiFine library name")?,
+    );
+    std::fs::create_dir_all(library_path.parent().unwrap())?;
+
+    // 安装器版本需要用 Patcher 对原版 jar 打补丁，否则 jar 本身即为 library
+    if archive.by_name("optifine/Patcher.class").is_ok() {
+        println!("执行 OptiFine Patcher");
+
+        let status = std::process::Command::new(java)
+            .arg("-cp")
+            .arg(optifine_jar)
+            .arg("optifine.Patcher")
+            .arg(&minecraft_jar)
+            .arg(optifine_jar)
+            .arg(&library_path)
+            .status()?;
+
+        if !status.success() {
+            return Err(format!("OptiFine Patcher exited with code: {:?}", status.code()).into());
+        }
+    } else {
+        std::fs::copy(optifine_jar, &library_path)?;
+    }
+
+    let mut version = serde_json::json!({
+        "id": optifine.version_id(),
+        "inheritsFrom": base.id,
+        "type": base.type_,
+        "time": base.time,
+        "releaseTime": base.release_time,
+        "mainClass": LAUNCH_WRAPPER_MAIN,
+        "libraries": [
+            {"name": optifine.library_name()},
+            {"name": launch_wrapper},
+        ],
+    });
+
+    // 1.13 之后使用 arguments，之前需要在原参数后追加 tweakClass
+    if base.arguments.is_some() {
+        version["arguments"] = serde_json::json!({"game": ["--tweakClass", TWEAK_CLASS]});
+    } else {
+        version["minecraftArguments"] = serde_json::json!(format!(
+            "{} --tweakClass {}",
+            base.minecraft_arguments.unwrap_or_default(),
+            TWEAK_CLASS
+        ));
+    }
+
+    let id = optifine.version_id();
+    let version_dir = versions_dir.join(&id);
+    std::fs::create_dir_all(&version_dir)?;
+    std::fs::write(
+        version_dir.join(format!("{}.json", id)),
+        serde_json::to_string_pretty(&version)?,
+    )?;
+
+    // 旧版本的 launchwrapper 需要从 Mojang 仓库下载
+    crate::Download::download(
+        &parse::version::load(versions_dir, &id)?.libraries,
+        game_dir,
+    )?;
+
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    // 生成只包含 UTF8 常量的 class 文件
+    fn class(strings: &[&str]) -> Vec<u8> {
+        let mut class = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 52];
+        class.extend(((strings.len() + 1) as u16).to_be_bytes());
+        for string in strings {
+            class.push(1);
+            class.extend((string.len() as u16).to_be_bytes());
+            class.extend(string.as_bytes());
+        }
+        class
+    }
+
+    fn optifine_jar(path: &Path, class_strings: &[&str]) {
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+
+        zip.start_file("net/optifine/Config.class", options)
+            .unwrap();
+        zip.write_all(&class(class_strings)).unwrap();
+        zip.start_file("launchwrapper-of.txt", options).unwrap();
+        zip.write_all(b"2.3").unwrap();
+        zip.start_file("launchwrapper-of-2.3.jar", options).unwrap();
+        zip.write_all(b"launchwrapper").unwrap();
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_open() {
+        let dir = &std::env::temp_dir().join("mcl-download-test-optifine-open");
+        std::fs::create_dir_all(dir).unwrap();
+
+        let jar = dir.join("optifine.jar");
+        optifine_jar(
+            &jar,
+            &["OptiFine", "1.20.1", "HD_U", "I6", "java/lang/Object"],
+        );
+        assert_eq!(
+            OptiFine {
+                minecraft: "1.20.1".to_string(),
+                edition: "HD_U_I6".to_string()
+            },
+            OptiFine::open(&jar).unwrap()
+        );
+
+        let jar = dir.join("OptiFine_1.12.2_HD_U_G5.jar");
+        optifine_jar(&jar, &[]);
+        assert_eq!(
+            "1.12.2-OptiFine_HD_U_G5",
+            OptiFine::open(&jar).unwrap().version_id()
+        );
+    }
+
+    #[test]
+    fn test_check_compatibility() {
+        let optifine = OptiFine {
+            minecraft: "1.20.1".to_string(),
+            edition: "HD_U_I6".to_string(),
+        };
+
+        assert!(check_compatibility(&optifine, "1.20.1", None).is_ok());
+        assert!(check_compatibility(&optifine, "1.20.1", Some(Loader::Forge)).is_ok());
+        assert!(check_compatibility(&optifine, "1.21", None).is_err());
+        assert!(check_compatibility(&optifine, "1.20.1", Some(Loader::Fabric)).is_err());
+        assert!(check_compatibility(&optifine, "1.20.1", Some(Loader::NeoForge)).is_err());
+    }
+
+    #[test]
+    fn test_install_optifine() {
+        let dir = &std::env::temp_dir().join("mcl-download-test-optifine");
+        let _ = std::fs::remove_dir_all(dir);
+        let game_dir = &dir.join(".minecraft");
+
+        let vanilla_dir = game_dir.join("versions").join("1.20.1");
+        std::fs::create_dir_all(&vanilla_dir).unwrap();
+        std::fs::write(vanilla_dir.join("1.20.1.jar"), b"minecraft").unwrap();
+        std::fs::write(
+            vanilla_dir.join("1.20.1.json"),
+            r#"{"assetIndex": {"id": "5", "sha1": "", "size": 0, "totalSize": 0, "url": ""}, "downloads": {"client": {"sha1": "", "size": 0, "url": ""}}, "id": "1.20.1", "libraries": [], "arguments": {"game": [], "jvm": []}, "mainClass": "net.minecraft.client.main.Main", "releaseTime": "2023-06-12T13:25:51+00:00", "time": "2023-06-12T13:25:51+00:00", "type": "release"}"#,
+        )
+        .unwrap();
+
+        let jar = dir.join("OptiFine_1.20.1_HD_U_I6.jar");
+        optifine_jar(&jar, &["1.20.1", "HD_U", "I6"]);
+
+        let id = install_optifine(game_dir, &jar, "1.20.1", "java")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!("1.20.1-OptiFine_HD_U_I6", id);
+
+        let version = parse::version::load(&game_dir.join("versions"), &id).unwrap();
+        assert_eq!(LAUNCH_WRAPPER_MAIN, version.main_class);
+        assert_eq!(Some("1.20.1".to_string()), version.jar);
+        assert!(game_dir
+            .join("libraries/optifine/OptiFine/1.20.1_HD_U_I6/OptiFine-1.20.1_HD_U_I6.jar")
+            .exists());
+        assert!(game_dir
+            .join("libraries/optifine/launchwrapper-of/2.3/launchwrapper-of-2.3.jar")
+            .exists());
+
+        // 隔离的 Forge 版本中作为 mod 放入该版本自己的 mods 目录
+        let forge_id = "1.20.1-forge-47.2.0";
+        let forge_dir = game_dir.join("versions").join(forge_id);
+        std::fs::create_dir_all(&forge_dir).unwrap();
+        std::fs::write(
+            forge_dir.join(format!("{}.json", forge_id)),
+            r#"{"id": "1.20.1-forge-47.2.0", "inheritsFrom": "1.20.1", "libraries": [{"name": "net.minecraftforge:forge:1.20.1-47.2.0:universal"}], "mainClass": "cpw.mods.bootstraplauncher.BootstrapLauncher"}"#,
+        )
+        .unwrap();
+        let mut isolation = path::IsolationSettings::default();
+        isolation.versions.insert(forge_id.to_string(), true);
+        isolation.save(game_dir).unwrap();
+
+        let id = install_optifine(game_dir, &jar, forge_id, "java")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(forge_id, id);
+        assert!(forge_dir.join("mods/OptiFine_1.20.1_HD_U_I6.jar").exists());
+        assert!(!game_dir.join("mods").exists());
+    }
+}
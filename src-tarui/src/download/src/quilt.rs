@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use model::fabric::LoaderVersion;
+use parse::Parse;
+
+use crate::{fabric::install_profile, get};
+
+pub const QUILT_META: &str = "https://meta.quiltmc.org";
+
+// Quilt meta 接口，返回的结构与 Fabric meta 一致
+pub struct QuiltMeta {
+    pub base_url: String,
+}
+
+impl Default for QuiltMeta {
+    fn default() -> Self {
+        Self::new(QUILT_META)
+    }
+}
+
+impl QuiltMeta {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    // 获取与游戏版本兼容的加载器版本列表
+    pub fn loader_versions(
+        &self,
+        game_version: &str,
+    ) -> Result<Vec<LoaderVersion>, Box<dyn std::error::Error>> {
+        let url = format!("{}/v3/versions/loader/{}", self.base_url, game_version);
+        let text = get(&url)?.error_for_status()?.text()?;
+        Ok(Vec::<LoaderVersion>::parse(&text)?)
+    }
+
+    // 安装加载器，library 从 Quilt Maven 下载，原版本体需要另外安装
+    pub fn install(
+        &self,
+        game_dir: &Path,
+        game_version: &str,
+        loader_version: &str,
+    ) -> Result<String, Box<dyn std::error::Error>> {
+        let url = format!(
+            "{}/v3/versions/loader/{}/{}/profile/json",
+            self.base_url, game_version, loader_version
+        );
+        let text = get(&url)?.error_for_status()?.text()?;
+
+        println!("安装 Quilt {}", loader_version);
+        install_profile(game_dir, &text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_install() {
+        let fixture = crate::fixture::Fixture::new();
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/v3/versions/loader/1.21".to_string(),
+            br#"[{"loader": {"separator": ".", "build": 0, "maven": "org.quiltmc:quilt-loader:0.26.0", "version": "0.26.0"}, "hashed": {"maven": "org.quiltmc:hashed:1.21", "version": "1.21"}, "intermediary": {"maven": "net.fabricmc:intermediary:1.21", "version": "1.21"}}]"#.to_vec(),
+        );
+        routes.insert(
+            "/v3/versions/loader/1.21/0.26.0/profile/json".to_string(),
+            format!(
+                r#"{{"id": "quilt-loader-0.26.0-1.21", "inheritsFrom": "1.21", "type": "release", "mainClass": "org.quiltmc.loader.impl.launch.knot.KnotClient", "arguments": {{"game": []}}, "libraries": [{{"name": "org.quiltmc:quilt-loader:0.26.0", "url": "{}/repository/release/"}}], "releaseTime": "2024-06-13T08:24:03+00:00", "time": "2024-06-13T08:24:03+00:00"}}"#,
+                fixture.address
+            )
+            .into_bytes(),
+        );
+        routes.insert(
+            "/repository/release/org/quiltmc/quilt-loader/0.26.0/quilt-loader-0.26.0.jar"
+                .to_string(),
+            b"quilt-loader".to_vec(),
+        );
+        let address = fixture.serve(routes);
+
+        let meta = QuiltMeta::new(&address);
+
+        let versions = meta
+            .loader_versions("1.21")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!("0.26.0", versions[0].loader.version);
+        assert_eq!(None, versions[0].loader.stable);
+
+        let game_dir = &std::env::temp_dir().join("mcl-download-test-quilt");
+        let _ = std::fs::remove_dir_all(game_dir);
+
+        let id = meta
+            .install(game_dir, "1.21", "0.26.0")
+            .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!("quilt-loader-0.26.0-1.21", id);
+        assert!(game_dir
+            .join("libraries")
+            .join("org/quiltmc/quilt-loader/0.26.0/quilt-loader-0.26.0.jar")
+            .exists());
+    }
+}
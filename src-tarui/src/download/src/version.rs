@@ -1,7 +1,20 @@
 use std::path::Path;
 
 use crate::{get, Download};
-use model::version_manifest::Version;
+use model::version_manifest::{Version, VersionManifest};
+
+pub const VERSION_MANIFEST: &str = "https://launchermeta.mojang.com/mc/game/version_manifest.json";
+
+// 按 id 从版本清单中查找并安装原版
+pub fn install(game_dir: &Path, id: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = get(VERSION_MANIFEST)?.json::<VersionManifest>()?;
+    let version = manifest
+        .versions
+        .iter()
+        .find(|v| v.id.eq(id))
+        .ok_or(format!("Version: {} not found", id))?;
+    version.download(game_dir)
+}
 
 // 为Version结构体实现Download特性，用于下载Minecraft游戏版本
 impl Download for Version {
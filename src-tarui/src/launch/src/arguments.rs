@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+
+use model::version::{Argument, ArgumentRule, ArgumentValue};
+
+// 当前系统在版本配置中的名称
+pub fn os_name() -> &'static str {
+    if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "osx"
+    } else {
+        "linux"
+    }
+}
+
+// 规则为空时允许，否则以最后一条匹配的规则为准
+pub fn rules_allowed(rules: &[ArgumentRule], features: &[&str]) -> bool {
+    let mut allowed = rules.is_empty();
+
+    for rule in rules {
+        if rule_matches(rule, features) {
+            allowed = rule.action == "allow";
+        }
+    }
+
+    allowed
+}
+
+fn rule_matches(rule: &ArgumentRule, features: &[&str]) -> bool {
+    if let Some(os) = &rule.os {
+        if let Some(name) = &os.name {
+            if name != os_name() {
+                return false;
+            }
+        }
+
+        if let Some(arch) = &os.arch {
+            if arch == "x86" && !cfg!(target_arch = "x86") {
+                return false;
+            }
+        }
+    }
+
+    if let Some(required) = &rule.features {
+        for (feature, value) in required {
+            if features.contains(&feature.as_str()) != *value {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+// 按规则展开参数，并替换其中的 ${变量}
+pub fn resolve(
+    arguments: &[Argument],
+    features: &[&str],
+    variables: &HashMap<&str, String>,
+) -> Vec<String> {
+    arguments
+        .iter()
+        .flat_map(|argument| match argument {
+            Argument::Plain(value) => vec![value.clone()],
+            Argument::Conditional { rules, value } => {
+                if !rules_allowed(rules, features) {
+                    return Vec::new();
+                }
+
+                match value {
+                    ArgumentValue::Single(value) => vec![value.clone()],
+                    ArgumentValue::Multiple(values) => values.clone(),
+                }
+            }
+        })
+        .map(|argument| substitute(&argument, variables))
+        .collect()
+}
+
+// 1.20 起的版本配置通过这个特性启用 --quickPlayMultiplayer
+pub const QUICK_PLAY_MULTIPLAYER: &str = "is_quick_play_multiplayer";
+
+// 服务器地址填入的变量，官方版本配置使用 ${quick_play_multiplayer}，同时兼容驼峰写法
+pub const QUICK_PLAY_MULTIPLAYER_VARIABLES: [&str; 2] =
+    ["quick_play_multiplayer", "quickPlayMultiplayer"];
+
+// 参数中是否有规则要求指定的特性，用于判断版本支持哪些启动参数
+pub fn supports_feature(arguments: &[Argument], feature: &str) -> bool {
+    arguments.iter().any(|argument| match argument {
+        Argument::Plain(_) => false,
+        Argument::Conditional { rules, .. } => rules.iter().any(|rule| {
+            rule.features
+                .as_ref()
+                .is_some_and(|features| features.contains_key(feature))
+        }),
+    })
+}
+
+// 拆分服务器地址与端口，没有端口时为默认的 25565，IPv6 地址需要写在 [] 中
+pub fn split_server_address(address: &str) -> (String, u16) {
+    let address = address.trim();
+    if let Some(rest) = address.strip_prefix('[') {
+        if let Some((host, port)) = rest.split_once(']') {
+            let port = port.strip_prefix(':').and_then(|port| port.parse().ok());
+            return (host.to_string(), port.unwrap_or(25565));
+        }
+    }
+    match address.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => {
+            (host.to_string(), port.parse().unwrap_or(25565))
+        }
+        _ => (address.to_string(), 25565),
+    }
+}
+
+pub fn substitute(argument: &str, variables: &HashMap<&str, String>) -> String {
+    let mut argument = argument.to_string();
+
+    for (key, value) in variables {
+        argument = argument.replace(&format!("${{{}}}", key), value);
+    }
+
+    argument
+}
+
+#[cfg(test)]
+mod tests {
+    use model::version::Arguments;
+    use parse::Parse;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let arguments = Arguments::parse(
+            r#"{"game": ["--username", "${auth_player_name}", {"rules": [{"action": "allow", "features": {"is_demo_user": true}}], "value": "--demo"}, {"rules": [{"action": "allow", "features": {"has_custom_resolution": true}}], "value": ["--width", "${resolution_width}"]}], "jvm": [{"rules": [{"action": "allow", "os": {"name": "osx"}}], "value": ["-XstartOnFirstThread"]}, {"rules": [{"action": "allow", "os": {"name": "windows"}}], "value": "-XX:HeapDumpPath=MojangTricksIntelDriversForPerformance_javaw.exe_minecraft.exe.heapdump"}, {"rules": [{"action": "allow", "os": {"arch": "x86"}}], "value": "-Xss1M"}, "-Djava.library.path=${natives_directory}", "-cp", "${classpath}"]}"#,
+        )
+        .unwrap_or_else(|err| panic!("{:?}", err));
+
+        let mut variables = HashMap::new();
+        variables.insert("auth_player_name", "Player".to_string());
+        variables.insert("resolution_width", "854".to_string());
+        variables.insert("natives_directory", "natives".to_string());
+        variables.insert("classpath", "a.jar".to_string());
+
+        assert_eq!(
+            vec!["--username", "Player"],
+            resolve(&arguments.game, &[], &variables)
+        );
+        assert_eq!(
+            vec!["--username", "Player", "--width", "854"],
+            resolve(&arguments.game, &["has_custom_resolution"], &variables)
+        );
+
+        let jvm = resolve(&arguments.jvm, &[], &variables);
+        assert_eq!(
+            ["-Djava.library.path=natives", "-cp", "a.jar"],
+            jvm[jvm.len() - 3..]
+        );
+        assert_eq!(
+            cfg!(target_os = "macos"),
+            jvm.contains(&"-XstartOnFirstThread".to_string())
+        );
+    }
+
+    #[test]
+    fn test_quick_play() {
+        // 1.20.1 客户端版本配置中 arguments.game 的 Quick Play 部分
+        let arguments = Arguments::parse(
+            r#"{"game": ["--username", "${auth_player_name}", {"rules": [{"action": "allow", "features": {"has_custom_resolution": true}}], "value": ["--width", "${resolution_width}", "--height", "${resolution_height}"]}, {"rules": [{"action": "allow", "features": {"has_quick_plays_support": true}}], "value": ["--quickPlayPath", "${quickPlayPath}"]}, {"rules": [{"action": "allow", "features": {"is_quick_play_singleplayer": true}}], "value": ["--quickPlaySingleplayer", "${quickPlaySingleplayer}"]}, {"rules": [{"action": "allow", "features": {"is_quick_play_multiplayer": true}}], "value": ["--quickPlayMultiplayer", "${quick_play_multiplayer}"]}, {"rules": [{"action": "allow", "features": {"is_quick_play_realms": true}}], "value": ["--quickPlayRealms", "${quickPlayRealms}"]}]}"#,
+        )
+        .unwrap_or_else(|err| panic!("{:?}", err));
+        assert!(supports_feature(&arguments.game, QUICK_PLAY_MULTIPLAYER));
+        assert!(!supports_feature(&arguments.game, "is_demo_user"));
+
+        let mut variables = HashMap::new();
+        variables.insert("auth_player_name", "Player".to_string());
+        for name in QUICK_PLAY_MULTIPLAYER_VARIABLES {
+            variables.insert(name, "localhost:25566".to_string());
+        }
+        assert_eq!(
+            vec![
+                "--username",
+                "Player",
+                "--quickPlayMultiplayer",
+                "localhost:25566"
+            ],
+            resolve(&arguments.game, &[QUICK_PLAY_MULTIPLAYER], &variables)
+        );
+        assert_eq!(
+            "localhost:25566",
+            substitute("${quickPlayMultiplayer}", &variables)
+        );
+    }
+
+    #[test]
+    fn test_split_server_address() {
+        assert_eq!(
+            ("localhost".to_string(), 25565),
+            split_server_address("localhost")
+        );
+        assert_eq!(
+            ("mc.example.com".to_string(), 25566),
+            split_server_address(" mc.example.com:25566 ")
+        );
+        assert_eq!(("::1".to_string(), 25565), split_server_address("::1"));
+        assert_eq!(
+            ("::1".to_string(), 25570),
+            split_server_address("[::1]:25570")
+        );
+        assert_eq!(
+            ("host".to_string(), 25565),
+            split_server_address("host:abc")
+        );
+    }
+}
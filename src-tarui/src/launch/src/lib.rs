@@ -0,0 +1,225 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use download::LibaryAllowed;
+use path::Instance;
+
+pub mod arguments;
+
+pub const LAUNCHER_NAME: &str = "rmcl";
+pub const LAUNCHER_VERSION: &str = "0.1.0";
+
+// 启动一个已安装版本所需的信息
+pub struct LaunchOptions {
+    // 根目录，libraries、assets 与 versions 所在的目录
+    pub game_dir: PathBuf,
+    // 游戏运行目录 --gameDir，为空时使用 game_dir
+    pub game_directory: Option<PathBuf>,
+    pub version_id: String,
+    pub username: String,
+    pub java: String,
+    // 额外的 JVM 参数，放在版本配置的参数之前
+    pub jvm_args: Vec<String>,
+    // 内存，单位 MB
+    pub min_memory: Option<u32>,
+    pub max_memory: Option<u32>,
+    // 启动后直接进入的服务器地址，可以带端口
+    pub server: Option<String>,
+}
+
+impl LaunchOptions {
+    pub fn new(game_dir: &Path, version_id: &str, username: &str) -> Self {
+        Self {
+            game_dir: game_dir.to_path_buf(),
+            game_directory: None,
+            version_id: version_id.to_string(),
+            username: username.to_string(),
+            java: "java".to_string(),
+            jvm_args: Vec::new(),
+            min_memory: None,
+            max_memory: None,
+            server: None,
+        }
+    }
+
+    // 使用实例的目录与启动设置
+    pub fn from_instance(game_dir: &Path, instance: &Instance, username: &str) -> Self {
+        let mut options = Self::new(game_dir, &instance.id, username);
+        options.game_directory = Some(instance.game_dir(game_dir));
+        if let Some(java) = &instance.java {
+            options.java = java.clone();
+        }
+        options.jvm_args = instance.jvm_args.clone();
+        options.min_memory = instance.min_memory;
+        options.max_memory = instance.max_memory;
+        options
+    }
+}
+
+// 解析版本配置（包括 inheritsFrom）、解压 natives 并生成启动命令
+pub fn command(options: &LaunchOptions) -> Result<Command, Box<dyn std::error::Error>> {
+    let game_dir = &options.game_dir;
+    let libraries_dir = game_dir.join("libraries");
+    let assets_dir = game_dir.join("assets");
+    let versions_dir = game_dir.join("versions");
+    let version_dir = versions_dir.join(&options.version_id);
+    let natives_dir = version_dir.join("natives");
+    let config_path = version_dir.join(format!("{}.json", options.version_id));
+    let game_directory = options.game_directory.as_ref().unwrap_or(game_dir);
+
+    if !config_path.exists() {
+        return Err(format!("Version: {} not found", options.version_id).into());
+    }
+
+    let version = parse::version::load(&versions_dir, &options.version_id)?;
+
+    let jar = version.jar.as_deref().unwrap_or(&version.id);
+    let version_path = versions_dir.join(jar).join(format!("{}.jar", jar));
+
+    if !version_path.exists() {
+        return Err(format!("Version: {} not found", jar).into());
+    }
+
+    if !natives_dir.exists() {
+        std::fs::create_dir_all(&natives_dir)?;
+    }
+
+    for library in &version.libraries {
+        if library.allowed() && library.name.contains("natives") {
+            extract_jar(
+                &libraries_dir.join(&library.downloads.artifact.path),
+                &natives_dir,
+            )?;
+        }
+    }
+
+    let separator = if cfg!(windows) { ";" } else { ":" };
+
+    let mut classpath: Vec<String> = version
+        .libraries
+        .iter()
+        .filter(|library| library.allowed())
+        .map(|library| {
+            libraries_dir
+                .join(&library.downloads.artifact.path)
+                .display()
+                .to_string()
+        })
+        .collect();
+    classpath.push(version_path.display().to_string());
+
+    let mut variables = HashMap::new();
+    variables.insert("auth_player_name", options.username.clone());
+    variables.insert("version_name", version.id.clone());
+    variables.insert("game_directory", game_directory.display().to_string());
+    variables.insert("assets_root", assets_dir.display().to_string());
+    variables.insert("game_assets", assets_dir.display().to_string());
+    variables.insert("assets_index_name", version.asset_index.id.clone());
+    variables.insert("auth_uuid", "00000000000000000000000000000000".to_string());
+    variables.insert("auth_access_token", "0".to_string());
+    variables.insert("auth_session", "0".to_string());
+    variables.insert("auth_xuid", "0".to_string());
+    variables.insert("clientid", "0".to_string());
+    variables.insert("user_type", "legacy".to_string());
+    variables.insert("user_properties", "{}".to_string());
+    variables.insert(
+        "version_type",
+        format!("{} {}", LAUNCHER_NAME.to_uppercase(), LAUNCHER_VERSION),
+    );
+    variables.insert("natives_directory", natives_dir.display().to_string());
+    variables.insert("launcher_name", LAUNCHER_NAME.to_string());
+    variables.insert("launcher_version", LAUNCHER_VERSION.to_string());
+    variables.insert("classpath", classpath.join(separator));
+    variables.insert("classpath_separator", separator.to_string());
+    variables.insert("library_directory", libraries_dir.display().to_string());
+
+    let arguments = version.arguments.unwrap_or_default();
+
+    // 1.20 起使用 --quickPlayMultiplayer 进入服务器，之前的版本使用 --server 与 --port
+    let quick_play = options.server.is_some()
+        && arguments::supports_feature(&arguments.game, arguments::QUICK_PLAY_MULTIPLAYER);
+    let mut features = Vec::new();
+    if let Some(server) = options.server.as_ref().filter(|_| quick_play) {
+        features.push(arguments::QUICK_PLAY_MULTIPLAYER);
+        for name in arguments::QUICK_PLAY_MULTIPLAYER_VARIABLES {
+            variables.insert(name, server.trim().to_string());
+        }
+    }
+
+    // 1.13 之前的版本没有 jvm 参数
+    let jvm = if arguments.jvm.is_empty() {
+        vec![
+            format!("-Djava.library.path={}", natives_dir.display()),
+            "-cp".to_string(),
+            variables["classpath"].clone(),
+        ]
+    } else {
+        arguments::resolve(&arguments.jvm, &[], &variables)
+    };
+
+    let mut game: Vec<String> = if arguments.game.is_empty() {
+        version
+            .minecraft_arguments
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(|argument| arguments::substitute(argument, &variables))
+            .collect()
+    } else {
+        arguments::resolve(&arguments.game, &features, &variables)
+    };
+    if let Some(server) = options.server.as_ref().filter(|_| !quick_play) {
+        let (host, port) = arguments::split_server_address(server);
+        game.extend([
+            "--server".to_string(),
+            host,
+            "--port".to_string(),
+            port.to_string(),
+        ]);
+    }
+
+    let mut command = Command::new(&options.java);
+    std::fs::create_dir_all(game_directory)?;
+    command.current_dir(game_directory);
+    if let Some(min_memory) = options.min_memory {
+        command.arg(format!("-Xms{}M", min_memory));
+    }
+    if let Some(max_memory) = options.max_memory {
+        command.arg(format!("-Xmx{}M", max_memory));
+    }
+    command
+        .args(&options.jvm_args)
+        .args(jvm)
+        .arg(&version.main_class)
+        .args(game);
+
+    Ok(command)
+}
+
+pub fn extract_jar(jar: &Path, dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut archive = zip::ZipArchive::new(std::fs::File::open(jar)?)?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_file() && !entry.name().contains("META-INF") {
+            let mut name = entry.name();
+
+            if name.contains('/') {
+                name = &name[entry.name().rfind('/').unwrap() + 1..];
+            }
+
+            let path = dir.join(name);
+
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+
+            let mut file = std::fs::File::create(&path)?;
+
+            std::io::copy(&mut entry, &mut file)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,9 +1,8 @@
 use std::{default, path::Path};
 
 use clap::{arg, builder::Str, Command};
-use download::{get, Download, LibaryAllowed};
-use model::version::Version;
-use parse::{version, Parse};
+use download::{get, loader::Loader, optifine, Download};
+use launch::LaunchOptions;
 
 fn cli() -> Command {
     Command::new("rmcl")
@@ -28,12 +27,60 @@ fn cli() -> Command {
             Command::new("download")
                 .about("Download Game")
                 .arg(arg!(<VERSION> "Game version"))
+                .arg(
+                    arg!(-l --loader <LOADER> "Mod loader")
+                        .value_parser(["fabric", "quilt", "forge", "neoforge"])
+                        .require_equals(true),
+                )
+                .arg(arg!(--"loader-version" <LOADER_VERSION> "Mod loader version").require_equals(true))
+                .arg_required_else_help(true),
+        )
+        .subcommand(
+            Command::new("optifine")
+                .about("Install OptiFine from a downloaded jar")
+                .arg(arg!(<JAR> "OptiFine jar"))
+                .arg(arg!(<VERSION> "Installed version to install onto"))
                 .arg_required_else_help(true),
         )
+        .subcommand(
+            Command::new("instance")
+                .about("Manage instances")
+                .subcommand_required(true)
+                .arg_required_else_help(true)
+                .subcommand(Command::new("list").about("List instances"))
+                .subcommand(
+                    Command::new("create")
+                        .about("Create an instance from an installed version")
+                        .arg(arg!(<NAME> "Instance name"))
+                        .arg(arg!(<VERSION> "Installed version"))
+                        .arg_required_else_help(true),
+                )
+                .subcommand(
+                    Command::new("rename")
+                        .about("Rename an instance")
+                        .arg(arg!(<ID> "Instance id"))
+                        .arg(arg!(<NAME> "New name"))
+                        .arg_required_else_help(true),
+                )
+                .subcommand(
+                    Command::new("clone")
+                        .about("Clone an instance")
+                        .arg(arg!(<ID> "Instance id"))
+                        .arg(arg!(<NAME> "New instance name"))
+                        .arg_required_else_help(true),
+                )
+                .subcommand(
+                    Command::new("delete")
+                        .about("Move an instance to the trash")
+                        .arg(arg!(<ID> "Instance id"))
+                        .arg_required_else_help(true),
+                ),
+        )
         .subcommand(
             Command::new("launch")
                 .about("Launch Game")
                 .arg(arg!(<VERSION> "Game version"))
+                .arg(arg!(--server <ADDRESS> "Join a server after launching").require_equals(true))
                 .arg_required_else_help(true),
         )
 }
@@ -44,6 +91,8 @@ fn main() {
     match matches.subcommand() {
         Some(("search", sub_matches)) => search(sub_matches),
         Some(("download", sub_matches)) => download(sub_matches),
+        Some(("optifine", sub_matches)) => optifine(sub_matches),
+        Some(("instance", sub_matches)) => instance(sub_matches),
         Some(("launch", sub_matches)) => launch(sub_matches),
         _ => unreachable!(),
     }
@@ -83,106 +132,109 @@ fn download(sub_matches: &clap::ArgMatches) {
     println!("Game Dir:{}", game_dir.display());
     println!("Version:{}", version);
     if let Some(version) = versions.iter().find(|v| v.id.eq(version)) {
-        version.download(&game_dir).unwrap_or_else(|err| {
+        if let Err(err) = version.download(&game_dir) {
             eprintln!("Download Error:{}", err);
-        });
+            return;
+        }
     } else {
         eprintln!("Version:{} not found", version);
+        return;
+    }
+
+    if let Some(loader) = sub_matches.get_one::<String>("loader") {
+        let loader_version = sub_matches.get_one::<String>("loader-version");
+        match loader
+            .parse::<Loader>()
+            .unwrap()
+            .install(&game_dir, version, loader_version.map(|v| v.as_str()), "java")
+        {
+            Ok(id) => println!("Installed:{}", id),
+            Err(err) => eprintln!("Install Error:{}", err),
+        }
     }
 }
 
-fn launch(sub_matches: &clap::ArgMatches) {
+fn optifine(sub_matches: &clap::ArgMatches) {
     let game_dir = std::env::current_dir().unwrap().join(".minecraft");
-    let libraries_dir = game_dir.join("libraries");
-    let assets_dir = game_dir.join("assets");
+    let jar = sub_matches.get_one::<String>("JAR").unwrap();
     let version = sub_matches.get_one::<String>("VERSION").unwrap();
-    let version_dir = game_dir.join("versions").join(version);
-    let natives_dir = version_dir.join("natives");
-    let config_path = version_dir.join(format!("{}.json", version));
-    let version_path = version_dir.join(format!("{}.jar", version));
-
-    if !version_path.exists() || !config_path.exists() {
-        eprintln!("Version:{} not found", version);
-        return;
-    }
 
-    let version = &Version::parse(&std::fs::read_to_string(&config_path).unwrap()).unwrap();
-
-    if !natives_dir.exists() {
-        std::fs::create_dir_all(&natives_dir).unwrap();
-    }
-
-    for library in &version.libraries {
-        if library.allowed() && library.name.contains("natives") {
-            extract_jar(
-                &libraries_dir.join(&library.downloads.artifact.path),
-                &natives_dir,
-            );
-        }
+    match optifine::install_optifine(&game_dir, Path::new(jar), version, "java") {
+        Ok(id) => println!("Installed:{}", id),
+        Err(err) => eprintln!("Install Error:{}", err),
     }
-
-    let classpath = format!(
-        "{}{}",
-        &version
-            .libraries
-            .iter()
-            .map(|library| {
-                format!(
-                    "{}{}",
-                    libraries_dir
-                        .join(&library.downloads.artifact.path)
-                        .display(),
-                    if cfg!(windows) { ";" } else { ":" }
-                )
-            })
-            .collect::<String>(),
-        version_path.display()
-    );
-
-    std::process::Command::new("java")
-        .current_dir(&game_dir)
-        .arg(format!("-Djava.library.path={}", natives_dir.display()))
-        .arg("-cp")
-        .arg(classpath)
-        .arg(&version.main_class)
-        .arg("--username")
-        .arg("Enaium")
-        .arg("--version")
-        .arg(&version.id)
-        .arg("--gameDir")
-        .arg(game_dir)
-        .arg("--assetsDir")
-        .arg(assets_dir)
-        .arg("--assetIndex")
-        .arg(&version.asset_index.id)
-        .arg("--accessToken")
-        .arg("0")
-        .arg("--versionType")
-        .arg("RMCL 0.1.0")
-        .status()
-        .unwrap();
 }
 
-fn extract_jar(jar: &Path, dir: &Path) {
-    let mut archive = zip::ZipArchive::new(std::fs::File::open(jar).unwrap()).unwrap();
-    for i in 0..archive.len() {
-        let mut entry = archive.by_index(i).unwrap();
-        if entry.is_file() && !entry.name().contains("META-INF") {
-            let mut name = entry.name();
-
-            if name.contains("/") {
-                name = &name[entry.name().rfind('/').unwrap() + 1..];
+fn instance(sub_matches: &clap::ArgMatches) {
+    let game_dir = std::env::current_dir().unwrap().join(".minecraft");
+    let arg =
+        |matches: &clap::ArgMatches, name: &str| matches.get_one::<String>(name).unwrap().clone();
+
+    let result = match sub_matches.subcommand() {
+        Some(("list", _)) => {
+            for instance in path::list_instances(&game_dir) {
+                println!(
+                    "Instance:{} ({}) Version:{}",
+                    instance.name, instance.id, instance.version
+                );
             }
+            Ok(())
+        }
+        Some(("create", matches)) => {
+            let version = arg(matches, "VERSION");
+            path::create_instance(&game_dir, &arg(matches, "NAME"), &version).and_then(
+                |mut instance| {
+                    // 记录基础版本的加载器
+                    let loaded = parse::version::load(&game_dir.join("versions"), &version);
+                    if let Some((loader, loader_version)) =
+                        loaded.ok().as_ref().and_then(Loader::detect)
+                    {
+                        instance.loader = Some(loader.name().to_string());
+                        instance.loader_version = Some(loader_version);
+                        instance.save(&game_dir)?;
+                    }
+                    println!("Created:{}", instance.id);
+                    Ok(())
+                },
+            )
+        }
+        Some(("rename", matches)) => {
+            path::rename_instance(&game_dir, &arg(matches, "ID"), &arg(matches, "NAME"))
+                .map(|instance| println!("Renamed:{}", instance.id))
+        }
+        Some(("clone", matches)) => {
+            path::clone_instance(&game_dir, &arg(matches, "ID"), &arg(matches, "NAME"))
+                .map(|instance| println!("Cloned:{}", instance.id))
+        }
+        Some(("delete", matches)) => path::delete_instance(&game_dir, &arg(matches, "ID"))
+            .map(|trash| println!("Deleted:{}", trash.display())),
+        _ => unreachable!(),
+    };
 
-            let path = dir.join(name);
+    if let Err(err) = result {
+        eprintln!("Instance Error:{}", err);
+    }
+}
 
-            if path.exists() {
-                std::fs::remove_file(&path).unwrap();
-            }
+fn launch(sub_matches: &clap::ArgMatches) {
+    let game_dir = std::env::current_dir().unwrap().join(".minecraft");
+    let version = sub_matches.get_one::<String>("VERSION").unwrap();
 
-            let mut file = std::fs::File::create(&path).unwrap();
+    // 实例使用自己的游戏目录与启动设置
+    let mut options = match path::Instance::load(&game_dir, version) {
+        Ok(instance) => LaunchOptions::from_instance(&game_dir, &instance, "Enaium"),
+        Err(_) => {
+            let mut options = LaunchOptions::new(&game_dir, version, "Enaium");
+            options.game_directory = Some(path::game_directory(&game_dir, version));
+            options
+        }
+    };
+    options.server = sub_matches.get_one::<String>("server").cloned();
 
-            std::io::copy(&mut entry, &mut file).unwrap();
+    match launch::command(&options) {
+        Ok(mut command) => {
+            command.status().unwrap();
         }
+        Err(err) => eprintln!("Launch Error:{}", err),
     }
 }
@@ -2,17 +2,29 @@
     all(not(debug_assertions), target_os = "windows"),
     windows_subsystem = "windows"
 )]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
-use std::sync::Arc;
-use std::sync::Once;
+use std::sync::MutexGuard;
+use std::collections::HashMap;
+use std::time::Duration;
 
-use download::{get, Download, LibaryAllowed};
-use model::version::Version;
-use parse::Parse;
-use tauri::command;
+use download::{
+    curseforge::{CurseForge, ModpackImport, SearchQuery as CurseForgeSearchQuery},
+    get,
+    loader::Loader,
+    modrinth::{ModUpdate, Modrinth, SearchQuery},
+    mrpack,
+    multimc::{self, InstanceImport},
+    optifine, Download,
+};
+use model::curseforge;
+use model::modrinth::{Project, ProjectVersion, SearchResponse};
+use launch::LaunchOptions;
+use settings::{Settings, SettingsStore};
+use tauri::{command, AppHandle, Emitter, Manager, State};
 use serde::{Serialize, Deserialize};
-use path::MinecraftPath;  
+use path::{check_mods, Instance, IsolationMode, IsolationSettings, MinecraftPath, ModCheckReport, ModEnvironment, ModMetadata, ModPath, PackInfo, PackKind, RootChange, RootWatcher, ScreenshotPage, ScreenshotQuery, ServerInfo, WorldBackup, WorldInfo};
+use tauri_plugin_clipboard_manager::ClipboardExt;  
 
 #[derive(Serialize, Deserialize)]
 struct MinecraftVersion {
@@ -35,8 +47,12 @@ impl From<model::version_manifest::Version> for MinecraftVersion {
     }
 }
 
+// 默认使用上次选中的根目录
 #[command]
-fn get_default_game_directory() -> String {
+fn get_default_game_directory(settings: State<'_, SettingsStore>) -> String {
+    if let Some(root) = settings.load().ok().and_then(|s| s.selected_root) {
+        return root;
+    }
     let game_dir = std::env::current_dir().unwrap().join(".minecraft");
     game_dir.to_string_lossy().to_string()
 }
@@ -63,91 +79,178 @@ fn search_versions(version_filter: Option<String>, version_type: String) -> Vec<
 }
 
 #[command]
-fn download_version(version_id: String, game_dir: String) -> Result<String, String> {
-    let game_dir = Path::new(&game_dir);
+fn download_version(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    settings_store: State<'_, SettingsStore>,
+    version_id: String,
+    game_dir: String,
+    loader: Option<String>,
+    loader_version: Option<String>,
+) -> Result<String, String> {
+    let root_path = game_dir;
+    let game_dir = Path::new(&root_path);
     let versions = get_version_manifest().versions;
 
     if let Some(version) = versions.iter().find(|v| v.id.eq(&version_id)) {
         version.download(game_dir).map_err(|err| format!("Download Error: {}", err))?;
-        Ok(format!("Successfully downloaded version {}", version_id))
     } else {
-        Err(format!("Version: {} not found", version_id))
+        return Err(format!("Version: {} not found", version_id));
     }
+
+    // 加载器版本继承原版，原版安装完成后再安装加载器
+    if let Some(loader) = loader {
+        let loader = loader.parse::<Loader>()?;
+        let id = loader
+            .install(game_dir, &version_id, loader_version.as_deref(), &java_path(&settings_store))
+            .map_err(|err| format!("Install Error: {}", err))?;
+        refresh_minecraft_path(&minecraft_paths, &root_path);
+        return Ok(format!("Successfully installed {}", id));
+    }
+
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(format!("Successfully downloaded version {}", version_id))
+}
+
+#[derive(Serialize, Deserialize)]
+struct LoaderVersionInfo {
+    version: String,
+    stable: bool,
 }
 
 #[command]
-fn launch_game(username: String, version_id: String, game_dir: String) -> Result<String, String> {
-    let game_dir = Path::new(&game_dir);
-    let libraries_dir = game_dir.join("libraries");
-    let assets_dir = game_dir.join("assets");
-    let version_dir = game_dir.join("versions").join(&version_id);
-    let natives_dir = version_dir.join("natives");
-    let config_path = version_dir.join(format!("{}.json", version_id));
-    let version_path = version_dir.join(format!("{}.jar", version_id));
-
-    if !version_path.exists() || !config_path.exists() {
-        return Err(format!("Version: {} not found", version_id));
-    }
+fn get_loader_versions(loader: String, game_version: String) -> Result<Vec<LoaderVersionInfo>, String> {
+    let versions = loader
+        .parse::<Loader>()?
+        .loader_versions(&game_version)
+        .map_err(|err| err.to_string())?;
 
-    let version = &Version::parse(&std::fs::read_to_string(&config_path).map_err(|e| e.to_string())?)
-        .map_err(|e| e.to_string())?;
+    Ok(versions
+        .into_iter()
+        .map(|v| LoaderVersionInfo {
+            version: v.version,
+            stable: v.stable,
+        })
+        .collect())
+}
 
-    if !natives_dir.exists() {
-        std::fs::create_dir_all(&natives_dir).map_err(|e| e.to_string())?;
-    }
+// Fabric 的旧命令，保留给已有的调用方，与 get_loader_versions 和 download_version 相同
+#[command]
+fn get_fabric_loader_versions(game_version: String) -> Result<Vec<LoaderVersionInfo>, String> {
+    get_loader_versions("fabric".to_string(), game_version)
+}
+
+#[command]
+fn install_fabric(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    settings_store: State<'_, SettingsStore>,
+    game_version: String,
+    loader_version: String,
+    game_dir: String,
+) -> Result<String, String> {
+    download_version(minecraft_paths, settings_store, game_version, game_dir, Some("fabric".to_string()), Some(loader_version))
+}
 
-    for library in &version.libraries {
-        if library.allowed() && library.name.contains("natives") {
-            extract_jar(
-                &libraries_dir.join(&library.downloads.artifact.path),
-                &natives_dir,
-            );
+// 运行安装器使用的 Java，未设置时使用 PATH 中的 java
+fn java_path(settings_store: &SettingsStore) -> String {
+    settings_store
+        .load()
+        .ok()
+        .and_then(|settings| settings.java.default_path)
+        .unwrap_or("java".to_string())
+}
+
+#[command]
+fn install_optifine(minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, game_dir: String, optifine_path: String, base_version: String) -> Result<String, String> {
+    let id = optifine::install_optifine(Path::new(&game_dir), Path::new(&optifine_path), &base_version, &java_path(&settings_store))
+        .map_err(|err| format!("Install Error: {}", err))?;
+    refresh_minecraft_path(&minecraft_paths, &game_dir);
+
+    Ok(format!("Successfully installed OptiFine to {}", id))
+}
+
+// 启动设置，launch_game 时覆盖实例中保存的设置
+#[derive(Deserialize)]
+struct LaunchSettings {
+    java: Option<String>,
+    #[serde(default)]
+    jvm_args: Vec<String>,
+    min_memory: Option<u32>,
+    max_memory: Option<u32>,
+    // 启动后直接进入的服务器地址
+    server: Option<String>,
+    // 忽略 mod 检查发现的问题，仍然启动游戏
+    #[serde(default)]
+    ignore_mod_issues: bool,
+}
+
+// 启动结果，mod 检查发现问题时不启动游戏，返回检查报告
+#[derive(Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+enum LaunchResult {
+    Exited { message: String },
+    ModIssues { report: ModCheckReport },
+}
+
+#[command]
+fn launch_game(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    settings_store: State<'_, SettingsStore>,
+    username: Option<String>,
+    version_id: String,
+    game_dir: String,
+    settings: Option<LaunchSettings>,
+) -> Result<LaunchResult, String> {
+    let saved = settings_store.load().map_err(|e| e.to_string())?;
+    // 未指定用户名时使用设置中的默认账户
+    let username = username
+        .filter(|name| !name.trim().is_empty())
+        .or(saved.default_account)
+        .ok_or("No username specified and no default account set")?;
+    let root = Path::new(&game_dir);
+    // 实例使用自己的游戏目录与启动设置
+    let mut options = match Instance::load(root, &version_id) {
+        Ok(instance) => LaunchOptions::from_instance(root, &instance, &username),
+        Err(_) => {
+            let mut options = LaunchOptions::new(root, &version_id, &username);
+            options.game_directory = Some(path::game_directory(root, &version_id));
+            options
+        }
+    };
+    // 实例没有指定 Java 时使用设置中的默认 Java
+    if options.java == "java" {
+        if let Some(java) = saved.java.default_path {
+            options.java = java;
+        }
+    }
+    let ignore_mod_issues = settings.as_ref().is_some_and(|settings| settings.ignore_mod_issues);
+    if let Some(settings) = settings {
+        if let Some(java) = settings.java {
+            options.java = java;
         }
+        if !settings.jvm_args.is_empty() {
+            options.jvm_args = settings.jvm_args;
+        }
+        options.min_memory = settings.min_memory.or(options.min_memory);
+        options.max_memory = settings.max_memory.or(options.max_memory);
+        options.server = settings.server;
     }
 
-    let classpath = format!(
-        "{}{}",
-        &version
-            .libraries
-            .iter()
-            .map(|library| {
-                format!(
-                    "{}{}",
-                    libraries_dir
-                        .join(&library.downloads.artifact.path)
-                        .display(),
-                    if cfg!(windows) { ";" } else { ":" }
-                )
-            })
-            .collect::<String>(),
-        version_path.display()
-    );
+    if !ignore_mod_issues {
+        let report = mod_check_report(&minecraft_paths, &game_dir, &version_id)?;
+        if !report.is_ok() {
+            return Ok(LaunchResult::ModIssues { report });
+        }
+    }
 
-    let status = std::process::Command::new("java")
-        .current_dir(&game_dir)
-        .arg(format!("-Djava.library.path={}", natives_dir.display()))
-        .arg("-cp")
-        .arg(classpath)
-        .arg(&version.main_class)
-        .arg("--username")
-        .arg(username)
-        .arg("--version")
-        .arg(&version.id)
-        .arg("--gameDir")
-        .arg(&game_dir)
-        .arg("--assetsDir")
-        .arg(assets_dir)
-        .arg("--assetIndex")
-        .arg(&version.asset_index.id)
-        .arg("--accessToken")
-        .arg("0")
-        .arg("--versionType")
-        .arg("RMCL 0.1.0")
+    let status = launch::command(&options)
+        .map_err(|e| e.to_string())?
         .status()
         .map_err(|e| e.to_string())?;
 
     if status.success() {
-        Ok(format!("Game {} launched and exited successfully", version_id))
+        Ok(LaunchResult::Exited {
+            message: format!("Game {} launched and exited successfully", version_id),
+        })
     } else {
         Err(format!("Game exited with code: {:?}", status.code()))
     }
@@ -183,87 +286,225 @@ fn get_installed_versions(game_dir: String) -> Vec<String> {
     }
 }
 
-// Maintain multiple Minecraft paths
-static INIT: Once = Once::new();
-static mut MINECRAFT_PATHS: Option<Arc<Mutex<Vec<MinecraftPath>>>> = None;
+// 已添加的游戏根目录，由 Tauri 管理
+#[derive(Default)]
+struct MinecraftPaths(Mutex<Vec<MinecraftPath>>);
 
-// Function to initialize and add a new MinecraftPath instance to the collection
-fn initialize_minecraft_path(root_path: &str) -> Result<String, String> {
-    unsafe {
-        // First time initialization
-        if MINECRAFT_PATHS.is_none() {
-            INIT.call_once(|| {
-                let initial_paths = vec![MinecraftPath::new(root_path)];
-                MINECRAFT_PATHS = Some(Arc::new(Mutex::new(initial_paths)));
+impl MinecraftPaths {
+    fn lock(&self) -> Result<MutexGuard<'_, Vec<MinecraftPath>>, String> {
+        self.0
+            .lock()
+            .map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())
+    }
+
+    // 添加新的根目录，已存在时忽略
+    fn add(&self, root_path: &str, name: Option<&str>) -> Result<String, String> {
+        let mut paths = self.lock()?;
+        // Check if path already exists to avoid duplicates
+        if !paths.iter().any(|path| path.get_path().to_str() == Some(root_path)) {
+            paths.push(match name {
+                Some(name) => MinecraftPath::new_with_name(root_path, name),
+                None => MinecraftPath::new(root_path),
             });
-        } 
-        // Add to existing collection
-        else if let Some(instance) = &MINECRAFT_PATHS {
-            if let Ok(mut guard) = instance.lock() {
-                // Check if path already exists to avoid duplicates
-                if !guard.iter().any(|path| path.get_path().to_str() == Some(root_path)) {
-                    guard.push(MinecraftPath::new(root_path));
-                }
-            } else {
-                return Err("Failed to acquire lock on MinecraftPaths".to_string());
-            }
         }
+        Ok("添加新的游戏路径成功".to_string())
     }
-    
-    Ok("添加新的游戏路径成功".to_string())
 }
 
-// Function to initialize a MinecraftPath with a custom name
-fn initialize_minecraft_path_with_name(root_path: &str, name: &str) -> Result<String, String> {
-    unsafe {
-        // First time initialization
-        if MINECRAFT_PATHS.is_none() {
-            INIT.call_once(|| {
-                let initial_paths = vec![MinecraftPath::new_with_name(root_path, name)];
-                MINECRAFT_PATHS = Some(Arc::new(Mutex::new(initial_paths)));
-            });
-        } 
-        // Add to existing collection
-        else if let Some(instance) = &MINECRAFT_PATHS {
-            if let Ok(mut guard) = instance.lock() {
-                // Check if path already exists to avoid duplicates
-                if !guard.iter().any(|path| path.get_path().to_str() == Some(root_path)) {
-                    guard.push(MinecraftPath::new_with_name(root_path, name));
+// 文件监听合并同一批变化的间隔
+const WATCH_DELAY: Duration = Duration::from_millis(500);
+
+// 已开启文件监听的根目录
+#[derive(Default)]
+struct RootWatchers(Mutex<HashMap<String, RootWatcher>>);
+
+// 文件监听事件的内容
+#[derive(Clone, Serialize)]
+struct RootChangeEvent {
+    root_path: String,
+    // 隔离版本的 mods 或截图变化时为版本名
+    version: Option<String>,
+    // 新增的截图路径
+    path: Option<String>,
+}
+
+// 开始监听根目录，变化时更新缓存并发送 mods-changed、versions-changed、screenshot-added 事件
+fn watch_root(app: &AppHandle, root_path: &str) -> Result<(), String> {
+    let watchers = app.state::<RootWatchers>();
+    let mut watchers = watchers
+        .0
+        .lock()
+        .map_err(|_| "Failed to acquire lock on RootWatchers".to_string())?;
+    if watchers.contains_key(root_path) {
+        return Ok(());
+    }
+
+    let handle = app.clone();
+    let root = root_path.to_string();
+    let watcher = RootWatcher::new(Path::new(root_path), WATCH_DELAY, move |changes| {
+        let minecraft_paths = handle.state::<MinecraftPaths>();
+        let _ = with_minecraft_path(&minecraft_paths, &root, |p| {
+            changes.iter().for_each(|change| p.apply_change(change));
+            Ok(())
+        });
+
+        for change in changes {
+            let (event, version, path) = match change {
+                RootChange::Mods { version } => ("mods-changed", version, None),
+                RootChange::Versions => ("versions-changed", None, None),
+                RootChange::Screenshot { version, path } if path.is_file() => {
+                    ("screenshot-added", version, Some(path.to_string_lossy().to_string()))
                 }
-            } else {
-                return Err("Failed to acquire lock on MinecraftPaths".to_string());
+                RootChange::Screenshot { .. } => continue,
+            };
+            let payload = RootChangeEvent { root_path: root.clone(), version, path };
+            if let Err(err) = handle.emit(event, payload) {
+                eprintln!("发送文件变化事件失败: {}", err);
             }
         }
+    })?;
+    watchers.insert(root_path.to_string(), watcher);
+    Ok(())
+}
+
+fn unwatch_root(app: &AppHandle, root_path: &str) {
+    if let Ok(mut watchers) = app.state::<RootWatchers>().0.lock() {
+        watchers.remove(root_path);
     }
-    
-    Ok("添加新的游戏路径成功".to_string())
 }
 
-// Function to get a reference to the initialized MinecraftPaths collection
-fn get_minecraft_paths() -> Result<Arc<Mutex<Vec<MinecraftPath>>>, String> {
-    unsafe {
-        if let Some(instance) = &MINECRAFT_PATHS {
-            Ok(instance.clone())
+// 设置中开启了文件监听时监听新添加的根目录
+fn watch_root_if_enabled(app: &AppHandle, root_path: &str) -> Result<(), String> {
+    let settings = app.state::<SettingsStore>().load().map_err(|e| e.to_string())?;
+    if settings.watch_roots {
+        watch_root(app, root_path)?;
+    }
+    Ok(())
+}
+
+// 开启或关闭所有根目录的文件监听
+#[command]
+fn set_root_watching(app: AppHandle, minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, enabled: bool) -> Result<Settings, String> {
+    let settings = settings_store
+        .modify(|settings| {
+            settings.watch_roots = enabled;
+            Ok(())
+        })
+        .map_err(|e| e.to_string())?;
+
+    let roots: Vec<String> = minecraft_paths
+        .lock()?
+        .iter()
+        .map(|p| p.get_path().to_string_lossy().to_string())
+        .collect();
+    for root in roots {
+        if enabled {
+            watch_root(&app, &root)?;
         } else {
-            Err("MinecraftPaths has not been initialized yet".to_string())
+            unwatch_root(&app, &root);
+        }
+    }
+    Ok(settings)
+}
+
+#[command]
+fn initialize_game_path(app: AppHandle, minecraft_paths: State<'_, MinecraftPaths>, root_path: String) -> Result<String, String> {
+    let message = minecraft_paths.add(&root_path, None)?;
+    let display_name = with_minecraft_path(&minecraft_paths, &root_path, |p| Ok(p.get_display_name().to_string()))?;
+    remember_root(&app.state::<SettingsStore>(), &root_path, &display_name, false)?;
+    watch_root_if_enabled(&app, &root_path)?;
+    Ok(message)
+}
+
+#[command]
+fn initialize_game_path_with_name(app: AppHandle, minecraft_paths: State<'_, MinecraftPaths>, root_path: String, name: String) -> Result<String, String> {
+    let message = minecraft_paths.add(&root_path, Some(&name))?;
+    remember_root(&app.state::<SettingsStore>(), &root_path, &name, true)?;
+    watch_root_if_enabled(&app, &root_path)?;
+    Ok(message)
+}
+
+// 把根目录保存到设置中，重启后自动恢复，rename 为 false 时不覆盖已保存的显示名称
+fn remember_root(settings_store: &SettingsStore, root_path: &str, display_name: &str, rename: bool) -> Result<(), String> {
+    settings_store
+        .modify(|settings| {
+            if rename || !settings.roots.iter().any(|root| root.path == root_path) {
+                settings.add_root(root_path, display_name);
+            }
+            Ok(())
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// 移除根目录，只从启动器中移除，不删除文件
+#[command]
+fn remove_game_path(app: AppHandle, minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, root_path: String) -> Result<(), String> {
+    unwatch_root(&app, &root_path);
+    minecraft_paths
+        .lock()?
+        .retain(|p| p.get_path().to_string_lossy() != root_path);
+
+    settings_store
+        .modify(|settings| {
+            settings.remove_root(&root_path);
+            Ok(())
+        })
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[command]
+fn rename_game_path(minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, root_path: String, name: String) -> Result<(), String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        p.set_display_name(name.clone());
+        Ok(())
+    })?;
+    remember_root(&settings_store, &root_path, &name, true)
+}
+
+// 重新扫描根目录下的版本、mod 与截图
+#[command]
+fn refresh_game_path(minecraft_paths: State<'_, MinecraftPaths>, root_path: String) -> Result<RootPathInfo, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        p.refresh();
+        Ok(RootPathInfo {
+            path: p.get_path().to_string_lossy().to_string(),
+            display_name: p.get_display_name().to_string(),
+        })
+    })
+}
+
+// 恢复设置中保存的根目录，上次选中的根目录排在最前面
+fn restore_minecraft_paths(settings_store: &SettingsStore) -> MinecraftPaths {
+    let minecraft_paths = MinecraftPaths::default();
+    match settings_store.load() {
+        Ok(settings) => {
+            for root in settings.restore_order() {
+                if let Err(err) = minecraft_paths.add(&root.path, Some(&root.display_name)) {
+                    eprintln!("恢复游戏路径失败: {}", err);
+                }
+            }
         }
+        Err(err) => eprintln!("读取设置失败: {}", err),
     }
+    minecraft_paths
 }
 
 #[command]
-fn initialize_game_path(root_path: String) -> Result<String, String> {
-    initialize_minecraft_path(&root_path)
+fn get_settings(settings_store: State<'_, SettingsStore>) -> Result<Settings, String> {
+    settings_store.load().map_err(|e| e.to_string())
 }
 
+// 按 JSON merge patch 合并修改并保存，返回新的设置
 #[command]
-fn initialize_game_path_with_name(root_path: String, name: String) -> Result<String, String> {
-    initialize_minecraft_path_with_name(&root_path, &name)
+fn update_settings(settings_store: State<'_, SettingsStore>, patch: serde_json::Value) -> Result<Settings, String> {
+    settings_store.update(patch).map_err(|e| e.to_string())
 }
 
 #[command]
-fn get_minecraft_path_info() -> Result<Vec<String>, String> {
-    let instance = get_minecraft_paths()?;
-    let paths = instance.lock().map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())?;
+fn get_minecraft_path_info(minecraft_paths: State<'_, MinecraftPaths>) -> Result<Vec<String>, String> {
+    let paths = minecraft_paths.lock()?;
     
     if paths.is_empty() {
         return Err("No Minecraft paths have been initialized".to_string());
@@ -283,6 +524,7 @@ struct VersionInfo {
     name: String,
     path: String,
     mod_count: usize,
+    isolated: bool,
 }
 
 #[derive(Serialize)]
@@ -290,12 +532,25 @@ struct ModInfo {
     name: String,
     path: String,
     location: String,
+    enabled: bool,
+    metadata: Option<ModMetadata>,
+}
+
+impl From<&ModPath> for ModInfo {
+    fn from(value: &ModPath) -> Self {
+        Self {
+            name: value.name.clone(),
+            path: value.path.clone(),
+            location: value.location.clone(),
+            enabled: value.enabled,
+            metadata: value.metadata.clone(),
+        }
+    }
 }
 
 #[command]
-fn get_all_minecraft_paths() -> Result<Vec<RootPathInfo>, String> {
-    let instance = get_minecraft_paths()?;
-    let paths = instance.lock().map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())?;
+fn get_all_minecraft_paths(minecraft_paths: State<'_, MinecraftPaths>) -> Result<Vec<RootPathInfo>, String> {
+    let paths = minecraft_paths.lock()?;
     
     Ok(paths.iter().map(|p| RootPathInfo {
         path: p.get_path().to_string_lossy().to_string(),
@@ -304,9 +559,8 @@ fn get_all_minecraft_paths() -> Result<Vec<RootPathInfo>, String> {
 }
 
 #[command]
-fn get_minecraft_versions_for_path(path: String) -> Result<Vec<VersionInfo>, String> {
-    let instance = get_minecraft_paths()?;
-    let paths = instance.lock().map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())?;
+fn get_minecraft_versions_for_path(minecraft_paths: State<'_, MinecraftPaths>, path: String) -> Result<Vec<VersionInfo>, String> {
+    let paths = minecraft_paths.lock()?;
     
     // Find the specified path
     if let Some(mc_path) = paths.iter().find(|p| p.get_path().to_string_lossy() == path) {
@@ -315,6 +569,7 @@ fn get_minecraft_versions_for_path(path: String) -> Result<Vec<VersionInfo>, Str
                 name: v.name.clone(),
                 path: v.root.to_string_lossy().to_string(),
                 mod_count: v.mods.len(),
+                isolated: v.isolated,
             }).collect()),
             Err(e) => Err(e.to_string()),
         }
@@ -324,18 +579,13 @@ fn get_minecraft_versions_for_path(path: String) -> Result<Vec<VersionInfo>, Str
 }
 
 #[command]
-fn get_minecraft_mods_for_version(root_path: String, version_name: String) -> Result<Vec<ModInfo>, String> {
-    let instance = get_minecraft_paths()?;
-    let paths = instance.lock().map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())?;
+fn get_minecraft_mods_for_version(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String) -> Result<Vec<ModInfo>, String> {
+    let paths = minecraft_paths.lock()?;
     
     // Find the specified path
     if let Some(mc_path) = paths.iter().find(|p| p.get_path().to_string_lossy() == root_path) {
         match mc_path.get_version_mods(&version_name) {
-            Ok(mods) => Ok(mods.iter().map(|m| ModInfo {
-                name: m.name.clone(),
-                path: m.path.clone(),
-                location: m.location.clone(),
-            }).collect()),
+            Ok(mods) => Ok(mods.iter().map(ModInfo::from).collect()),
             Err(e) => Err(e.to_string()),
         }
     } else {
@@ -344,18 +594,13 @@ fn get_minecraft_mods_for_version(root_path: String, version_name: String) -> Re
 }
 
 #[command]
-fn get_minecraft_all_available_mods(root_path: String, version_name: String) -> Result<Vec<ModInfo>, String> {
-    let instance = get_minecraft_paths()?;
-    let paths = instance.lock().map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())?;
+fn get_minecraft_all_available_mods(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String) -> Result<Vec<ModInfo>, String> {
+    let paths = minecraft_paths.lock()?;
     
     // Find the specified path
     if let Some(mc_path) = paths.iter().find(|p| p.get_path().to_string_lossy() == root_path) {
         match mc_path.get_all_available_mods(&version_name) {
-            Ok(mods) => Ok(mods.iter().map(|m| ModInfo {
-                name: m.name.clone(),
-                path: m.path.clone(),
-                location: m.location.clone(),
-            }).collect()),
+            Ok(mods) => Ok(mods.iter().map(ModInfo::from).collect()),
             Err(e) => Err(e.to_string()),
         }
     } else {
@@ -363,24 +608,761 @@ fn get_minecraft_all_available_mods(root_path: String, version_name: String) ->
     }
 }
 
+// 在指定根目录上执行修改操作
+fn with_minecraft_path<T>(
+    minecraft_paths: &MinecraftPaths,
+    root_path: &str,
+    f: impl FnOnce(&mut MinecraftPath) -> Result<T, &'static str>,
+) -> Result<T, String> {
+    let mut paths = minecraft_paths.lock()?;
+
+    if let Some(mc_path) = paths.iter_mut().find(|p| p.get_path().to_string_lossy() == root_path) {
+        f(mc_path).map_err(|e| e.to_string())
+    } else {
+        Err("Specified Minecraft path not found".to_string())
+    }
+}
+
+#[command]
+fn enable_mod(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, mod_path: String) -> Result<String, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| p.set_mod_enabled(&mod_path, true))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[command]
+fn disable_mod(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, mod_path: String) -> Result<String, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| p.set_mod_enabled(&mod_path, false))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[command]
+fn delete_mod(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, mod_path: String) -> Result<String, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| p.delete_mod(&mod_path))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[command]
+fn add_mod(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String, source_path: String) -> Result<String, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| p.add_mod(Path::new(&source_path), &version_name))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+// 读取版本配置得到游戏版本及加载器
+fn mod_environment(root_path: &str, version_name: &str) -> Result<ModEnvironment, String> {
+    let versions_dir = Path::new(root_path).join("versions");
+    let version = parse::version::load(&versions_dir, version_name).map_err(|e| e.to_string())?;
+    let (loader, loader_version) = match Loader::detect(&version) {
+        Some((loader, loader_version)) => (Some(loader.name().to_string()), Some(loader_version)),
+        None => (None, None),
+    };
+    Ok(ModEnvironment {
+        game_version: version.jar.clone().unwrap_or(version.id.clone()),
+        loader,
+        loader_version,
+    })
+}
+
+// 启动前检查 mod 的重复、前置、冲突以及与加载器和游戏版本的兼容性
 #[command]
-fn get_minecraft_screenshots() -> Result<Vec<String>, String> {
-    let instance = get_minecraft_paths()?;
-    let mut paths = instance.lock().map_err(|_| "Failed to acquire lock on MinecraftPaths".to_string())?;
+fn check_minecraft_mods(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String) -> Result<ModCheckReport, String> {
+    mod_check_report(&minecraft_paths, &root_path, &version_name)
+}
+
+// 原版与只安装 OptiFine 的版本不加载 mod，不检查；根目录尚未添加时临时扫描一次
+fn mod_check_report(minecraft_paths: &MinecraftPaths, root_path: &str, version_name: &str) -> Result<ModCheckReport, String> {
+    let environment = mod_environment(root_path, version_name)?;
+    if environment.loader.is_none() {
+        return Ok(ModCheckReport { issues: Vec::new() });
+    }
+    let mods = match with_minecraft_path(minecraft_paths, root_path, |p| p.get_all_available_mods(version_name)) {
+        Ok(mods) => mods,
+        Err(_) => MinecraftPath::new(root_path).get_all_available_mods(version_name).map_err(|e| e.to_string())?,
+    };
+    Ok(check_mods(&mods, &environment))
+}
+
+#[command]
+fn search_mods(
+    query: String,
+    game_version: Option<String>,
+    loader: Option<String>,
+    categories: Option<Vec<String>>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<SearchResponse, String> {
+    let query = SearchQuery {
+        query,
+        game_version,
+        loader,
+        categories: categories.unwrap_or_default(),
+        project_type: Some("mod".to_string()),
+        offset: offset.unwrap_or(0),
+        limit: limit.unwrap_or(20),
+    };
+    Modrinth::default().search(&query).map_err(|e| e.to_string())
+}
+
+#[command]
+fn get_mod_project(project_id: String) -> Result<Project, String> {
+    Modrinth::default().project(&project_id).map_err(|e| e.to_string())
+}
+
+#[command]
+fn get_mod_versions(
+    project_id: String,
+    game_version: Option<String>,
+    loader: Option<String>,
+) -> Result<Vec<ProjectVersion>, String> {
+    Modrinth::default()
+        .versions(&project_id, game_version.as_deref(), loader.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+// 安装 Modrinth 上的 mod 版本及其必需前置到版本的 mods 目录
+#[command]
+fn install_mod(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String, mod_version_id: String) -> Result<Vec<String>, String> {
+    let environment = mod_environment(&root_path, &version_name)?;
+    let mods_dir = with_minecraft_path(&minecraft_paths, &root_path, |p| Ok(p.get_mods_directory(&version_name)))?;
+
+    let paths = Modrinth::default()
+        .install(
+            &mods_dir,
+            &mod_version_id,
+            Some(&environment.game_version),
+            environment.loader.as_deref(),
+        )
+        .map_err(|e| e.to_string())?;
+
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        p.refresh();
+        Ok(())
+    })?;
+    Ok(paths.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+// 通过文件 hash 在 Modrinth 上检查版本可用的 mod 更新
+#[command]
+fn check_mod_updates(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String) -> Result<Vec<ModUpdate>, String> {
+    let environment = mod_environment(&root_path, &version_name)?;
+    let mods = with_minecraft_path(&minecraft_paths, &root_path, |p| p.get_all_available_mods(&version_name))?;
+    let paths: Vec<PathBuf> = mods.iter().map(|m| PathBuf::from(&m.path)).collect();
+
+    Modrinth::default()
+        .check_updates(&paths, Some(&environment.game_version), environment.loader.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+// 更新选中的 mod，旧文件以 .bak 保留，返回更新后的文件
+#[command]
+fn apply_mod_updates(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String, mod_paths: Vec<String>) -> Result<Vec<String>, String> {
+    let environment = mod_environment(&root_path, &version_name)?;
+    let mods_dir = with_minecraft_path(&minecraft_paths, &root_path, |p| Ok(p.get_mods_directory(&version_name)))?;
+    let paths = mod_paths
+        .iter()
+        .map(|mod_path| mod_in_directory(&mods_dir, mod_path))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let modrinth = Modrinth::default();
+    let updates = modrinth
+        .check_updates(&paths, Some(&environment.game_version), environment.loader.as_deref())
+        .map_err(|e| e.to_string())?;
+    let result = modrinth.apply_updates(&updates).map_err(|e| e.to_string());
+
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        p.refresh();
+        Ok(())
+    })?;
+    result.map(|paths| paths.iter().map(|path| path.to_string_lossy().to_string()).collect())
+}
+
+// 要更新的 mod 必须直接位于该版本的 mods 目录下
+fn mod_in_directory(mods_dir: &Path, mod_path: &str) -> Result<PathBuf, String> {
+    let mods_dir = mods_dir.canonicalize().map_err(|_| "未找到 mods 目录".to_string())?;
+    let path = Path::new(mod_path)
+        .canonicalize()
+        .map_err(|_| format!("未找到 mod: {}", mod_path))?;
+    if path.is_file() && path.parent() == Some(mods_dir.as_path()) {
+        Ok(path)
+    } else {
+        Err(format!("{} 不在 mods 目录中", mod_path))
+    }
+}
+
+// CurseForge 接口使用设置中保存的 API key
+fn curseforge(settings_store: &SettingsStore) -> Result<CurseForge, String> {
+    let api_key = settings_store
+        .load()
+        .map_err(|e| e.to_string())?
+        .download
+        .curseforge_api_key
+        .filter(|key| !key.trim().is_empty())
+        .ok_or("CurseForge API key is not set")?;
+    Ok(CurseForge::with_key(&api_key))
+}
+
+#[command]
+fn search_curseforge_mods(
+    settings_store: State<'_, SettingsStore>,
+    query: String,
+    game_version: Option<String>,
+    loader: Option<String>,
+    category_id: Option<u32>,
+    index: Option<u32>,
+    page_size: Option<u32>,
+) -> Result<curseforge::SearchResponse, String> {
+    let query = CurseForgeSearchQuery {
+        query,
+        game_version,
+        loader,
+        category_id,
+        index: index.unwrap_or(0),
+        page_size: page_size.unwrap_or(20),
+    };
+    curseforge(&settings_store)?.search(&query).map_err(|e| e.to_string())
+}
+
+#[command]
+fn get_curseforge_files(
+    settings_store: State<'_, SettingsStore>,
+    mod_id: u32,
+    game_version: Option<String>,
+    loader: Option<String>,
+) -> Result<Vec<curseforge::File>, String> {
+    curseforge(&settings_store)?
+        .files(mod_id, game_version.as_deref(), loader.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+struct IdentifiedMod {
+    path: String,
+    matched: curseforge::FingerprintMatch,
+}
+
+// 通过指纹识别版本可用的 mod 在 CurseForge 上对应的文件
+#[command]
+fn identify_curseforge_mods(minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, root_path: String, version_name: String) -> Result<Vec<IdentifiedMod>, String> {
+    let mods = with_minecraft_path(&minecraft_paths, &root_path, |p| p.get_all_available_mods(&version_name))?;
+    let paths: Vec<PathBuf> = mods.iter().map(|m| PathBuf::from(&m.path)).collect();
+
+    let identified = curseforge(&settings_store)?.identify(&paths).map_err(|e| e.to_string())?;
+    Ok(identified
+        .into_iter()
+        .map(|(path, matched)| IdentifiedMod {
+            path: path.to_string_lossy().to_string(),
+            matched,
+        })
+        .collect())
+}
+
+// 安装 CurseForge 上的 mod 文件及其必需前置到版本的 mods 目录
+#[command]
+fn install_curseforge_mod(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    settings_store: State<'_, SettingsStore>,
+    root_path: String,
+    version_name: String,
+    mod_id: u32,
+    file_id: u32,
+) -> Result<Vec<String>, String> {
+    let environment = mod_environment(&root_path, &version_name)?;
+    let mods_dir = with_minecraft_path(&minecraft_paths, &root_path, |p| Ok(p.get_mods_directory(&version_name)))?;
+
+    let result = curseforge(&settings_store)?.install(
+        &mods_dir,
+        mod_id,
+        file_id,
+        Some(&environment.game_version),
+        environment.loader.as_deref(),
+    );
+
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        p.refresh();
+        Ok(())
+    })?;
+    let paths = result.map_err(|e| e.to_string())?;
+    Ok(paths.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+// 刷新已初始化的根目录缓存，根目录未初始化时忽略
+fn refresh_minecraft_path(minecraft_paths: &MinecraftPaths, root_path: &str) {
+    let _ = with_minecraft_path(minecraft_paths, root_path, |p| {
+        p.refresh();
+        Ok(())
+    });
+}
+
+#[command]
+fn list_instances(root_path: String) -> Vec<Instance> {
+    path::list_instances(Path::new(&root_path))
+}
+
+// 从已安装的版本创建实例，记录该版本的加载器
+#[command]
+fn create_instance(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, name: String, version_id: String) -> Result<Instance, String> {
+    let environment = mod_environment(&root_path, &version_id)?;
+    let root = Path::new(&root_path);
+    let mut instance = path::create_instance(root, &name, &version_id)?;
+    instance.loader = environment.loader;
+    instance.loader_version = environment.loader_version;
+    instance.save(root)?;
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(instance)
+}
+
+#[command]
+fn rename_instance(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, id: String, name: String) -> Result<Instance, String> {
+    let instance = path::rename_instance(Path::new(&root_path), &id, &name)?;
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(instance)
+}
+
+#[command]
+fn clone_instance(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, id: String, name: String) -> Result<Instance, String> {
+    let instance = path::clone_instance(Path::new(&root_path), &id, &name)?;
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(instance)
+}
+
+// 删除实例，移动到根目录下的 .trash 目录
+#[command]
+fn delete_instance(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, id: String) -> Result<String, String> {
+    let trash = path::delete_instance(Path::new(&root_path), &id)?;
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(trash.to_string_lossy().to_string())
+}
+
+#[command]
+fn set_instance_icon(root_path: String, id: String, icon_path: String) -> Result<Instance, String> {
+    Ok(path::set_instance_icon(Path::new(&root_path), &id, Path::new(&icon_path))?)
+}
+
+// 保存实例的 Java 路径、JVM 参数与内存
+#[command]
+fn update_instance_settings(root_path: String, id: String, settings: LaunchSettings) -> Result<Instance, String> {
+    let root = Path::new(&root_path);
+    let mut instance = Instance::load(root, &id)?;
+    instance.java = settings.java;
+    instance.jvm_args = settings.jvm_args;
+    instance.min_memory = settings.min_memory;
+    instance.max_memory = settings.max_memory;
+    instance.save(root)?;
+    Ok(instance)
+}
+
+// 导入 Modrinth 整合包，返回新版本的 id
+#[command]
+fn import_mrpack(minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, game_dir: String, pack_path: String, name: Option<String>) -> Result<String, String> {
+    let id = mrpack::import(Path::new(&game_dir), Path::new(&pack_path), name.as_deref(), &java_path(&settings_store))
+        .map_err(|e| format!("Import Error: {}", e))?;
+    refresh_minecraft_path(&minecraft_paths, &game_dir);
+    Ok(id)
+}
+
+// 导入 MultiMC / Prism 实例目录或导出的 zip
+#[command]
+fn import_multimc_instance(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    settings_store: State<'_, SettingsStore>,
+    game_dir: String,
+    source_path: String,
+    name: Option<String>,
+) -> Result<InstanceImport, String> {
+    let result = multimc::import(Path::new(&game_dir), Path::new(&source_path), name.as_deref(), &java_path(&settings_store))
+        .map_err(|e| format!("Import Error: {}", e))?;
+    refresh_minecraft_path(&minecraft_paths, &game_dir);
+    Ok(result)
+}
+
+// 导入 CurseForge 整合包，返回版本 id 与需要手动下载的文件
+#[command]
+fn import_curseforge_modpack(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    settings_store: State<'_, SettingsStore>,
+    game_dir: String,
+    pack_path: String,
+    name: Option<String>,
+) -> Result<ModpackImport, String> {
+    let result = curseforge(&settings_store)?
+        .import_modpack(Path::new(&game_dir), Path::new(&pack_path), name.as_deref(), &java_path(&settings_store))
+        .map_err(|e| format!("Import Error: {}", e))?;
+    refresh_minecraft_path(&minecraft_paths, &game_dir);
+    Ok(result)
+}
+
+#[derive(Deserialize)]
+struct MrpackExportOptions {
+    name: String,
+    pack_version: String,
+    summary: Option<String>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+}
+
+// 导出版本为 Modrinth 整合包，未指定 include 时使用默认的 mods、config 等目录
+#[command]
+fn export_mrpack(
+    game_dir: String,
+    version_id: String,
+    output_path: String,
+    options: MrpackExportOptions,
+) -> Result<mrpack::ExportSummary, String> {
+    let mut export_options = mrpack::ExportOptions::new(&options.name, &options.pack_version);
+    export_options.summary = options.summary;
+    if let Some(include) = options.include {
+        export_options.include = include;
+    }
+    export_options.exclude = options.exclude.unwrap_or_default();
+
+    mrpack::export(
+        Path::new(&game_dir),
+        &version_id,
+        Path::new(&output_path),
+        &export_options,
+        &Modrinth::default(),
+    )
+    .map_err(|e| format!("Export Error: {}", e))
+}
+
+#[command]
+fn get_isolation_settings(root_path: String) -> IsolationSettings {
+    IsolationSettings::load(Path::new(&root_path))
+}
+
+// 设置未单独设置的版本的默认隔离方式
+#[command]
+fn set_isolation_mode(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, mode: IsolationMode) -> Result<IsolationSettings, String> {
+    let root = Path::new(&root_path);
+    let mut settings = IsolationSettings::load(root);
+    settings.mode = mode;
+    settings.save(root)?;
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(settings)
+}
+
+// 单独设置版本是否隔离，isolated 为空时跟随默认方式
+#[command]
+fn set_version_isolation(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: String,
+    version_name: String,
+    isolated: Option<bool>,
+) -> Result<IsolationSettings, String> {
+    let root = Path::new(&root_path);
+    let mut settings = IsolationSettings::load(root);
+    match isolated {
+        Some(isolated) => settings.versions.insert(version_name, isolated),
+        None => settings.versions.remove(&version_name),
+    };
+    settings.save(root)?;
+    refresh_minecraft_path(&minecraft_paths, &root_path);
+    Ok(settings)
+}
+
+// 版本游戏目录下的截图，隔离的版本为 versions/<id>/screenshots
+#[command]
+fn get_version_screenshots(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String) -> Result<Vec<String>, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| Ok(p.get_version_screenshots(&version_name).1))
+}
+
+#[command]
+fn get_version_saves(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: String) -> Result<Vec<String>, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| Ok(p.get_version_saves(&version_name).1))
+}
+
+#[command]
+fn get_minecraft_screenshots(minecraft_paths: State<'_, MinecraftPaths>, root_path: Option<String>) -> Result<Vec<String>, String> {
+    let mut paths = minecraft_paths.lock()?;
     
     if paths.is_empty() {
         return Err("No Minecraft paths have been initialized".to_string());
     }
     
-    // For now, just return screenshots from the first path
-    match paths[0].get_sceenshots() {
+    // 未指定根目录时使用第一个
+    let mc_path = match &root_path {
+        Some(root_path) => paths
+            .iter_mut()
+            .find(|p| p.get_path().to_string_lossy() == *root_path)
+            .ok_or("Specified Minecraft path not found".to_string())?,
+        None => &mut paths[0],
+    };
+    match mc_path.get_sceenshots() {
         Ok((_, screenshots)) => Ok(screenshots),
         Err(e) => Err(e.to_string()),
     }
 }
 
+// 分页列出截图，root_path 为空时包括所有根目录，version_name 为空时包括根目录与所有版本的游戏目录
+#[command]
+fn list_screenshots(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: Option<String>,
+    version_name: Option<String>,
+    query: Option<ScreenshotQuery>,
+) -> Result<ScreenshotPage, String> {
+    // 只在锁内复制根目录的路径，读取截图时不占用锁
+    let roots = root_directories(&minecraft_paths, root_path.as_deref())?;
+    let screenshots = roots
+        .iter()
+        .flat_map(|root| path::root_screenshots(root, version_name.as_deref()))
+        .collect();
+    Ok(path::query_screenshots(screenshots, &query.unwrap_or_default()))
+}
+
+// 列出存档，按最后游玩时间从新到旧排序，root_path 与 version_name 为空时的范围与 list_screenshots 相同
+#[command]
+fn list_worlds(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: Option<String>,
+    version_name: Option<String>,
+) -> Result<Vec<WorldInfo>, String> {
+    let paths = minecraft_paths.lock()?;
+    let mut worlds = Vec::new();
+    for mc_path in paths
+        .iter()
+        .filter(|p| root_path.as_ref().is_none_or(|root| p.get_path().to_string_lossy() == *root))
+    {
+        match &version_name {
+            Some(version_name) => worlds.extend(mc_path.get_version_worlds(version_name)),
+            None => worlds.extend(mc_path.get_all_worlds()),
+        }
+    }
+    worlds.sort_by_key(|world| std::cmp::Reverse(world.last_played));
+    Ok(worlds)
+}
+
+// 截图必须位于已添加的根目录或版本游戏目录的 screenshots 目录中
+fn screenshot_in_roots(minecraft_paths: &MinecraftPaths, screenshot_path: &str) -> Result<PathBuf, String> {
+    let roots = root_directories(minecraft_paths, None)?;
+    Ok(path::screenshot_in_roots(&roots, Path::new(screenshot_path))?)
+}
+
+// 返回缓存的缩略图路径，不存在时生成
+#[command]
+fn get_screenshot_thumbnail(app: AppHandle, minecraft_paths: State<'_, MinecraftPaths>, screenshot_path: String) -> Result<String, String> {
+    let screenshot = screenshot_in_roots(&minecraft_paths, &screenshot_path)?;
+    let cache_dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| e.to_string())?
+        .join("thumbnails");
+    let thumbnail = path::screenshot_thumbnail(&screenshot, &cache_dir)?;
+    Ok(thumbnail.to_string_lossy().to_string())
+}
+
+// 删除截图，移动到根目录下的 .trash 目录
+#[command]
+fn delete_screenshot(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, screenshot_path: String) -> Result<String, String> {
+    with_minecraft_path(&minecraft_paths, &root_path, |p| p.delete_screenshot(Path::new(&screenshot_path)))
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[command]
+fn copy_screenshot_to_clipboard(app: AppHandle, minecraft_paths: State<'_, MinecraftPaths>, screenshot_path: String) -> Result<(), String> {
+    let screenshot = screenshot_in_roots(&minecraft_paths, &screenshot_path)?;
+    let (rgba, width, height) = path::read_screenshot_rgba(&screenshot)?;
+    app.clipboard()
+        .write_image(&tauri::image::Image::new_owned(rgba, width, height))
+        .map_err(|e| e.to_string())
+}
+
+// 复制截图到指定目录，返回导出后的路径
+#[command]
+fn export_screenshots(minecraft_paths: State<'_, MinecraftPaths>, screenshot_paths: Vec<String>, target_dir: String) -> Result<Vec<String>, String> {
+    let paths = screenshot_paths
+        .iter()
+        .map(|path| screenshot_in_roots(&minecraft_paths, path))
+        .collect::<Result<Vec<_>, _>>()?;
+    let exported = path::export_screenshots(&paths, Path::new(&target_dir))?;
+    Ok(exported.iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+// 版本对应的游戏版本，未指定版本或无法读取版本配置时为 None
+fn game_version(root_path: &str, version_name: Option<&str>) -> Option<String> {
+    let version_name = version_name?;
+    mod_environment(root_path, version_name).ok().map(|environment| environment.game_version)
+}
+
+// 资源包或光影包，指定版本时检查资源包与游戏版本的兼容性
+#[command]
+fn list_packs(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: String,
+    version_name: Option<String>,
+    kind: PackKind,
+) -> Result<Vec<PackInfo>, String> {
+    let game_version = game_version(&root_path, version_name.as_deref());
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        Ok(p.get_version_packs(&version_name, kind, game_version.as_deref()))
+    })
+}
+
+// 在 options.txt 中启用或禁用资源包
+#[command]
+fn set_resource_pack_enabled(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: String,
+    version_name: Option<String>,
+    file_name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    let game_version = game_version(&root_path, version_name.as_deref());
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        p.set_resource_pack_enabled(&version_name, &file_name, enabled, game_version.as_deref())
+    })
+}
+
+// 版本游戏目录下 servers.dat 中的服务器列表，未指定版本时为根目录下的
+#[command]
+fn list_servers(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: Option<String>) -> Result<Vec<ServerInfo>, String> {
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| path::list_servers(&p.get_game_directory(&version_name)))
+}
+
+#[command]
+fn add_server(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: Option<String>, server: ServerInfo) -> Result<usize, String> {
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| path::add_server(&p.get_game_directory(&version_name), &server))
+}
+
+#[command]
+fn update_server(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: String,
+    version_name: Option<String>,
+    index: usize,
+    server: ServerInfo,
+) -> Result<(), String> {
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| path::update_server(&p.get_game_directory(&version_name), index, &server))
+}
+
+#[command]
+fn remove_server(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: Option<String>, index: usize) -> Result<ServerInfo, String> {
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| path::remove_server(&p.get_game_directory(&version_name), index))
+}
+
+#[command]
+fn move_server(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, version_name: Option<String>, from: usize, to: usize) -> Result<(), String> {
+    let version_name = version_name.unwrap_or("global".to_string());
+    with_minecraft_path(&minecraft_paths, &root_path, |p| path::move_server(&p.get_game_directory(&version_name), from, to))
+}
+
+// 已添加的根目录的路径，只在锁内复制路径，之后的文件操作不占用锁
+fn root_directory(minecraft_paths: &MinecraftPaths, root_path: &str) -> Result<PathBuf, String> {
+    with_minecraft_path(minecraft_paths, root_path, |p| Ok(p.get_path().to_path_buf()))
+}
+
+// 已添加的根目录的路径，root_path 为空时为所有根目录
+fn root_directories(minecraft_paths: &MinecraftPaths, root_path: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    Ok(minecraft_paths
+        .lock()?
+        .iter()
+        .map(|p| p.get_path().to_path_buf())
+        .filter(|path| root_path.is_none_or(|root| path.to_string_lossy() == root))
+        .collect())
+}
+
+// 存档必须是已添加的根目录中包含 level.dat 的目录，解析 .. 与符号链接后再比较
+fn world_in_root(root: &Path, world_path: &str) -> Result<PathBuf, &'static str> {
+    let root = root.canonicalize().map_err(|_| "未找到游戏目录")?;
+    let world = Path::new(world_path).canonicalize().map_err(|_| "未找到存档")?;
+    if world.starts_with(&root) && world.join("level.dat").is_file() {
+        Ok(world)
+    } else {
+        Err("未找到存档")
+    }
+}
+
+// 在阻塞线程中执行压缩、解压等耗时的文件操作
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, &'static str> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// 备份存档，按设置中的数量保留最新的备份
+#[command]
+async fn backup_world(minecraft_paths: State<'_, MinecraftPaths>, settings_store: State<'_, SettingsStore>, root_path: String, world_path: String) -> Result<WorldBackup, String> {
+    let max_backups = settings_store.load().map(|s| s.backup.max_backups).unwrap_or(10);
+    let root = root_directory(&minecraft_paths, &root_path)?;
+    run_blocking(move || path::backup_world(&world_in_root(&root, &world_path)?, max_backups)).await
+}
+
+#[command]
+fn list_world_backups(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, world_path: String) -> Result<Vec<WorldBackup>, String> {
+    let root = root_directory(&minecraft_paths, &root_path)?;
+    Ok(path::list_backups(&world_in_root(&root, &world_path)?))
+}
+
+// 用备份替换存档，游戏正在运行时会失败
+#[command]
+async fn restore_world_backup(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, world_path: String, backup_path: String) -> Result<(), String> {
+    let root = root_directory(&minecraft_paths, &root_path)?;
+    run_blocking(move || path::restore_backup(&world_in_root(&root, &world_path)?, Path::new(&backup_path))).await
+}
+
+#[command]
+fn delete_world_backup(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, world_path: String, backup_path: String) -> Result<(), String> {
+    let root = root_directory(&minecraft_paths, &root_path)?;
+    Ok(path::delete_backup(&world_in_root(&root, &world_path)?, Path::new(&backup_path))?)
+}
+
+// 导出存档为压缩包用于分享
+#[command]
+async fn export_world(minecraft_paths: State<'_, MinecraftPaths>, root_path: String, world_path: String, output_path: String) -> Result<(), String> {
+    let root = root_directory(&minecraft_paths, &root_path)?;
+    run_blocking(move || path::export_world(&world_in_root(&root, &world_path)?, Path::new(&output_path))).await
+}
+
+// 导入存档压缩包到版本的游戏目录，未指定版本时导入到根目录
+#[command]
+async fn import_world(
+    minecraft_paths: State<'_, MinecraftPaths>,
+    root_path: String,
+    version_name: Option<String>,
+    zip_path: String,
+    name: Option<String>,
+) -> Result<WorldInfo, String> {
+    let (root, game_directory) = with_minecraft_path(&minecraft_paths, &root_path, |p| {
+        let root = p.get_path().to_path_buf();
+        let game_directory = match &version_name {
+            Some(version_name) => p.get_game_directory(version_name),
+            None => root.clone(),
+        };
+        Ok((root, game_directory))
+    })?;
+    run_blocking(move || {
+        let version = version_name.as_deref().filter(|_| game_directory != root);
+        let world = path::import_world(&game_directory.join("saves"), Path::new(&zip_path), name.as_deref())?;
+        path::read_world(&world, &root, version).ok_or("读取存档失败")
+    })
+    .await
+}
+
 fn main() {
+    let settings_store = SettingsStore::default();
     tauri::Builder::default()
+        .manage(restore_minecraft_paths(&settings_store))
+        .manage(settings_store)
+        .manage(RootWatchers::default())
+        .setup(|app| {
+            let settings = app.state::<SettingsStore>().load()?;
+            if settings.watch_roots {
+                for root in &settings.roots {
+                    if let Err(err) = watch_root(app.handle(), &root.path) {
+                        eprintln!("监听游戏路径失败: {}", err);
+                    }
+                }
+            }
+            Ok(())
+        })
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_os::init())
@@ -394,6 +1376,10 @@ fn main() {
             search_versions,
             download_version,
             launch_game,
+            get_loader_versions,
+            get_fabric_loader_versions,
+            install_fabric,
+            install_optifine,
             get_default_game_directory,
             get_installed_versions,
             a_test,
@@ -402,6 +1388,10 @@ fn main() {
             initialize_game_path_with_name,
             get_minecraft_path_info,
             get_minecraft_screenshots,
+            remove_game_path,
+            rename_game_path,
+            refresh_game_path,
+            set_root_watching,
             // Remove the undefined functions
             // get_minecraft_versions,
             // get_minecraft_all_mods,
@@ -411,6 +1401,58 @@ fn main() {
             get_minecraft_versions_for_path,
             get_minecraft_mods_for_version,
             get_minecraft_all_available_mods, // Add this line to register the new command
+            enable_mod,
+            disable_mod,
+            delete_mod,
+            add_mod,
+            check_minecraft_mods,
+            search_mods,
+            get_mod_project,
+            get_mod_versions,
+            install_mod,
+            check_mod_updates,
+            apply_mod_updates,
+            search_curseforge_mods,
+            get_curseforge_files,
+            identify_curseforge_mods,
+            install_curseforge_mod,
+            import_mrpack,
+            export_mrpack,
+            import_curseforge_modpack,
+            import_multimc_instance,
+            list_instances,
+            create_instance,
+            rename_instance,
+            clone_instance,
+            delete_instance,
+            set_instance_icon,
+            update_instance_settings,
+            get_isolation_settings,
+            set_isolation_mode,
+            set_version_isolation,
+            get_version_screenshots,
+            list_screenshots,
+            get_screenshot_thumbnail,
+            delete_screenshot,
+            copy_screenshot_to_clipboard,
+            export_screenshots,
+            get_version_saves,
+            list_worlds,
+            backup_world,
+            list_world_backups,
+            restore_world_backup,
+            delete_world_backup,
+            export_world,
+            import_world,
+            list_packs,
+            set_resource_pack_enabled,
+            list_servers,
+            add_server,
+            update_server,
+            remove_server,
+            move_server,
+            get_settings,
+            update_settings,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -422,28 +1464,3 @@ fn get_version_manifest() -> model::version_manifest::VersionManifest {
         .json::<model::version_manifest::VersionManifest>()
         .unwrap();
 }
-
-fn extract_jar(jar: &Path, dir: &Path) {
-    let mut archive = zip::ZipArchive::new(std::fs::File::open(jar).unwrap()).unwrap();
-    for i in 0..archive.len() {
-        let mut entry = archive.by_index(i).unwrap();
-        if entry.is_file() && !entry.name().contains("META-INF") {
-            let mut name = entry.name();
-
-            if name.contains("/") {
-                name = &name[entry.name().rfind('/').unwrap() + 1..];
-            }
-
-            let path = dir.join(name);
-
-            if path.exists() {
-                std::fs::remove_file(&path).unwrap();
-            }
-
-            let mut file = std::fs::File::create(&path).unwrap();
-
-            std::io::copy(&mut entry, &mut file).unwrap();
-        }
-    }
-}
-
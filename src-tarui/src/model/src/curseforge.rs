@@ -0,0 +1,250 @@
+use serde::{Deserialize, Serialize};
+
+/*
+
+  GET /v1/mods/search?gameId=432&classId=6&searchFilter=jei&gameVersion=1.20.1&modLoaderType=1
+
+  {
+    "data": [
+      {
+        "id": 238222,
+        "gameId": 432,
+        "name": "Just Enough Items (JEI)",
+        "slug": "jei",
+        "summary": "View Items and Recipes",
+        "downloadCount": 300000000,
+        "classId": 6,
+        "categories": [{ "id": 421, "name": "API and Library", "slug": "library-api" }],
+        "logo": { "thumbnailUrl": "https://media.forgecdn.net/avatars/thumbnails/29/69/256/256/635838945588716414.jpeg", "url": "..." },
+        "allowModDistribution": true
+      }
+    ],
+    "pagination": { "index": 0, "pageSize": 20, "resultCount": 1, "totalCount": 1 }
+  }
+
+*/
+#[derive(Deserialize, Serialize)]
+// 映射源 curseforge/v1/mods/search
+pub struct SearchResponse {
+    pub data: Vec<Mod>,
+    pub pagination: Pagination,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Pagination {
+    pub index: u32,
+    #[serde(alias = "pageSize")]
+    pub page_size: u32,
+    #[serde(alias = "resultCount")]
+    pub result_count: u32,
+    #[serde(alias = "totalCount")]
+    pub total_count: u32,
+}
+
+#[derive(Deserialize)]
+// 映射源 curseforge/v1/mods/<id>
+pub struct ModResponse {
+    pub data: Mod,
+}
+
+#[derive(Deserialize)]
+// 映射源 curseforge/v1/mods (按 id 批量获取)
+pub struct ModsResponse {
+    pub data: Vec<Mod>,
+}
+
+#[derive(Deserialize)]
+// 映射源 curseforge/v1/mods/<id>/files 与 curseforge/v1/mods/files
+pub struct FilesResponse {
+    pub data: Vec<File>,
+}
+
+#[derive(Deserialize)]
+// 映射源 curseforge/v1/mods/<id>/files/<file_id>
+pub struct FileResponse {
+    pub data: File,
+}
+
+#[derive(Deserialize)]
+// 映射源 curseforge/v1/fingerprints/<game_id>
+pub struct FingerprintResponse {
+    pub data: FingerprintMatches,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Mod {
+    pub id: u32,
+    #[serde(alias = "gameId")]
+    pub game_id: u32,
+    pub name: String,
+    pub slug: String,
+    pub summary: String,
+    #[serde(alias = "downloadCount")]
+    pub download_count: f64,
+    #[serde(alias = "classId")]
+    pub class_id: Option<u32>,
+    #[serde(default)]
+    pub categories: Vec<Category>,
+    pub logo: Option<Logo>,
+    pub links: Option<Links>,
+    // 作者不允许第三方启动器下载时为 false
+    #[serde(alias = "allowModDistribution")]
+    pub allow_mod_distribution: Option<bool>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Category {
+    pub id: u32,
+    pub name: String,
+    pub slug: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Links {
+    #[serde(alias = "websiteUrl")]
+    pub website_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Logo {
+    #[serde(alias = "thumbnailUrl")]
+    pub thumbnail_url: String,
+    pub url: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct File {
+    pub id: u32,
+    #[serde(alias = "modId")]
+    pub mod_id: u32,
+    #[serde(alias = "displayName")]
+    pub display_name: String,
+    #[serde(alias = "fileName")]
+    pub file_name: String,
+    // 1 release / 2 beta / 3 alpha
+    #[serde(alias = "releaseType")]
+    pub release_type: u32,
+    #[serde(alias = "fileDate")]
+    pub file_date: String,
+    #[serde(alias = "fileLength")]
+    pub file_length: u64,
+    // 作者不允许第三方启动器下载时为 null
+    #[serde(alias = "downloadUrl")]
+    pub download_url: Option<String>,
+    // 同时包含游戏版本与加载器名称，例如 ["1.20.1", "Forge"]
+    #[serde(alias = "gameVersions", default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub hashes: Vec<FileHash>,
+    #[serde(default)]
+    pub dependencies: Vec<FileDependency>,
+    #[serde(alias = "fileFingerprint")]
+    pub file_fingerprint: u32,
+}
+
+impl File {
+    pub fn sha1(&self) -> Option<&str> {
+        self.hashes
+            .iter()
+            .find(|h| h.algo == 1)
+            .map(|h| h.value.as_str())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct FileHash {
+    pub value: String,
+    // 1 sha1 / 2 md5
+    pub algo: u32,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct FileDependency {
+    #[serde(alias = "modId")]
+    pub mod_id: u32,
+    // 1 embedded / 2 optional / 3 required / 4 tool / 5 incompatible / 6 include
+    #[serde(alias = "relationType")]
+    pub relation_type: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct FingerprintMatches {
+    #[serde(alias = "exactMatches", default)]
+    pub exact_matches: Vec<FingerprintMatch>,
+    #[serde(alias = "unmatchedFingerprints", default)]
+    pub unmatched_fingerprints: Vec<u32>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct FingerprintMatch {
+    // mod id
+    pub id: u32,
+    pub file: File,
+    #[serde(alias = "latestFiles", default)]
+    pub latest_files: Vec<File>,
+}
+
+/*
+
+  <pack>.zip/manifest.json
+
+  {
+    "minecraft": {
+      "version": "1.20.1",
+      "modLoaders": [{ "id": "forge-47.2.0", "primary": true }]
+    },
+    "manifestType": "minecraftModpack",
+    "manifestVersion": 1,
+    "name": "Example Pack",
+    "version": "1.0.0",
+    "author": "Someone",
+    "files": [{ "projectID": 238222, "fileID": 4712868, "required": true }],
+    "overrides": "overrides"
+  }
+
+*/
+#[derive(Deserialize)]
+// 映射源 CurseForge 整合包中的 manifest.json
+pub struct Manifest {
+    pub minecraft: ManifestMinecraft,
+    #[serde(alias = "manifestType")]
+    pub manifest_type: String,
+    #[serde(alias = "manifestVersion")]
+    pub manifest_version: u32,
+    pub name: String,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+    pub overrides: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ManifestMinecraft {
+    pub version: String,
+    #[serde(alias = "modLoaders", default)]
+    pub mod_loaders: Vec<ManifestModLoader>,
+}
+
+#[derive(Deserialize)]
+pub struct ManifestModLoader {
+    // 例如 forge-47.2.0、fabric-0.15.11
+    pub id: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ManifestFile {
+    #[serde(alias = "projectID")]
+    pub project_id: u32,
+    #[serde(alias = "fileID")]
+    pub file_id: u32,
+    // 部分整合包省略了 required，此时视为必需
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
@@ -0,0 +1,47 @@
+use serde::Deserialize;
+
+/*
+
+  GET /v2/versions/loader/1.21
+
+  [
+    {
+      "loader": {
+        "separator": ".",
+        "build": 11,
+        "maven": "net.fabricmc:fabric-loader:0.15.11",
+        "version": "0.15.11",
+        "stable": true
+      },
+      "intermediary": {
+        "maven": "net.fabricmc:intermediary:1.21",
+        "version": "1.21",
+        "stable": true
+      }
+    }
+  ]
+
+*/
+#[derive(Deserialize)]
+// 映射源 meta/v2/versions/loader/<game_version>[index]
+// 加载器的 profile 使用 version::PartialVersion 解析
+pub struct LoaderVersion {
+    pub loader: Loader,
+    pub intermediary: Intermediary,
+}
+
+#[derive(Deserialize)]
+pub struct Loader {
+    pub separator: String,
+    pub build: u32,
+    pub maven: String,
+    pub version: String,
+    pub stable: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct Intermediary {
+    pub maven: String,
+    pub version: String,
+    pub stable: Option<bool>,
+}
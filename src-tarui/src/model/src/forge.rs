@@ -0,0 +1,70 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::version::Libraries;
+
+/*
+
+  installer.jar/install_profile.json
+
+  {
+    "spec": 1,
+    "profile": "forge",
+    "version": "1.21-forge-51.0.33",
+    "minecraft": "1.21",
+    "json": "/version.json",
+    "data": {
+      "BINPATCH": {"client": "/data/client.lzma", "server": "/data/server.lzma"},
+      "MC_SRG": {"client": "[net.minecraft:client:1.21-20240613.152323:srg]", "server": "..."}
+    },
+    "processors": [
+      {
+        "sides": ["client"],
+        "jar": "net.minecraftforge:binarypatcher:1.1.1:fatjar",
+        "classpath": [],
+        "args": ["--clean", "{MC_SRG}", "--output", "{PATCHED}", "--apply", "{BINPATCH}"],
+        "outputs": {"{PATCHED}": "{PATCHED_SHA}"}
+      }
+    ],
+    "libraries": []
+  }
+
+*/
+#[derive(Deserialize)]
+// 映射源 Forge/NeoForge 1.13+ 安装器中的 install_profile.json
+pub struct InstallProfile {
+    pub spec: Option<u32>,
+    pub profile: Option<String>,
+    pub version: String,
+    pub minecraft: String,
+    // 安装器中版本配置的路径，例如 /version.json
+    pub json: String,
+    #[serde(default)]
+    pub data: HashMap<String, DataEntry>,
+    #[serde(default)]
+    pub processors: Vec<Processor>,
+    #[serde(default)]
+    pub libraries: Libraries,
+}
+
+#[derive(Deserialize)]
+// 值的格式：[maven 坐标]、'字面量' 或 /安装器中的文件
+pub struct DataEntry {
+    pub client: String,
+    pub server: String,
+}
+
+#[derive(Deserialize)]
+pub struct Processor {
+    // 为空时客户端和服务端都需要执行
+    pub sides: Option<Vec<String>>,
+    pub jar: String,
+    #[serde(default)]
+    pub classpath: Vec<String>,
+    #[serde(default)]
+    pub args: Vec<String>,
+    // 产物路径 -> sha1，两者都可能引用 data
+    #[serde(default)]
+    pub outputs: HashMap<String, String>,
+}
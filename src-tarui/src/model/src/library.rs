@@ -24,6 +24,7 @@ use serde::Deserialize;
 
 */
 #[derive(Deserialize)]
+#[serde(from = "RawLibrary")]
 // 映射源 version_manifest/uri(Version)/libraries[Library_index]
 pub struct Library {
     pub downloads: Download,
@@ -31,10 +32,68 @@ pub struct Library {
     pub rules: Option<Vec<Rule>>,
 }
 
+#[derive(Deserialize)]
+// 加载器的 library 可能只给出 maven 坐标和仓库地址，例如
+// {"name": "net.fabricmc:fabric-loader:0.15.11", "url": "https://maven.fabricmc.net/"}
+struct RawLibrary {
+    downloads: Option<Download>,
+    name: String,
+    url: Option<String>,
+    rules: Option<Vec<Rule>>,
+    sha1: Option<String>,
+    size: Option<i32>,
+}
+
+pub const MOJANG_MAVEN: &str = "https://libraries.minecraft.net/";
+
+impl From<RawLibrary> for Library {
+    fn from(value: RawLibrary) -> Self {
+        let downloads = value.downloads.unwrap_or_else(|| {
+            let path = maven_path(&value.name).unwrap_or_default();
+            let url = format!(
+                "{}/{}",
+                value.url.as_deref().unwrap_or(MOJANG_MAVEN).trim_end_matches('/'),
+                path
+            );
+
+            Download {
+                artifact: Artifact {
+                    path,
+                    sha1: value.sha1.unwrap_or_default(),
+                    size: value.size.unwrap_or_default(),
+                    url,
+                },
+            }
+        });
+
+        Self {
+            downloads,
+            name: value.name,
+            rules: value.rules,
+        }
+    }
+}
+
+impl Library {
+    // 用于合并时判断是否为同一个 library，natives 等 classifier 视为不同的 library
+    pub fn key(&self) -> String {
+        let coordinate = self.name.split('@').next().unwrap_or_default();
+        let parts: Vec<&str> = coordinate.split(':').collect();
+        match parts.as_slice() {
+            [group, artifact, _, classifier, ..] => {
+                format!("{}:{}:{}", group, artifact, classifier)
+            }
+            [group, artifact, ..] => format!("{}:{}", group, artifact),
+            _ => self.name.clone(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct Rule {
     pub action: String,
-    pub os: Os,
+    // 为空时规则对所有系统生效
+    pub os: Option<Os>,
 }
 
 #[derive(Deserialize)]
@@ -54,3 +113,30 @@ pub struct Artifact {
     pub size: i32,
     pub url: String,
 }
+
+// 将 maven 坐标 group:artifact:version[:classifier][@ext] 转换为仓库中的相对路径
+pub fn maven_path(name: &str) -> Option<String> {
+    let (coordinate, extension) = match name.split_once('@') {
+        Some((coordinate, extension)) => (coordinate, extension),
+        None => (name, "jar"),
+    };
+
+    let mut parts = coordinate.split(':');
+    let group = parts.next()?;
+    let artifact = parts.next()?;
+    let version = parts.next()?;
+    let classifier = parts.next();
+
+    let file = match classifier {
+        Some(classifier) => format!("{}-{}-{}.{}", artifact, version, classifier, extension),
+        None => format!("{}-{}.{}", artifact, version, extension),
+    };
+
+    Some(format!(
+        "{}/{}/{}/{}",
+        group.replace('.', "/"),
+        artifact,
+        version,
+        file
+    ))
+}
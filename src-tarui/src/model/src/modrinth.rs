@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+/*
+
+  GET /v2/search?query=sodium&facets=[["categories:fabric"],["versions:1.21"]]
+
+  {
+    "hits": [
+      {
+        "project_id": "AANobbMI",
+        "project_type": "mod",
+        "slug": "sodium",
+        "author": "jellysquid3",
+        "title": "Sodium",
+        "description": "The fastest and most compatible rendering optimization mod for Minecraft",
+        "categories": ["fabric", "optimization"],
+        "versions": ["1.20.1", "1.21"],
+        "downloads": 54000000,
+        "icon_url": "https://cdn.modrinth.com/data/AANobbMI/icon.png"
+      }
+    ],
+    "offset": 0,
+    "limit": 10,
+    "total_hits": 1
+  }
+
+*/
+#[derive(Deserialize, Serialize)]
+// 映射源 modrinth/v2/search
+pub struct SearchResponse {
+    pub hits: Vec<SearchHit>,
+    pub offset: u32,
+    pub limit: u32,
+    pub total_hits: u32,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct SearchHit {
+    pub project_id: String,
+    pub project_type: String,
+    pub slug: String,
+    pub author: String,
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+// 映射源 modrinth/v2/project/<id|slug>
+pub struct Project {
+    pub id: String,
+    pub slug: String,
+    pub project_type: String,
+    pub title: String,
+    pub description: String,
+    pub body: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    pub client_side: Option<String>,
+    pub server_side: Option<String>,
+    pub downloads: u64,
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub versions: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+// 映射源 modrinth/v2/project/<id>/version[index] 与 modrinth/v2/version/<id>
+pub struct ProjectVersion {
+    pub id: String,
+    pub project_id: String,
+    pub name: String,
+    pub version_number: String,
+    pub changelog: Option<String>,
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    // release / beta / alpha
+    pub version_type: String,
+    pub date_published: String,
+    pub files: Vec<VersionFile>,
+    #[serde(default)]
+    pub dependencies: Vec<VersionDependency>,
+}
+
+impl ProjectVersion {
+    // 主文件，没有标记时取第一个
+    pub fn primary_file(&self) -> Option<&VersionFile> {
+        self.files
+            .iter()
+            .find(|f| f.primary)
+            .or(self.files.first())
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct VersionFile {
+    pub hashes: Hashes,
+    pub url: String,
+    pub filename: String,
+    #[serde(default)]
+    pub primary: bool,
+    pub size: u64,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Hashes {
+    pub sha1: String,
+    pub sha512: String,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct VersionDependency {
+    pub version_id: Option<String>,
+    pub project_id: Option<String>,
+    pub file_name: Option<String>,
+    // required / optional / incompatible / embedded
+    pub dependency_type: String,
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/*
+
+  modrinth.index.json
+
+  {
+    "formatVersion": 1,
+    "game": "minecraft",
+    "versionId": "1.0.0",
+    "name": "Example Pack",
+    "files": [
+      {
+        "path": "mods/sodium.jar",
+        "hashes": { "sha1": "...", "sha512": "..." },
+        "env": { "client": "required", "server": "unsupported" },
+        "downloads": ["https://cdn.modrinth.com/data/AANobbMI/versions/mc1FYQqF/sodium.jar"],
+        "fileSize": 1024
+      }
+    ],
+    "dependencies": { "minecraft": "1.21", "fabric-loader": "0.15.11" }
+  }
+
+*/
+#[derive(Deserialize, Serialize)]
+// 映射源 <pack>.mrpack/modrinth.index.json，导出时按原字段名写回
+pub struct Index {
+    #[serde(rename = "formatVersion")]
+    pub format_version: u32,
+    pub game: String,
+    #[serde(rename = "versionId")]
+    pub version_id: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    pub files: Vec<IndexFile>,
+    // minecraft / forge / neoforge / fabric-loader / quilt-loader -> 版本
+    pub dependencies: HashMap<String, String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct IndexFile {
+    // 相对于实例目录的路径
+    pub path: String,
+    pub hashes: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<Env>,
+    pub downloads: Vec<String>,
+    #[serde(rename = "fileSize")]
+    pub file_size: u64,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct Env {
+    // required / optional / unsupported
+    pub client: String,
+    pub server: String,
+}
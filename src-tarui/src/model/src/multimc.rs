@@ -0,0 +1,51 @@
+use serde::Deserialize;
+
+/*
+
+  <instance>/mmc-pack.json
+
+  {
+    "components": [
+      { "uid": "org.lwjgl3", "version": "3.3.1", "dependencyOnly": true },
+      { "uid": "net.minecraft", "version": "1.20.1", "important": true },
+      { "uid": "net.fabricmc.intermediary", "version": "1.20.1", "dependencyOnly": true },
+      { "uid": "net.fabricmc.fabric-loader", "version": "0.15.11" }
+    ],
+    "formatVersion": 1
+  }
+
+*/
+#[derive(Deserialize)]
+// 映射源 MultiMC / Prism Launcher 实例中的 mmc-pack.json
+pub struct Pack {
+    #[serde(alias = "formatVersion")]
+    pub format_version: u32,
+    #[serde(default)]
+    pub components: Vec<Component>,
+}
+
+impl Pack {
+    pub fn component(&self, uid: &str) -> Option<&Component> {
+        self.components.iter().find(|c| c.uid == uid)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Component {
+    // 例如 net.minecraft、net.minecraftforge、org.lwjgl3
+    pub uid: String,
+    pub version: Option<String>,
+    // 旧格式只记录了 cachedVersion
+    #[serde(alias = "cachedVersion")]
+    pub cached_version: Option<String>,
+    #[serde(alias = "cachedName")]
+    pub cached_name: Option<String>,
+    #[serde(alias = "dependencyOnly", default)]
+    pub dependency_only: bool,
+}
+
+impl Component {
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref().or(self.cached_version.as_deref())
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serde::Deserialize;
 
 use crate::{asset::AssetIndex, library::Library};
@@ -9,16 +11,51 @@ pub type Libraries = Vec<Library>;
 pub struct Version {
     #[serde(alias = "assetIndex")]
     pub asset_index: AssetIndex,
+    pub assets: Option<String>,
     pub downloads: Download,
     pub id: String,
     pub libraries: Libraries,
     #[serde(alias = "mainClass")]
     pub main_class: String,
+    // 1.13 之后的启动参数
+    pub arguments: Option<Arguments>,
+    // 1.13 之前的启动参数
+    #[serde(alias = "minecraftArguments")]
+    pub minecraft_arguments: Option<String>,
     #[serde(alias = "releaseTime")]
     pub release_time: String,
     pub time: String,
     #[serde(alias = "type")]
     pub type_: String,
+    // 游戏本体 jar 所在的版本 id，合并 inheritsFrom 后指向父版本
+    #[serde(default)]
+    pub jar: Option<String>,
+}
+
+#[derive(Deserialize)]
+// 映射源 versions/<id>/<id>.json 中声明了 inheritsFrom 的版本
+// Fabric、Quilt、Forge、OptiFine 等只列出与父版本的差异，所有字段都可能缺失
+pub struct PartialVersion {
+    pub id: String,
+    #[serde(alias = "inheritsFrom")]
+    pub inherits_from: Option<String>,
+    #[serde(alias = "assetIndex")]
+    pub asset_index: Option<AssetIndex>,
+    pub assets: Option<String>,
+    pub downloads: Option<Download>,
+    #[serde(default)]
+    pub libraries: Libraries,
+    #[serde(alias = "mainClass")]
+    pub main_class: Option<String>,
+    pub arguments: Option<Arguments>,
+    #[serde(alias = "minecraftArguments")]
+    pub minecraft_arguments: Option<String>,
+    #[serde(alias = "releaseTime")]
+    pub release_time: Option<String>,
+    pub time: Option<String>,
+    #[serde(alias = "type")]
+    pub type_: Option<String>,
+    pub jar: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -33,3 +70,63 @@ pub struct Client {
     pub size: u32,
     pub url: String,
 }
+
+/*
+
+  "arguments": {
+    "game": [
+      "--username",
+      "${auth_player_name}",
+      {
+        "rules": [{"action": "allow", "features": {"is_demo_user": true}}],
+        "value": "--demo"
+      }
+    ],
+    "jvm": [
+      {
+        "rules": [{"action": "allow", "os": {"name": "osx"}}],
+        "value": ["-XstartOnFirstThread"]
+      },
+      "-cp",
+      "${classpath}"
+    ]
+  }
+
+*/
+#[derive(Deserialize, Default)]
+pub struct Arguments {
+    #[serde(default)]
+    pub game: Vec<Argument>,
+    #[serde(default)]
+    pub jvm: Vec<Argument>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Argument {
+    Plain(String),
+    Conditional {
+        rules: Vec<ArgumentRule>,
+        value: ArgumentValue,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+#[derive(Deserialize)]
+pub struct ArgumentRule {
+    pub action: String,
+    pub os: Option<ArgumentOs>,
+    pub features: Option<HashMap<String, bool>>,
+}
+
+#[derive(Deserialize)]
+pub struct ArgumentOs {
+    pub name: Option<String>,
+    pub arch: Option<String>,
+}
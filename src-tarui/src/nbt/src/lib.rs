@@ -0,0 +1,7 @@
+mod read;
+mod tag;
+mod write;
+
+pub use read::*;
+pub use tag::*;
+pub use write::*;
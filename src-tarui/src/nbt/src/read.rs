@@ -0,0 +1,285 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use flate2::read::GzDecoder;
+
+use crate::tag::{Compound, Tag};
+
+// 嵌套深度上限，与 Minecraft 一致，避免损坏或恶意的文件导致栈溢出
+const MAX_DEPTH: usize = 512;
+
+// gzip 压缩数据的文件头
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.into())
+}
+
+// 读取未压缩的 NBT，返回根标签的名称与根标签
+pub fn from_reader<R: Read>(reader: &mut R) -> io::Result<(String, Tag)> {
+    let id = read_u8(reader)?;
+    if id != 10 {
+        return Err(invalid("Root tag must be a compound"));
+    }
+    let name = read_string(reader)?;
+    let tag = read_payload(reader, id, 0)?;
+    Ok((name, tag))
+}
+
+// 读取 NBT，自动识别 gzip 压缩（level.dat）与未压缩（servers.dat）的数据
+pub fn from_bytes(bytes: &[u8]) -> io::Result<(String, Tag)> {
+    if bytes.starts_with(&GZIP_MAGIC) {
+        from_reader(&mut GzDecoder::new(bytes))
+    } else {
+        from_reader(&mut &bytes[..])
+    }
+}
+
+pub fn read_file(path: &Path) -> io::Result<(String, Tag)> {
+    from_bytes(&fs::read(path)?)
+}
+
+fn read_array<const N: usize, R: Read>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut buffer = [0; N];
+    reader.read_exact(&mut buffer)?;
+    Ok(buffer)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> io::Result<u8> {
+    Ok(read_array::<1, R>(reader)?[0])
+}
+
+// 数组与列表的长度，负数视为损坏的数据
+fn read_len<R: Read>(reader: &mut R) -> io::Result<usize> {
+    let len = i32::from_be_bytes(read_array(reader)?);
+    usize::try_from(len).map_err(|_| invalid(format!("Invalid length: {}", len)))
+}
+
+// 按实际读到的数据分配内存，长度字段损坏时不会预先分配大量内存
+fn read_bytes<R: Read>(reader: &mut R, len: usize) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+fn read_string<R: Read>(reader: &mut R) -> io::Result<String> {
+    let len = u16::from_be_bytes(read_array(reader)?) as usize;
+    decode_modified_utf8(&read_bytes(reader, len)?)
+}
+
+// Java 的 modified UTF-8：\0 编码为 C0 80，辅助平面的字符编码为两个代理项各自的三字节形式，
+// 与标准 UTF-8 不同时才按 UTF-16 代码单元逐个解码
+fn decode_modified_utf8(bytes: &[u8]) -> io::Result<String> {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Ok(text.to_string());
+    }
+
+    let continuation = |index: usize| {
+        bytes
+            .get(index)
+            .filter(|byte| *byte & 0xC0 == 0x80)
+            .map(|byte| (*byte & 0x3F) as u16)
+            .ok_or_else(|| invalid("Invalid modified UTF-8 string"))
+    };
+    let mut units = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let first = bytes[index] as u16;
+        let (unit, width) = if first < 0x80 {
+            (first, 1)
+        } else if first & 0xE0 == 0xC0 {
+            (((first & 0x1F) << 6) | continuation(index + 1)?, 2)
+        } else if first & 0xF0 == 0xE0 {
+            let unit =
+                ((first & 0x0F) << 12) | (continuation(index + 1)? << 6) | continuation(index + 2)?;
+            (unit, 3)
+        } else {
+            return Err(invalid("Invalid modified UTF-8 string"));
+        };
+        units.push(unit);
+        index += width;
+    }
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn read_payload<R: Read>(reader: &mut R, id: u8, depth: usize) -> io::Result<Tag> {
+    if depth > MAX_DEPTH {
+        return Err(invalid("NBT is nested too deeply"));
+    }
+    Ok(match id {
+        1 => Tag::Byte(i8::from_be_bytes(read_array(reader)?)),
+        2 => Tag::Short(i16::from_be_bytes(read_array(reader)?)),
+        3 => Tag::Int(i32::from_be_bytes(read_array(reader)?)),
+        4 => Tag::Long(i64::from_be_bytes(read_array(reader)?)),
+        5 => Tag::Float(f32::from_be_bytes(read_array(reader)?)),
+        6 => Tag::Double(f64::from_be_bytes(read_array(reader)?)),
+        7 => {
+            let len = read_len(reader)?;
+            Tag::ByteArray(
+                read_bytes(reader, len)?
+                    .into_iter()
+                    .map(|b| b as i8)
+                    .collect(),
+            )
+        }
+        8 => Tag::String(read_string(reader)?),
+        9 => {
+            let element = read_u8(reader)?;
+            let len = read_len(reader)?;
+            let mut list = Vec::new();
+            for _ in 0..len {
+                list.push(read_payload(reader, element, depth + 1)?);
+            }
+            Tag::List(list)
+        }
+        10 => {
+            let mut compound = Compound::new();
+            loop {
+                let id = read_u8(reader)?;
+                if id == 0 {
+                    break;
+                }
+                let name = read_string(reader)?;
+                compound.insert(name, read_payload(reader, id, depth + 1)?);
+            }
+            Tag::Compound(compound)
+        }
+        11 => {
+            let len = read_len(reader)?;
+            let bytes = read_bytes(
+                reader,
+                len.checked_mul(4)
+                    .ok_or_else(|| invalid("Array is too long"))?,
+            )?;
+            Tag::IntArray(
+                bytes
+                    .chunks_exact(4)
+                    .map(|chunk| i32::from_be_bytes(chunk.try_into().unwrap()))
+                    .collect(),
+            )
+        }
+        12 => {
+            let len = read_len(reader)?;
+            let bytes = read_bytes(
+                reader,
+                len.checked_mul(8)
+                    .ok_or_else(|| invalid("Array is too long"))?,
+            )?;
+            Tag::LongArray(
+                bytes
+                    .chunks_exact(8)
+                    .map(|chunk| i64::from_be_bytes(chunk.try_into().unwrap()))
+                    .collect(),
+            )
+        }
+        _ => return Err(invalid(format!("Unknown tag type: {}", id))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hello_world() {
+        let (name, tag) = from_bytes(include_bytes!("../samples/hello_world.nbt")).unwrap();
+        assert_eq!("hello world", name);
+        assert_eq!(Some("Bananrama"), tag.get("name").and_then(Tag::as_str));
+    }
+
+    #[test]
+    fn test_bigtest() {
+        // gzip 压缩
+        let (name, tag) = from_bytes(include_bytes!("../samples/bigtest.nbt")).unwrap();
+        assert_eq!("Level", name);
+        assert_eq!(Some(i64::MAX), tag.get("longTest").and_then(Tag::as_i64));
+        assert_eq!(Some(32767), tag.get("shortTest").and_then(Tag::as_i64));
+        assert_eq!(
+            Some(i32::MAX as i64),
+            tag.get("intTest").and_then(Tag::as_i64)
+        );
+        assert_eq!(Some(127), tag.get("byteTest").and_then(Tag::as_i64));
+        assert_eq!(
+            Some("HELLO WORLD THIS IS A TEST STRING ÅÄÖ!"),
+            tag.get("stringTest").and_then(Tag::as_str)
+        );
+        assert_eq!(
+            Some(0.4982314705848694),
+            tag.get("floatTest").and_then(Tag::as_f64)
+        );
+        assert_eq!(
+            Some(0.4931287132182315),
+            tag.get("doubleTest").and_then(Tag::as_f64)
+        );
+        assert_eq!(
+            Some("Eggbert"),
+            tag.get_path(&["nested compound test", "egg", "name"])
+                .and_then(Tag::as_str)
+        );
+
+        let longs: Vec<i64> = tag
+            .get("listTest (long)")
+            .and_then(Tag::as_list)
+            .unwrap()
+            .iter()
+            .filter_map(Tag::as_i64)
+            .collect();
+        assert_eq!(vec![11, 12, 13, 14, 15], longs);
+        let compounds = tag
+            .get("listTest (compound)")
+            .and_then(Tag::as_list)
+            .unwrap();
+        assert_eq!(
+            Some("Compound tag #1"),
+            compounds[1].get("name").and_then(Tag::as_str)
+        );
+        assert_eq!(
+            Some(1264099775885),
+            compounds[1].get("created-on").and_then(Tag::as_i64)
+        );
+
+        let (_, bytes) = tag
+            .as_compound()
+            .unwrap()
+            .iter()
+            .find(|(key, _)| key.starts_with("byteArrayTest"))
+            .unwrap();
+        let Tag::ByteArray(bytes) = bytes else {
+            panic!("{:?}", bytes);
+        };
+        assert_eq!(1000, bytes.len());
+        assert!(bytes
+            .iter()
+            .enumerate()
+            .all(|(n, b)| *b as usize == (n * n * 255 + n * 7) % 100));
+    }
+
+    #[test]
+    fn test_modified_utf8() {
+        // "a\0😀"，\0 为 C0 80，😀 为代理项 D83D DE00
+        let bytes = [0x61, 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80];
+        assert_eq!("a\0😀", decode_modified_utf8(&bytes).unwrap());
+        assert!(decode_modified_utf8(&[0xE0, 0x80]).is_err());
+    }
+
+    #[test]
+    fn test_malformed() {
+        let bytes = include_bytes!("../samples/hello_world.nbt");
+        assert!(from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        // 根标签不是复合标签
+        assert!(from_bytes(&[8, 0, 0, 0, 0]).is_err());
+        // 负数长度
+        assert!(from_bytes(&[10, 0, 0, 7, 0, 1, b'a', 0xFF, 0xFF, 0xFF, 0xFF, 0]).is_err());
+
+        // 过深的嵌套
+        let mut nested = vec![10, 0, 0];
+        for _ in 0..MAX_DEPTH + 1 {
+            nested.extend([10, 0, 1, b'a']);
+        }
+        assert!(from_bytes(&nested).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+use crate::tag::Tag;
+
+fn invalid(message: impl Into<String>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message.into())
+}
+
+// 写入未压缩的 NBT，根标签必须是复合标签
+pub fn to_writer<W: Write>(writer: &mut W, name: &str, tag: &Tag) -> io::Result<()> {
+    if !matches!(tag, Tag::Compound(_)) {
+        return Err(invalid("Root tag must be a compound"));
+    }
+    writer.write_all(&[tag.id()])?;
+    write_string(writer, name)?;
+    write_payload(writer, tag)
+}
+
+pub fn to_bytes(name: &str, tag: &Tag) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    to_writer(&mut bytes, name, tag)?;
+    Ok(bytes)
+}
+
+// 写入未压缩的 NBT 文件（servers.dat），先写入临时文件再替换，避免写入中断时损坏原文件
+pub fn write_file(path: &Path, name: &str, tag: &Tag) -> io::Result<()> {
+    let bytes = to_bytes(name, tag)?;
+    let temp = path.with_extension("dat_tmp");
+    fs::write(&temp, bytes)?;
+    fs::rename(&temp, path)
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> io::Result<()> {
+    let len = i32::try_from(len).map_err(|_| invalid("Array is too long"))?;
+    writer.write_all(&len.to_be_bytes())
+}
+
+fn write_string<W: Write>(writer: &mut W, text: &str) -> io::Result<()> {
+    let bytes = encode_modified_utf8(text);
+    let len = u16::try_from(bytes.len()).map_err(|_| invalid("String is too long"))?;
+    writer.write_all(&len.to_be_bytes())?;
+    writer.write_all(&bytes)
+}
+
+// Java 的 modified UTF-8：\0 编码为 C0 80，辅助平面的字符按 UTF-16 代理项分别编码为三字节
+fn encode_modified_utf8(text: &str) -> Vec<u8> {
+    if !text.chars().any(|c| c == '\0' || c as u32 > 0xFFFF) {
+        return text.as_bytes().to_vec();
+    }
+
+    let mut bytes = Vec::with_capacity(text.len());
+    for unit in text.encode_utf16() {
+        match unit {
+            0x01..=0x7F => bytes.push(unit as u8),
+            0x00 | 0x80..=0x7FF => {
+                bytes.push(0xC0 | (unit >> 6) as u8);
+                bytes.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                bytes.push(0xE0 | (unit >> 12) as u8);
+                bytes.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                bytes.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    bytes
+}
+
+fn write_payload<W: Write>(writer: &mut W, tag: &Tag) -> io::Result<()> {
+    match tag {
+        Tag::Byte(value) => writer.write_all(&value.to_be_bytes()),
+        Tag::Short(value) => writer.write_all(&value.to_be_bytes()),
+        Tag::Int(value) => writer.write_all(&value.to_be_bytes()),
+        Tag::Long(value) => writer.write_all(&value.to_be_bytes()),
+        Tag::Float(value) => writer.write_all(&value.to_be_bytes()),
+        Tag::Double(value) => writer.write_all(&value.to_be_bytes()),
+        Tag::ByteArray(values) => {
+            write_len(writer, values.len())?;
+            let bytes: Vec<u8> = values.iter().map(|b| *b as u8).collect();
+            writer.write_all(&bytes)
+        }
+        Tag::String(value) => write_string(writer, value),
+        Tag::List(values) => {
+            // 空列表的元素类型为 TAG_End，列表中的元素类型必须一致
+            let element = values.first().map_or(0, Tag::id);
+            if values.iter().any(|value| value.id() != element) {
+                return Err(invalid("List elements must have the same type"));
+            }
+            writer.write_all(&[element])?;
+            write_len(writer, values.len())?;
+            for value in values {
+                write_payload(writer, value)?;
+            }
+            Ok(())
+        }
+        Tag::Compound(compound) => {
+            for (name, value) in compound {
+                writer.write_all(&[value.id()])?;
+                write_string(writer, name)?;
+                write_payload(writer, value)?;
+            }
+            writer.write_all(&[0])
+        }
+        Tag::IntArray(values) => {
+            write_len(writer, values.len())?;
+            for value in values {
+                writer.write_all(&value.to_be_bytes())?;
+            }
+            Ok(())
+        }
+        Tag::LongArray(values) => {
+            write_len(writer, values.len())?;
+            for value in values {
+                writer.write_all(&value.to_be_bytes())?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read::from_bytes;
+    use crate::tag::Compound;
+
+    #[test]
+    fn test_round_trip() {
+        let (name, tag) = from_bytes(include_bytes!("../samples/bigtest.nbt")).unwrap();
+        let bytes = to_bytes(&name, &tag).unwrap();
+        assert_eq!((name, tag), from_bytes(&bytes).unwrap());
+
+        // 未压缩的文件写回后与原文件相同
+        let hello_world = include_bytes!("../samples/hello_world.nbt");
+        let (name, tag) = from_bytes(hello_world).unwrap();
+        assert_eq!(hello_world.to_vec(), to_bytes(&name, &tag).unwrap());
+    }
+
+    #[test]
+    fn test_encode_modified_utf8() {
+        assert_eq!(
+            vec![0x61, 0xC0, 0x80, 0xED, 0xA0, 0xBD, 0xED, 0xB8, 0x80],
+            encode_modified_utf8("a\0😀")
+        );
+        assert_eq!("ÅÄÖ".as_bytes().to_vec(), encode_modified_utf8("ÅÄÖ"));
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(to_bytes("", &Tag::Int(1)).is_err());
+        let mut compound = Compound::new();
+        compound.insert(
+            "list".to_string(),
+            Tag::List(vec![Tag::Int(1), Tag::String("a".to_string())]),
+        );
+        assert!(to_bytes("", &Tag::Compound(compound)).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+use model::curseforge::*;
+
+use crate::Parse;
+
+impl Parse<&str> for SearchResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<SearchResponse>(value)
+    }
+}
+
+impl Parse<&str> for ModResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<ModResponse>(value)
+    }
+}
+
+impl Parse<&str> for ModsResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<ModsResponse>(value)
+    }
+}
+
+impl Parse<&str> for Manifest {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Manifest>(value)
+    }
+}
+
+impl Parse<&str> for FilesResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<FilesResponse>(value)
+    }
+}
+
+impl Parse<&str> for FileResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<FileResponse>(value)
+    }
+}
+
+impl Parse<&str> for FingerprintResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<FingerprintResponse>(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fingerprint_response() {
+        let response = FingerprintResponse::parse(
+            r#"{"data": {"isCacheBuilt": true, "exactMatches": [{"id": 238222, "file": {"id": 4712868, "gameId": 432, "modId": 238222, "isAvailable": true, "displayName": "jei-1.20.1-forge-15.2.0.27.jar", "fileName": "jei-1.20.1-forge-15.2.0.27.jar", "releaseType": 1, "fileStatus": 4, "hashes": [{"value": "d7b1c3b2b0b5", "algo": 1}, {"value": "aa", "algo": 2}], "fileDate": "2023-08-20T22:31:42.453Z", "fileLength": 1316456, "downloadCount": 1, "downloadUrl": null, "gameVersions": ["Forge", "1.20.1"], "dependencies": [{"modId": 1, "relationType": 3}], "fileFingerprint": 1234567890}, "latestFiles": []}], "exactFingerprints": [1234567890], "unmatchedFingerprints": [42]}}"#,
+        )
+        .unwrap_or_else(|err| panic!("{:?}", err));
+
+        let matched = &response.data.exact_matches[0];
+        assert_eq!(238222, matched.id);
+        assert_eq!(Some("d7b1c3b2b0b5"), matched.file.sha1());
+        assert_eq!(None, matched.file.download_url);
+        assert_eq!(3, matched.file.dependencies[0].relation_type);
+        assert_eq!(vec![42], response.data.unmatched_fingerprints);
+    }
+}
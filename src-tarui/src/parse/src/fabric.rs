@@ -0,0 +1,31 @@
+use model::fabric::*;
+
+use crate::Parse;
+
+impl Parse<&str> for Vec<LoaderVersion> {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Vec<LoaderVersion>>(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loader_versions() {
+        let versions = Vec::<LoaderVersion>::parse(
+            r#"[{"loader": {"separator": ".", "build": 11, "maven": "net.fabricmc:fabric-loader:0.15.11", "version": "0.15.11", "stable": true}, "intermediary": {"maven": "net.fabricmc:intermediary:1.21", "version": "1.21", "stable": true}, "launcherMeta": {"version": 2}}]"#,
+        ).unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!(1, versions.len());
+        assert_eq!("0.15.11", versions[0].loader.version);
+        assert_eq!(Some(true), versions[0].loader.stable);
+        assert_eq!(
+            "net.fabricmc:intermediary:1.21",
+            versions[0].intermediary.maven
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use model::forge::*;
+
+use crate::Parse;
+
+impl Parse<&str> for InstallProfile {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<InstallProfile>(value)
+    }
+}
+
+impl Parse<&str> for Processor {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Processor>(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_profile() {
+        let profile = InstallProfile::parse(
+            r#"{"_comment": ["Please do not automate the download and installation of Forge."], "spec": 1, "profile": "forge", "version": "1.21-forge-51.0.33", "path": null, "minecraft": "1.21", "serverJarPath": "{LIBRARY_DIR}/net/minecraft/server/{MINECRAFT_VERSION}/server-{MINECRAFT_VERSION}.jar", "data": {"BINPATCH": {"client": "/data/client.lzma", "server": "/data/server.lzma"}, "MC_SRG": {"client": "[net.minecraft:client:1.21-20240613.152323:srg]", "server": "[net.minecraft:server:1.21-20240613.152323:srg]"}, "PATCHED_SHA": {"client": "'4a3e7e5e2e2b9d3f2a1c4d5e6f7a8b9c0d1e2f3a'", "server": "''"}}, "processors": [{"sides": ["client"], "jar": "net.minecraftforge:binarypatcher:1.1.1:fatjar", "classpath": [], "args": ["--clean", "{MC_SRG}", "--output", "{PATCHED}", "--apply", "{BINPATCH}"], "outputs": {"{PATCHED}": "{PATCHED_SHA}"}}, {"jar": "net.minecraftforge:installertools:1.4.1:fatjar", "args": ["--task", "DOWNLOAD_MOJMAPS", "--version", "{MINECRAFT_VERSION}", "--side", "{SIDE}"]}], "icon": "", "json": "/version.json", "logo": "/big_logo.png", "mirrorList": "", "welcome": "", "libraries": [{"name": "net.minecraftforge:binarypatcher:1.1.1:fatjar", "downloads": {"artifact": {"path": "net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1-fatjar.jar", "url": "https://maven.minecraftforge.net/net/minecraftforge/binarypatcher/1.1.1/binarypatcher-1.1.1-fatjar.jar", "sha1": "54c1c4ab4b0e5a5e4b7e5b9d7c0c2e4b1a5f3c2d", "size": 1018045}}}]}"#,
+        )
+        .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!("1.21-forge-51.0.33", profile.version);
+        assert_eq!("1.21", profile.minecraft);
+        assert_eq!("/version.json", profile.json);
+        assert_eq!("/data/client.lzma", profile.data["BINPATCH"].client);
+        assert_eq!(2, profile.processors.len());
+        assert_eq!(
+            Some(vec!["client".to_string()]),
+            profile.processors[0].sides
+        );
+        assert_eq!("{PATCHED_SHA}", profile.processors[0].outputs["{PATCHED}"]);
+        assert_eq!(None, profile.processors[1].sides);
+        assert!(profile.processors[1].outputs.is_empty());
+        assert_eq!(
+            "net.minecraftforge:binarypatcher:1.1.1:fatjar",
+            profile.libraries[0].name
+        );
+    }
+}
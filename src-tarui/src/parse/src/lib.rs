@@ -1,5 +1,11 @@
 pub mod asset;
+pub mod curseforge;
+pub mod fabric;
+pub mod forge;
 pub mod library;
+pub mod modrinth;
+pub mod mrpack;
+pub mod multimc;
 pub mod version;
 pub mod version_manifest;
 
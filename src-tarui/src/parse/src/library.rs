@@ -69,6 +69,38 @@ mod tests {
         );
         let rules = &library.rules.unwrap();
         assert_eq!("allow", rules[0].action);
-        assert_eq!("osx", rules[0].os.name);
+        assert_eq!("osx", rules[0].os.as_ref().unwrap().name);
+
+        // 没有 os 的规则对所有系统生效
+        let rule = Rule::parse(r#"{"action": "allow"}"#).unwrap_or_else(|err| panic!("{:?}", err));
+        assert!(rule.os.is_none());
+    }
+
+    #[test]
+    fn test_maven_library() {
+        let library = Library::parse(
+            r#"{"name": "org.ow2.asm:asm:9.6", "url": "https://maven.fabricmc.net/", "sha1": "aa205cf0a06dbd8e04ece91c0b37c3f5d567546a", "size": 123598}"#,
+        )
+        .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!("org/ow2/asm/asm/9.6/asm-9.6.jar", library.downloads.artifact.path);
+        assert_eq!(
+            "https://maven.fabricmc.net/org/ow2/asm/asm/9.6/asm-9.6.jar",
+            library.downloads.artifact.url
+        );
+        assert_eq!(
+            "aa205cf0a06dbd8e04ece91c0b37c3f5d567546a",
+            library.downloads.artifact.sha1
+        );
+        assert_eq!(123598, library.downloads.artifact.size);
+        assert_eq!("org.ow2.asm:asm", library.key());
+
+        let library = Library::parse(r#"{"name": "org.lwjgl:lwjgl:3.3.3:natives-linux"}"#)
+            .unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(
+            "https://libraries.minecraft.net/org/lwjgl/lwjgl/3.3.3/lwjgl-3.3.3-natives-linux.jar",
+            library.downloads.artifact.url
+        );
+        assert_eq!("org.lwjgl:lwjgl:natives-linux", library.key());
     }
 }
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use model::modrinth::*;
+
+use crate::Parse;
+
+impl Parse<&str> for SearchResponse {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<SearchResponse>(value)
+    }
+}
+
+impl Parse<&str> for Project {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Project>(value)
+    }
+}
+
+impl Parse<&str> for ProjectVersion {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<ProjectVersion>(value)
+    }
+}
+
+impl Parse<&str> for Vec<ProjectVersion> {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Vec<ProjectVersion>>(value)
+    }
+}
+
+// version_files 接口返回 hash -> 版本
+impl Parse<&str> for HashMap<String, ProjectVersion> {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<HashMap<String, ProjectVersion>>(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_versions() {
+        let versions = Vec::<ProjectVersion>::parse(
+            r#"[{"id": "mc1FYQqF", "project_id": "AANobbMI", "author_id": "DzLrfrbK", "featured": false, "name": "Sodium 0.5.11", "version_number": "mc1.21-0.5.11", "changelog": "Fixes", "date_published": "2024-07-09T02:20:30.471Z", "downloads": 10, "version_type": "release", "status": "listed", "files": [{"hashes": {"sha1": "aa", "sha512": "bb"}, "url": "https://cdn.modrinth.com/data/AANobbMI/versions/mc1FYQqF/sodium.jar", "filename": "sodium.jar", "primary": true, "size": 1024, "file_type": null}], "dependencies": [{"version_id": null, "project_id": "P7dR8mSH", "file_name": null, "dependency_type": "required"}], "game_versions": ["1.21"], "loaders": ["fabric", "quilt"]}]"#,
+        ).unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!(1, versions.len());
+        assert_eq!("mc1.21-0.5.11", versions[0].version_number);
+        assert_eq!("sodium.jar", versions[0].primary_file().unwrap().filename);
+        assert_eq!(
+            Some("P7dR8mSH".to_string()),
+            versions[0].dependencies[0].project_id
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let response = SearchResponse::parse(
+            r#"{"hits": [{"project_id": "AANobbMI", "project_type": "mod", "slug": "sodium", "author": "jellysquid3", "title": "Sodium", "description": "Rendering", "categories": ["fabric"], "versions": ["1.21"], "downloads": 5, "icon_url": null, "follows": 1}], "offset": 0, "limit": 10, "total_hits": 1}"#,
+        ).unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!(1, response.total_hits);
+        assert_eq!("sodium", response.hits[0].slug);
+    }
+}
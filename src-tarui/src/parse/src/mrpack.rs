@@ -0,0 +1,11 @@
+use model::mrpack::*;
+
+use crate::Parse;
+
+impl Parse<&str> for Index {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Index>(value)
+    }
+}
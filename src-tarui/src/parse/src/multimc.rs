@@ -0,0 +1,37 @@
+use model::multimc::*;
+
+use crate::Parse;
+
+impl Parse<&str> for Pack {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Pack>(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack() {
+        let pack = Pack::parse(
+            r#"{"components": [{"cachedName": "LWJGL 3", "cachedVersion": "3.3.1", "cachedVolatile": true, "dependencyOnly": true, "uid": "org.lwjgl3", "version": "3.3.1"}, {"cachedName": "Minecraft", "cachedRequires": [{"suggests": "3.3.1", "uid": "org.lwjgl3"}], "cachedVersion": "1.20.1", "important": true, "uid": "net.minecraft", "version": "1.20.1"}, {"cachedName": "Forge", "cachedVersion": "47.2.0", "uid": "net.minecraftforge"}], "formatVersion": 1}"#,
+        )
+        .unwrap_or_else(|err| panic!("{:?}", err));
+
+        assert_eq!(1, pack.format_version);
+        assert_eq!(
+            Some("1.20.1"),
+            pack.component("net.minecraft").and_then(|c| c.version())
+        );
+        assert!(pack.component("org.lwjgl3").unwrap().dependency_only);
+        // 没有 version 时使用 cachedVersion
+        assert_eq!(
+            Some("47.2.0"),
+            pack.component("net.minecraftforge")
+                .and_then(|c| c.version())
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::{collections::HashSet, path::Path};
+
 use crate::Parse;
 use model::version::*;
 
@@ -25,10 +27,126 @@ impl Parse<&str> for Client {
     }
 }
 
+impl Parse<&str> for PartialVersion {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<PartialVersion>(value)
+    }
+}
+
+impl Parse<&str> for Arguments {
+    type Error = serde_json::Error;
+
+    fn parse(value: &str) -> Result<Self, Self::Error> {
+        serde_json::from_str::<Arguments>(value)
+    }
+}
+
+// 读取 versions/<id>/<id>.json，沿 inheritsFrom 逐级读取父版本后合并为完整的版本
+pub fn load(versions_dir: &Path, id: &str) -> Result<Version, Box<dyn std::error::Error>> {
+    let mut visited: Vec<String> = Vec::new();
+    let mut chain = Vec::new();
+    let mut next = Some(id.to_string());
+
+    while let Some(id) = next {
+        if visited.contains(&id) {
+            visited.push(id);
+            return Err(format!("Cyclic inheritsFrom: {}", visited.join(" -> ")).into());
+        }
+
+        let config_path = versions_dir.join(&id).join(format!("{}.json", id));
+        let text = std::fs::read_to_string(&config_path)
+            .map_err(|err| format!("Version: {} not found ({})", id, err))?;
+        let version = PartialVersion::parse(&text)?;
+
+        next = version.inherits_from.clone();
+        visited.push(id);
+        chain.push(version);
+    }
+
+    // 从最顶层的父版本开始，依次用子版本覆盖
+    let mut chain = chain.into_iter().rev();
+    let mut merged = chain.next().ok_or(format!("Version: {} not found", id))?;
+    for child in chain {
+        merged = merge(merged, child);
+    }
+
+    complete(merged)
+}
+
+// 将子版本合并到父版本上，子版本的字段优先
+pub fn merge(parent: PartialVersion, child: PartialVersion) -> PartialVersion {
+    // 子版本的 library 放在前面，按 group:artifact 覆盖父版本中的同名 library
+    let mut libraries = child.libraries;
+    let keys: HashSet<String> = libraries.iter().map(|library| library.key()).collect();
+    libraries.extend(
+        parent
+            .libraries
+            .into_iter()
+            .filter(|library| !keys.contains(&library.key())),
+    );
+
+    // 参数追加在父版本之后
+    let arguments = match (parent.arguments, child.arguments) {
+        (Some(mut parent), Some(child)) => {
+            parent.game.extend(child.game);
+            parent.jvm.extend(child.jvm);
+            Some(parent)
+        }
+        (parent, child) => child.or(parent),
+    };
+
+    // 子版本没有自己的本体时沿用父版本的 jar
+    let jar = match (child.jar, &child.downloads) {
+        (Some(jar), _) => Some(jar),
+        (None, Some(_)) => None,
+        (None, None) => Some(parent.jar.unwrap_or(parent.id)),
+    };
+
+    PartialVersion {
+        id: child.id,
+        inherits_from: parent.inherits_from,
+        asset_index: child.asset_index.or(parent.asset_index),
+        assets: child.assets.or(parent.assets),
+        downloads: child.downloads.or(parent.downloads),
+        libraries,
+        main_class: child.main_class.or(parent.main_class),
+        arguments,
+        minecraft_arguments: child.minecraft_arguments.or(parent.minecraft_arguments),
+        release_time: child.release_time.or(parent.release_time),
+        time: child.time.or(parent.time),
+        type_: child.type_.or(parent.type_),
+        jar,
+    }
+}
+
+// 合并后的版本必须包含启动所需的字段
+fn complete(version: PartialVersion) -> Result<Version, Box<dyn std::error::Error>> {
+    let missing = |field: &str| format!("Version: {} is missing {}", version.id, field);
+
+    Ok(Version {
+        asset_index: version.asset_index.ok_or_else(|| missing("assetIndex"))?,
+        assets: version.assets,
+        downloads: version.downloads.ok_or_else(|| missing("downloads"))?,
+        libraries: version.libraries,
+        main_class: version.main_class.ok_or_else(|| missing("mainClass"))?,
+        arguments: version.arguments,
+        minecraft_arguments: version.minecraft_arguments,
+        release_time: version.release_time.unwrap_or_default(),
+        time: version.time.unwrap_or_default(),
+        type_: version.type_.unwrap_or_default(),
+        jar: version.jar,
+        id: version.id,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const VANILLA: &str = r#"{"assetIndex": {"id": "17", "sha1": "fab15439bdef669e389e25e815eee8f1b2aa915e", "size": 447033, "totalSize": 799252591, "url": "https://piston-meta.mojang.com/v1/packages/fab15439bdef669e389e25e815eee8f1b2aa915e/17.json"}, "downloads": {"client": {"sha1": "0e9a07b9bb3390602f977073aa12884a4ce12431", "size": 26836080, "url": "https://piston-data.mojang.com/v1/objects/0e9a07b9bb3390602f977073aa12884a4ce12431/client.jar"}}, "id": "1.21", "libraries": [{"downloads": {"artifact": {"path": "org/ow2/asm/asm/9.3/asm-9.3.jar", "sha1": "8e6300ef51c1d801a7ed62d07cd221aca3a90640", "size": 122004, "url": "https://libraries.minecraft.net/org/ow2/asm/asm/9.3/asm-9.3.jar"}}, "name": "org.ow2.asm:asm:9.3"}], "mainClass": "net.minecraft.client.main.Main", "releaseTime": "2024-06-13T08:24:03+00:00", "time": "2024-06-13T08:32:38+00:00", "type": "release"}"#;
+
     #[test]
     fn test_version() {
         let game = Version::parse(
@@ -46,4 +164,64 @@ mod tests {
         assert_eq!("2024-06-13T08:32:38+00:00", game.time);
         assert_eq!("release", game.type_);
     }
+
+    #[test]
+    fn test_load_inherits_from() {
+        let versions_dir = &std::env::temp_dir()
+            .join("mcl-parse-test-load")
+            .join("versions");
+        let _ = std::fs::remove_dir_all(versions_dir);
+
+        std::fs::create_dir_all(versions_dir.join("1.21")).unwrap();
+        std::fs::write(versions_dir.join("1.21").join("1.21.json"), VANILLA).unwrap();
+
+        let fabric = "fabric-loader-0.15.11-1.21";
+        std::fs::create_dir_all(versions_dir.join(fabric)).unwrap();
+        std::fs::write(
+            versions_dir.join(fabric).join(format!("{}.json", fabric)),
+            r#"{"id": "fabric-loader-0.15.11-1.21", "inheritsFrom": "1.21", "releaseTime": "2024-06-13T08:24:03+00:00", "time": "2024-06-13T08:24:03+00:00", "type": "release", "mainClass": "net.fabricmc.loader.impl.launch.knot.KnotClient", "arguments": {"game": [], "jvm": ["-DFabricMcEmu= net.minecraft.client.main.Main "]}, "libraries": [{"name": "org.ow2.asm:asm:9.6", "url": "https://maven.fabricmc.net/"}, {"name": "net.fabricmc:fabric-loader:0.15.11", "url": "https://maven.fabricmc.net/"}]}"#,
+        )
+        .unwrap();
+
+        let vanilla = load(versions_dir, "1.21").unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(None, vanilla.jar);
+
+        let version = load(versions_dir, fabric).unwrap_or_else(|err| panic!("{:?}", err));
+        assert_eq!(fabric, version.id);
+        assert_eq!(Some("1.21".to_string()), version.jar);
+        assert_eq!(
+            "net.fabricmc.loader.impl.launch.knot.KnotClient",
+            version.main_class
+        );
+        assert_eq!("17", version.asset_index.id);
+        assert_eq!(1, version.arguments.unwrap().jvm.len());
+
+        // 加载器的 asm 覆盖原版的 asm
+        assert_eq!(2, version.libraries.len());
+        assert_eq!("org.ow2.asm:asm:9.6", version.libraries[0].name);
+        assert_eq!(
+            "net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar",
+            version.libraries[1].downloads.artifact.path
+        );
+    }
+
+    #[test]
+    fn test_load_cyclic_inherits_from() {
+        let versions_dir = &std::env::temp_dir()
+            .join("mcl-parse-test-cyclic")
+            .join("versions");
+        let _ = std::fs::remove_dir_all(versions_dir);
+
+        for (id, parent) in [("a", "b"), ("b", "a")] {
+            std::fs::create_dir_all(versions_dir.join(id)).unwrap();
+            std::fs::write(
+                versions_dir.join(id).join(format!("{}.json", id)),
+                format!(r#"{{"id": "{}", "inheritsFrom": "{}"}}"#, id, parent),
+            )
+            .unwrap();
+        }
+
+        let err = load(versions_dir, "a").err().unwrap();
+        assert_eq!("Cyclic inheritsFrom: a -> b -> a", err.to_string());
+    }
 }
@@ -0,0 +1,437 @@
+use std::fs::{self, File};
+use std::io::{self, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+// 游戏运行时持有的锁文件，备份与导出时跳过
+const SESSION_LOCK: &str = "session.lock";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorldBackup {
+    pub path: String,
+    pub file_name: String,
+    // 文件大小，单位字节
+    pub size: u64,
+    // 备份时间，unix 时间戳（秒）
+    pub created_at: i64,
+}
+
+// 存档的备份目录，位于存档所在游戏目录下的 backups/<存档目录名>
+pub fn backup_directory(world: &Path) -> Option<PathBuf> {
+    let game_directory = world.parent()?.parent()?;
+    Some(game_directory.join("backups").join(world.file_name()?))
+}
+
+fn check_world(world: &Path) -> Result<(), &'static str> {
+    if world.join("level.dat").is_file() {
+        Ok(())
+    } else {
+        Err("未找到存档")
+    }
+}
+
+fn millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default()
+}
+
+// 把目录下的文件写入压缩包的 prefix 目录下，保留空目录
+fn zip_directory<W: Write + Seek>(
+    writer: &mut ZipWriter<W>,
+    directory: &Path,
+    prefix: &str,
+) -> zip::result::ZipResult<()> {
+    writer.add_directory(format!("{}/", prefix), SimpleFileOptions::default())?;
+    let mut entries: Vec<_> = fs::read_dir(directory)?
+        .filter_map(|entry| entry.ok())
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name == SESSION_LOCK {
+            continue;
+        }
+        let path = entry.path();
+        let name = format!("{}/{}", prefix, file_name);
+        if path.is_dir() {
+            zip_directory(writer, &path, &name)?;
+        } else {
+            writer.start_file(name, SimpleFileOptions::default())?;
+            io::copy(&mut File::open(&path)?, writer)?;
+        }
+    }
+    Ok(())
+}
+
+// 压缩存档，压缩包内为 <存档目录名>/...，先写入临时文件，完成后再重命名
+fn zip_world(world: &Path, output: &Path) -> Result<(), &'static str> {
+    check_world(world)?;
+    let folder_name = world
+        .file_name()
+        .ok_or("无效的存档路径")?
+        .to_string_lossy()
+        .to_string();
+    if let Some(parent) = output.parent() {
+        fs::create_dir_all(parent).map_err(|_| "创建目录失败")?;
+    }
+
+    let temp = output.with_extension("zip.tmp");
+    let result = File::create(&temp)
+        .map_err(|_| "创建压缩包失败")
+        .and_then(|file| {
+            let mut writer = ZipWriter::new(file);
+            zip_directory(&mut writer, world, &folder_name)
+                .and_then(|_| writer.finish().map(|_| ()))
+                .map_err(|_| "压缩存档失败")
+        })
+        .and_then(|_| fs::rename(&temp, output).map_err(|_| "写入压缩包失败"));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+// 压缩包中存档的根目录：包含 level.dat 且层级最浅的目录，
+// 压缩包中有绝对路径或 .. 时拒绝导入
+fn find_world_prefix(archive: &mut ZipArchive<File>) -> Result<PathBuf, &'static str> {
+    let mut prefix: Option<PathBuf> = None;
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).map_err(|_| "读取压缩包失败")?;
+        let name = entry.enclosed_name().ok_or("压缩包中包含不安全的路径")?;
+        if entry.is_file() && name.file_name().is_some_and(|n| n == "level.dat") {
+            let parent = name.parent().map(Path::to_path_buf).unwrap_or_default();
+            if prefix.as_ref().is_none_or(|p| {
+                parent.components().count() < p.components().count()
+            }) {
+                prefix = Some(parent);
+            }
+        }
+    }
+    prefix.ok_or("压缩包中没有存档")
+}
+
+// 解压压缩包中的存档到 target，target 必须不存在，返回压缩包中存档根目录的名称
+fn extract_world(archive_path: &Path, target: &Path) -> Result<Option<String>, &'static str> {
+    let mut archive = File::open(archive_path)
+        .ok()
+        .and_then(|file| ZipArchive::new(file).ok())
+        .ok_or("读取压缩包失败")?;
+    let prefix = find_world_prefix(&mut archive)?;
+
+    fs::create_dir_all(target).map_err(|_| "创建存档目录失败")?;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(|_| "读取压缩包失败")?;
+        let Some(name) = entry.enclosed_name() else {
+            return Err("压缩包中包含不安全的路径");
+        };
+        let Ok(relative) = name.strip_prefix(&prefix) else {
+            continue;
+        };
+        if relative.as_os_str().is_empty()
+            || relative.file_name().is_some_and(|n| n == SESSION_LOCK)
+        {
+            continue;
+        }
+        let path = target.join(relative);
+        if entry.is_dir() {
+            fs::create_dir_all(&path).map_err(|_| "创建存档目录失败")?;
+        } else {
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(|_| "创建存档目录失败")?;
+            }
+            let mut file = File::create(&path).map_err(|_| "写入存档文件失败")?;
+            io::copy(&mut entry, &mut file).map_err(|_| "解压存档失败")?;
+        }
+    }
+    Ok(prefix
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string()))
+}
+
+// 备份存档到备份目录下以时间命名的压缩包，max_backups 大于 0 时删除超出数量的旧备份
+pub fn backup_world(world: &Path, max_backups: usize) -> Result<WorldBackup, &'static str> {
+    check_world(world)?;
+    let directory = backup_directory(world).ok_or("无效的存档路径")?;
+    let timestamp = Local::now().format("%Y-%m-%d_%H-%M-%S").to_string();
+    let mut target = directory.join(format!("{}.zip", timestamp));
+    let mut index = 1;
+    while target.exists() {
+        target = directory.join(format!("{}_{}.zip", timestamp, index));
+        index += 1;
+    }
+
+    zip_world(world, &target)?;
+    if max_backups > 0 {
+        prune_backups(world, max_backups)?;
+    }
+    read_backup(&target).ok_or("读取备份失败")
+}
+
+fn read_backup(path: &Path) -> Option<WorldBackup> {
+    let metadata = fs::metadata(path).ok()?;
+    Some(WorldBackup {
+        path: path.to_string_lossy().to_string(),
+        file_name: path.file_name()?.to_string_lossy().to_string(),
+        size: metadata.len(),
+        created_at: metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default(),
+    })
+}
+
+// 列出存档的备份，从新到旧排序
+pub fn list_backups(world: &Path) -> Vec<WorldBackup> {
+    let Some(directory) = backup_directory(world) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<WorldBackup> = fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "zip"))
+                .filter_map(|path| read_backup(&path))
+                .collect()
+        })
+        .unwrap_or_default();
+    // 文件名以时间开头，修改时间相同时按文件名排序
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+    backups
+}
+
+// 只保留最新的 keep 个备份，返回被删除的备份
+pub fn prune_backups(world: &Path, keep: usize) -> Result<Vec<PathBuf>, &'static str> {
+    let mut removed = Vec::new();
+    for backup in list_backups(world).into_iter().skip(keep) {
+        let path = PathBuf::from(backup.path);
+        fs::remove_file(&path).map_err(|_| "删除备份失败")?;
+        removed.push(path);
+    }
+    Ok(removed)
+}
+
+// 备份必须位于存档的备份目录中
+fn check_backup(world: &Path, backup: &Path) -> Result<(), &'static str> {
+    let directory = backup_directory(world).ok_or("无效的存档路径")?;
+    if backup.parent() != Some(directory.as_path()) || !backup.is_file() {
+        return Err("未找到指定备份");
+    }
+    Ok(())
+}
+
+pub fn delete_backup(world: &Path, backup: &Path) -> Result<(), &'static str> {
+    check_backup(world, backup)?;
+    fs::remove_file(backup).map_err(|_| "删除备份失败")
+}
+
+// 用备份替换存档，先解压到临时目录，成功后再替换，替换失败时恢复原存档
+// 被替换的目录必须是存档（包含 level.dat），避免误删其他目录
+pub fn restore_backup(world: &Path, backup: &Path) -> Result<(), &'static str> {
+    check_world(world)?;
+    check_backup(world, backup)?;
+    let saves = world.parent().ok_or("无效的存档路径")?;
+    let folder_name = world
+        .file_name()
+        .ok_or("无效的存档路径")?
+        .to_string_lossy()
+        .to_string();
+    let temp = saves.join(format!(".{}-{}.restoring", folder_name, millis()));
+    if let Err(err) = extract_world(backup, &temp) {
+        let _ = fs::remove_dir_all(&temp);
+        return Err(err);
+    }
+
+    let old = saves.join(format!(".{}-{}.old", folder_name, millis()));
+    if world.exists() {
+        fs::rename(world, &old).map_err(|_| {
+            let _ = fs::remove_dir_all(&temp);
+            "移动原存档失败，游戏可能正在运行"
+        })?;
+    }
+    if fs::rename(&temp, world).is_err() {
+        let _ = fs::rename(&old, world);
+        let _ = fs::remove_dir_all(&temp);
+        return Err("恢复存档失败");
+    }
+    let _ = fs::remove_dir_all(&old);
+    Ok(())
+}
+
+// 导出存档为压缩包用于分享，压缩包内为 <存档目录名>/...
+pub fn export_world(world: &Path, output: &Path) -> Result<(), &'static str> {
+    zip_world(world, output)
+}
+
+// 导入存档压缩包到 saves 目录，存档可以位于压缩包的子目录中，
+// 目录名默认为压缩包中存档目录的名称，已存在时在后面加序号，返回导入后的存档路径
+pub fn import_world(
+    saves: &Path,
+    archive: &Path,
+    name: Option<&str>,
+) -> Result<PathBuf, &'static str> {
+    fs::create_dir_all(saves).map_err(|_| "创建 saves 目录失败")?;
+    let temp = saves.join(format!(".import-{}", millis()));
+    let folder_name = match extract_world(archive, &temp) {
+        Ok(folder_name) => folder_name,
+        Err(err) => {
+            let _ = fs::remove_dir_all(&temp);
+            return Err(err);
+        }
+    };
+
+    let folder_name = name
+        .map(crate::instance::instance_id)
+        .or(folder_name)
+        .or_else(|| {
+            archive
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string())
+        })
+        .unwrap_or("world".to_string());
+    let mut target = saves.join(&folder_name);
+    let mut index = 1;
+    while target.exists() {
+        target = saves.join(format!("{} ({})", folder_name, index));
+        index += 1;
+    }
+    fs::rename(&temp, &target).map_err(|_| {
+        let _ = fs::remove_dir_all(&temp);
+        "导入存档失败"
+    })?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8])]) {
+        let mut writer = ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_backup_and_restore() {
+        let root = std::env::temp_dir().join("mcl-world-backup");
+        let _ = fs::remove_dir_all(&root);
+        let world = root.join("saves/World");
+        fs::create_dir_all(world.join("region")).unwrap();
+        fs::create_dir_all(world.join("data")).unwrap();
+        fs::write(world.join("level.dat"), b"level").unwrap();
+        fs::write(world.join("region/r.0.0.mca"), b"region").unwrap();
+        fs::write(world.join(SESSION_LOCK), b"lock").unwrap();
+
+        let first = backup_world(&world, 2).unwrap();
+        assert!(first
+            .path
+            .starts_with(&*root.join("backups/World").to_string_lossy()));
+        backup_world(&world, 2).unwrap();
+        backup_world(&world, 2).unwrap();
+        let backups = list_backups(&world);
+        assert_eq!(2, backups.len());
+        assert!(!Path::new(&first.path).exists());
+
+        // 恢复后内容与备份时相同，不包含 session.lock，空目录也被保留
+        fs::write(world.join("level.dat"), b"changed").unwrap();
+        fs::write(world.join("new.dat"), b"new").unwrap();
+        restore_backup(&world, Path::new(&backups[0].path)).unwrap();
+        assert_eq!(
+            b"level".to_vec(),
+            fs::read(world.join("level.dat")).unwrap()
+        );
+        assert_eq!(
+            b"region".to_vec(),
+            fs::read(world.join("region/r.0.0.mca")).unwrap()
+        );
+        assert!(world.join("data").is_dir());
+        assert!(!world.join("new.dat").exists());
+        assert!(!world.join(SESSION_LOCK).exists());
+        assert_eq!(1, fs::read_dir(root.join("saves")).unwrap().count());
+
+        let outside = root.join("outside.zip");
+        fs::copy(&backups[0].path, &outside).unwrap();
+        assert!(restore_backup(&world, &outside).is_err());
+        // 不是存档的目录不会被备份覆盖
+        let other = world.with_file_name("other");
+        fs::create_dir_all(&other).unwrap();
+        fs::write(other.join("notes.txt"), b"notes").unwrap();
+        fs::create_dir_all(backup_directory(&other).unwrap()).unwrap();
+        let other_backup = backup_directory(&other).unwrap().join("other.zip");
+        fs::copy(&backups[0].path, &other_backup).unwrap();
+        assert!(restore_backup(&other, &other_backup).is_err());
+        assert!(other.join("notes.txt").is_file());
+        assert!(delete_backup(&world, &outside).is_err());
+        delete_backup(&world, Path::new(&backups[1].path)).unwrap();
+        assert_eq!(1, list_backups(&world).len());
+    }
+
+    #[test]
+    fn test_export_and_import() {
+        let root = std::env::temp_dir().join("mcl-world-import");
+        let _ = fs::remove_dir_all(&root);
+        let world = root.join("saves/World");
+        fs::create_dir_all(&world).unwrap();
+        fs::write(world.join("level.dat"), b"level").unwrap();
+
+        let exported = root.join("World.zip");
+        export_world(&world, &exported).unwrap();
+        let imported = import_world(&root.join("saves"), &exported, None).unwrap();
+        assert_eq!(root.join("saves/World (1)"), imported);
+        assert_eq!(
+            b"level".to_vec(),
+            fs::read(imported.join("level.dat")).unwrap()
+        );
+
+        // 存档位于压缩包的多层子目录中
+        let nested = root.join("nested.zip");
+        write_zip(
+            &nested,
+            &[
+                ("readme.txt", b"readme"),
+                ("download/Skyblock/level.dat", b"skyblock"),
+                ("download/Skyblock/region/r.0.0.mca", b"region"),
+                ("download/Skyblock/datapacks/pack/level.dat", b"nested"),
+            ],
+        );
+        let imported = import_world(&root.join("saves"), &nested, None).unwrap();
+        assert_eq!(root.join("saves/Skyblock"), imported);
+        assert!(imported.join("region/r.0.0.mca").is_file());
+        assert!(!imported.join("readme.txt").exists());
+        let renamed = import_world(&root.join("saves"), &nested, Some("My: World")).unwrap();
+        assert_eq!(root.join("saves/My_ World"), renamed);
+
+        let unsafe_zip = root.join("unsafe.zip");
+        write_zip(
+            &unsafe_zip,
+            &[("level.dat", b"level"), ("../evil.txt", b"evil")],
+        );
+        assert!(import_world(&root.join("saves"), &unsafe_zip, None).is_err());
+        assert!(!root.join("evil.txt").exists());
+
+        let empty = root.join("empty.zip");
+        write_zip(&empty, &[("readme.txt", b"readme")]);
+        assert!(import_world(&root.join("saves"), &empty, None).is_err());
+        // 失败时不留下临时目录
+        assert_eq!(4, fs::read_dir(root.join("saves")).unwrap().count());
+    }
+}
@@ -0,0 +1,329 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+// 实例配置文件，位于 versions/<id> 下
+pub const INSTANCE_FILE: &str = "instance.json";
+
+// 实例：versions/<id> 下继承基础版本的版本配置，目录本身作为游戏目录，
+// 存放 saves、config、mods、resourcepacks 等，libraries 与 assets 与根目录共享
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Instance {
+    // 版本目录名，即启动时的版本 id
+    pub id: String,
+    pub name: String,
+    // 实例目录下的图标文件名
+    pub icon: Option<String>,
+    // 继承的基础版本 id
+    pub version: String,
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+    // 以下为启动设置，为空时使用默认值
+    #[serde(default)]
+    pub java: Option<String>,
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    // 内存，单位 MB
+    #[serde(default)]
+    pub min_memory: Option<u32>,
+    #[serde(default)]
+    pub max_memory: Option<u32>,
+}
+
+// 把名称转换为可用作目录名的版本 id，去掉文件名中不允许的字符
+pub fn instance_id(name: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    let id = id.trim().trim_matches('.').to_string();
+    if id.is_empty() {
+        "instance".to_string()
+    } else {
+        id
+    }
+}
+
+pub fn instance_dir(root: &Path, id: &str) -> PathBuf {
+    root.join("versions").join(id)
+}
+
+impl Instance {
+    pub fn new(id: &str, name: &str, version: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            icon: None,
+            version: version.to_string(),
+            loader: None,
+            loader_version: None,
+            java: None,
+            jvm_args: Vec::new(),
+            min_memory: None,
+            max_memory: None,
+        }
+    }
+
+    // 读取 versions/<id>/instance.json，id 以目录名为准
+    pub fn load(root: &Path, id: &str) -> Result<Self, &'static str> {
+        let path = instance_dir(root, id).join(INSTANCE_FILE);
+        if !path.is_file() {
+            return Err("未找到指定实例");
+        }
+        let text = fs::read_to_string(&path).map_err(|_| "读取实例配置失败")?;
+        let mut instance: Instance = serde_json::from_str(&text).map_err(|_| "解析实例配置失败")?;
+        instance.id = id.to_string();
+        Ok(instance)
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), &'static str> {
+        let dir = instance_dir(root, &self.id);
+        fs::create_dir_all(&dir).map_err(|_| "创建实例目录失败")?;
+        let text = serde_json::to_string_pretty(self).map_err(|_| "序列化实例配置失败")?;
+        fs::write(dir.join(INSTANCE_FILE), text).map_err(|_| "写入实例配置失败")
+    }
+
+    // 实例的游戏目录
+    pub fn game_dir(&self, root: &Path) -> PathBuf {
+        instance_dir(root, &self.id)
+    }
+
+    pub fn icon_path(&self, root: &Path) -> Option<PathBuf> {
+        self.icon
+            .as_ref()
+            .map(|icon| self.game_dir(root).join(icon))
+    }
+}
+
+// 列出根目录下的所有实例，按名称排序
+pub fn list_instances(root: &Path) -> Vec<Instance> {
+    let Ok(entries) = fs::read_dir(root.join("versions")) else {
+        return Vec::new();
+    };
+    let mut instances: Vec<Instance> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_string_lossy().to_string();
+            Instance::load(root, &id).ok()
+        })
+        .collect();
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+    instances
+}
+
+// 写入继承基础版本的版本配置
+fn write_version_profile(root: &Path, id: &str, version: &str) -> Result<(), &'static str> {
+    let profile = serde_json::json!({
+        "id": id,
+        "inheritsFrom": version,
+        "type": "release",
+    });
+    let text = serde_json::to_string_pretty(&profile).map_err(|_| "序列化版本配置失败")?;
+    fs::write(instance_dir(root, id).join(format!("{}.json", id)), text)
+        .map_err(|_| "写入版本配置失败")
+}
+
+// 把 dir 下 <old_id>.json 改名为新实例的版本配置并更新其中的 id，保留其余内容
+fn move_version_profile(
+    root: &Path,
+    old_id: &str,
+    instance: &Instance,
+) -> Result<(), &'static str> {
+    let dir = instance_dir(root, &instance.id);
+    let old = dir.join(format!("{}.json", old_id));
+    let Ok(text) = fs::read_to_string(&old) else {
+        return write_version_profile(root, &instance.id, &instance.version);
+    };
+    let mut profile: serde_json::Value =
+        serde_json::from_str(&text).map_err(|_| "解析版本配置失败")?;
+    profile["id"] = serde_json::Value::String(instance.id.clone());
+    let text = serde_json::to_string_pretty(&profile).map_err(|_| "序列化版本配置失败")?;
+    fs::write(dir.join(format!("{}.json", instance.id)), text).map_err(|_| "写入版本配置失败")?;
+    if old_id != instance.id {
+        let _ = fs::remove_file(old);
+    }
+    Ok(())
+}
+
+// 创建继承 version 的实例，版本 id 由名称生成
+pub fn create_instance(root: &Path, name: &str, version: &str) -> Result<Instance, &'static str> {
+    let id = instance_id(name);
+    let dir = instance_dir(root, &id);
+    if dir.exists() {
+        return Err("实例已存在");
+    }
+    fs::create_dir_all(&dir).map_err(|_| "创建实例目录失败")?;
+
+    let instance = Instance::new(&id, name, version);
+    let result = write_version_profile(root, &id, version).and_then(|_| instance.save(root));
+    if let Err(err) = result {
+        let _ = fs::remove_dir_all(&dir);
+        return Err(err);
+    }
+    Ok(instance)
+}
+
+// 重命名实例，同时移动版本目录并更新版本配置中的 id
+pub fn rename_instance(root: &Path, id: &str, name: &str) -> Result<Instance, &'static str> {
+    let mut instance = Instance::load(root, id)?;
+    let new_id = instance_id(name);
+    instance.name = name.to_string();
+    if new_id == id {
+        instance.save(root)?;
+        return Ok(instance);
+    }
+
+    let target = instance_dir(root, &new_id);
+    if target.exists() {
+        return Err("实例已存在");
+    }
+    let source = instance_dir(root, id);
+    fs::rename(&source, &target).map_err(|_| "重命名实例目录失败")?;
+    // natives 目录会在启动时重新解压
+    let _ = fs::remove_dir_all(target.join("natives"));
+
+    let old_profile = target.join(format!("{}.json", id));
+    let profile = fs::read(&old_profile).ok();
+    instance.id = new_id;
+    let result = move_version_profile(root, id, &instance).and_then(|_| instance.save(root));
+    if let Err(err) = result {
+        // 恢复原来的版本配置与目录名
+        let _ = fs::remove_file(target.join(format!("{}.json", instance.id)));
+        if let Some(profile) = profile {
+            let _ = fs::write(&old_profile, profile);
+        }
+        let _ = fs::rename(&target, &source);
+        return Err(err);
+    }
+    Ok(instance)
+}
+
+// 复制实例的全部文件到新的实例
+pub fn clone_instance(root: &Path, id: &str, name: &str) -> Result<Instance, &'static str> {
+    let mut instance = Instance::load(root, id)?;
+    let new_id = instance_id(name);
+    let target = instance_dir(root, &new_id);
+    if target.exists() {
+        return Err("实例已存在");
+    }
+
+    let source = instance_dir(root, id);
+    let result = copy_dir(&source, &target, &["natives"])
+        .map_err(|_| "复制实例文件失败")
+        .and_then(|_| {
+            instance.id = new_id;
+            instance.name = name.to_string();
+            move_version_profile(root, id, &instance)?;
+            instance.save(root)
+        });
+    if let Err(err) = result {
+        let _ = fs::remove_dir_all(&target);
+        return Err(err);
+    }
+    Ok(instance)
+}
+
+// 递归复制目录，跳过顶层中名称在 skip 中的条目
+pub fn copy_dir(source: &Path, target: &Path, skip: &[&str]) -> std::io::Result<()> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if skip.contains(&entry.file_name().to_string_lossy().as_ref()) {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            copy_dir(&path, &target.join(entry.file_name()), &[])?;
+        } else {
+            fs::copy(&path, target.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
+// 删除实例，移动到根目录下的 .trash 目录而不是直接删除，返回回收站中的路径
+pub fn delete_instance(root: &Path, id: &str) -> Result<PathBuf, &'static str> {
+    Instance::load(root, id)?;
+
+    let trash = root.join(".trash").join("instances");
+    fs::create_dir_all(&trash).map_err(|_| "创建回收站目录失败")?;
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or_default();
+    let target = trash.join(format!("{}-{}", timestamp, id));
+    fs::rename(instance_dir(root, id), &target).map_err(|_| "移动实例到回收站失败")?;
+    Ok(target)
+}
+
+// 复制图标到实例目录下的 icon.png
+pub fn set_instance_icon(root: &Path, id: &str, source: &Path) -> Result<Instance, &'static str> {
+    let mut instance = Instance::load(root, id)?;
+    if !source.is_file() {
+        return Err("图标文件不存在");
+    }
+    fs::copy(source, instance.game_dir(root).join("icon.png")).map_err(|_| "复制图标失败")?;
+    instance.icon = Some("icon.png".to_string());
+    instance.save(root)?;
+    Ok(instance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manage_instances() {
+        let root = std::env::temp_dir().join("mcl-manage-instances");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("versions/1.21")).unwrap();
+
+        let instance = create_instance(&root, "Survival: 1.21", "1.21").unwrap();
+        assert_eq!("Survival_ 1.21", instance.id);
+        assert!(create_instance(&root, "Survival: 1.21", "1.21").is_err());
+        let profile =
+            fs::read_to_string(root.join("versions/Survival_ 1.21/Survival_ 1.21.json")).unwrap();
+        assert!(profile.contains(r#""inheritsFrom": "1.21""#));
+
+        let dir = instance.game_dir(&root);
+        fs::create_dir_all(dir.join("saves/world")).unwrap();
+        fs::write(dir.join("saves/world/level.dat"), b"level").unwrap();
+
+        // 版本配置无法解析时恢复原来的目录
+        let profile_path = dir.join("Survival_ 1.21.json");
+        fs::write(&profile_path, b"broken").unwrap();
+        assert!(rename_instance(&root, &instance.id, "Survival").is_err());
+        assert!(dir.join(INSTANCE_FILE).is_file());
+        assert!(!root.join("versions/Survival").exists());
+        fs::write(&profile_path, profile).unwrap();
+
+        let renamed = rename_instance(&root, &instance.id, "Survival").unwrap();
+        assert_eq!("Survival", renamed.id);
+        assert!(!dir.exists());
+        assert!(root.join("versions/Survival/Survival.json").is_file());
+        assert!(!root.join("versions/Survival/Survival_ 1.21.json").exists());
+
+        let cloned = clone_instance(&root, "Survival", "Creative").unwrap();
+        assert_eq!("Creative", cloned.name);
+        assert_eq!(
+            b"level".to_vec(),
+            fs::read(root.join("versions/Creative/saves/world/level.dat")).unwrap()
+        );
+        assert!(!root.join("versions/Creative/Survival.json").exists());
+
+        // 普通版本不是实例
+        let names: Vec<String> = list_instances(&root).into_iter().map(|i| i.name).collect();
+        assert_eq!(vec!["Creative", "Survival"], names);
+
+        let trash = delete_instance(&root, "Creative").unwrap();
+        assert!(trash.join("saves/world/level.dat").is_file());
+        assert!(Instance::load(&root, "Creative").is_err());
+        assert!(delete_instance(&root, "1.21").is_err());
+    }
+}
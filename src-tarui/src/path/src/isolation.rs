@@ -0,0 +1,180 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::instance::INSTANCE_FILE;
+
+// 版本隔离设置，位于根目录下
+pub const ISOLATION_FILE: &str = "rmcl-isolation.json";
+
+// 加载器库的 group，用于判断版本是否安装了加载器
+const LOADER_GROUPS: [&str; 6] = [
+    "net.fabricmc:",
+    "org.quiltmc:",
+    "net.minecraftforge:",
+    "net.neoforged",
+    "optifine:",
+    "com.mumfrey:liteloader",
+];
+
+// 未单独设置的版本的默认隔离方式
+#[derive(Clone, Copy, Default, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum IsolationMode {
+    #[default]
+    Off,
+    // 只隔离安装了加载器的版本
+    Modded,
+    All,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+pub struct IsolationSettings {
+    #[serde(default)]
+    pub mode: IsolationMode,
+    // 单独设置过的版本，覆盖 mode
+    #[serde(default)]
+    pub versions: BTreeMap<String, bool>,
+}
+
+impl IsolationSettings {
+    // 读取根目录下的隔离设置，不存在或无法解析时使用默认值
+    pub fn load(root: &Path) -> Self {
+        fs::read_to_string(root.join(ISOLATION_FILE))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), &'static str> {
+        let text = serde_json::to_string_pretty(self).map_err(|_| "序列化隔离设置失败")?;
+        fs::write(root.join(ISOLATION_FILE), text).map_err(|_| "写入隔离设置失败")
+    }
+
+    // 实例总是隔离的，其余版本先看单独设置，再看默认方式
+    pub fn is_isolated(&self, root: &Path, id: &str) -> bool {
+        if root.join("versions").join(id).join(INSTANCE_FILE).is_file() {
+            return true;
+        }
+        if let Some(isolated) = self.versions.get(id) {
+            return *isolated;
+        }
+        match self.mode {
+            IsolationMode::Off => false,
+            IsolationMode::Modded => is_modded(root, id),
+            IsolationMode::All => true,
+        }
+    }
+}
+
+// 版本配置继承其他版本或包含加载器的库时视为 mod 版本
+pub fn is_modded(root: &Path, id: &str) -> bool {
+    let path = root.join("versions").join(id).join(format!("{}.json", id));
+    let Some(profile) = fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str::<serde_json::Value>(&text).ok())
+    else {
+        return false;
+    };
+    if profile.get("inheritsFrom").is_some() {
+        return true;
+    }
+    profile["libraries"].as_array().is_some_and(|libraries| {
+        libraries.iter().any(|library| {
+            library["name"]
+                .as_str()
+                .is_some_and(|name| LOADER_GROUPS.iter().any(|group| name.starts_with(group)))
+        })
+    })
+}
+
+// 启动时的游戏目录，隔离时为 versions/<id>，否则为根目录
+pub fn game_directory(root: &Path, id: &str) -> PathBuf {
+    if IsolationSettings::load(root).is_isolated(root, id) {
+        root.join("versions").join(id)
+    } else {
+        root.to_path_buf()
+    }
+}
+
+// 版本的游戏目录，global 或未隔离的版本为根目录
+pub fn version_game_directory(root: &Path, version_name: &str) -> PathBuf {
+    if version_name == "global" {
+        root.to_path_buf()
+    } else {
+        game_directory(root, version_name)
+    }
+}
+
+// 根目录与 versions 下各版本的目录，版本名为空表示根目录，
+// 版本目录在隔离或作为实例时是该版本的游戏目录
+pub fn game_directories(root: &Path) -> Vec<(Option<String>, PathBuf)> {
+    let mut directories = vec![(None, root.to_path_buf())];
+    if let Ok(entries) = fs::read_dir(root.join("versions")) {
+        let mut versions: Vec<(Option<String>, PathBuf)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .map(|entry| {
+                (
+                    Some(entry.file_name().to_string_lossy().to_string()),
+                    entry.path(),
+                )
+            })
+            .collect();
+        versions.sort();
+        directories.extend(versions);
+    }
+    directories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isolation() {
+        let root = std::env::temp_dir().join("mcl-isolation");
+        let _ = fs::remove_dir_all(&root);
+        for (id, profile) in [
+            (
+                "1.21",
+                r#"{"id": "1.21", "libraries": [{"name": "org.lwjgl:lwjgl:3.3.3"}]}"#,
+            ),
+            ("fabric", r#"{"id": "fabric", "inheritsFrom": "1.21"}"#),
+            (
+                "forge",
+                r#"{"id": "forge", "libraries": [{"name": "net.minecraftforge:forge:1.7.10"}]}"#,
+            ),
+        ] {
+            fs::create_dir_all(root.join("versions").join(id)).unwrap();
+            fs::write(
+                root.join("versions").join(id).join(format!("{}.json", id)),
+                profile,
+            )
+            .unwrap();
+        }
+
+        let mut settings = IsolationSettings::load(&root);
+        assert_eq!(IsolationMode::Off, settings.mode);
+        assert!(!settings.is_isolated(&root, "fabric"));
+
+        settings.mode = IsolationMode::Modded;
+        assert!(!settings.is_isolated(&root, "1.21"));
+        assert!(settings.is_isolated(&root, "fabric"));
+        assert!(settings.is_isolated(&root, "forge"));
+
+        settings.versions.insert("forge".to_string(), false);
+        settings.save(&root).unwrap();
+        assert_eq!(root, game_directory(&root, "forge"));
+        assert_eq!(
+            root.join("versions/fabric"),
+            game_directory(&root, "fabric")
+        );
+
+        // 实例总是隔离的
+        fs::write(root.join("versions/1.21").join(INSTANCE_FILE), "{}").unwrap();
+        assert_eq!(root.join("versions/1.21"), game_directory(&root, "1.21"));
+    }
+}
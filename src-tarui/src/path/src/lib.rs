@@ -1,4 +1,24 @@
+mod backup;
+mod instance;
+mod isolation;
 mod minecraft_path;
+mod mod_check;
+mod mod_metadata;
+mod resource_pack;
+mod saves;
+mod screenshot;
+mod servers;
+mod watcher;
 
 // Re-export all public items from minecraft_path.rs
+pub use backup::*;
+pub use instance::*;
+pub use isolation::*;
 pub use minecraft_path::*;
+pub use mod_check::*;
+pub use mod_metadata::*;
+pub use resource_pack::*;
+pub use saves::*;
+pub use screenshot::*;
+pub use servers::*;
+pub use watcher::*;
@@ -1,7 +1,29 @@
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Serialize, Deserialize};
+use crate::isolation::IsolationSettings;
+use crate::mod_metadata::{read_mod_metadata, ModMetadata};
+use crate::watcher::RootChange;
+
+// 被禁用的 mod 的后缀
+const DISABLED_SUFFIX: &str = ".jar.disabled";
+
+// 截图文件的扩展名
+const SCREENSHOT_EXTENSIONS: [&str; 5] = ["png", "jpg", "jpeg", "gif", "bmp"];
+
+pub(crate) fn is_screenshot(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        SCREENSHOT_EXTENSIONS.contains(&ext.to_string_lossy().to_lowercase().as_str())
+    })
+}
+
+// 文件名是否为 mod 文件，包括被禁用的
+pub(crate) fn is_mod_file(file_name: &str) -> bool {
+    let lower = file_name.to_lowercase();
+    lower.ends_with(".jar") || lower.ends_with(DISABLED_SUFFIX)
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MinecraftPath {
@@ -22,13 +44,20 @@ pub struct VersionPath {
     pub root: PathBuf,
     pub name: String,
     pub mods: Vec<ModPath>,
+    // 开启版本隔离时 versions/<name> 为游戏目录
+    pub isolated: bool,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ModPath {
     pub path: String,
+    // 有元数据时为 mod 的显示名称，否则为文件名
     pub name: String,
     pub location: String, 
+    // 文件名以 .jar.disabled 结尾时为 false
+    pub enabled: bool,
+    // jar 内的 mod 元数据，缺失或无法解析时为 None
+    pub metadata: Option<ModMetadata>,
 }
 
 impl MinecraftPath {
@@ -47,11 +76,7 @@ impl MinecraftPath {
                 .to_string(),
         };
 
-        if let Some(data_path) = &mut minecraft_path.data_path {
-            data_path.screenshots = Self::init_screenshots(&path);
-            data_path.mods = Self::init_root_mods(&path);
-            data_path.version = Self::init_versions(&path);
-        }
+        minecraft_path.refresh();
 
         minecraft_path
     }
@@ -69,11 +94,7 @@ impl MinecraftPath {
             display_name: name.to_string(),
         };
 
-        if let Some(data_path) = &mut minecraft_path.data_path {
-            data_path.screenshots = Self::init_screenshots(&path);
-            data_path.mods = Self::init_root_mods(&path);
-            data_path.version = Self::init_versions(&path);
-        }
+        minecraft_path.refresh();
 
         minecraft_path
     }
@@ -88,7 +109,17 @@ impl MinecraftPath {
         self.display_name = name;
     }
 
-    // 初始化截图
+    // 重新扫描目录，刷新缓存的 DataPath
+    pub fn refresh(&mut self) {
+        let path = self.root.clone();
+        if let Some(data_path) = &mut self.data_path {
+            data_path.screenshots = Self::init_screenshots(&path);
+            data_path.mods = Self::init_root_mods(&path);
+            data_path.version = Self::init_versions(&path);
+        }
+    }
+
+    // 初始化截图，path 为游戏目录
     fn init_screenshots(path: &PathBuf) -> Vec<String> {
         let mut screen_shots_path = path.clone();
         screen_shots_path.push("screenshots");
@@ -102,13 +133,8 @@ impl MinecraftPath {
                 .filter_map(|entry| entry.ok())
                 .filter_map(|entry| {
                     let path = entry.path();
-                    if path.is_file() {
-                        if let Some(ext) = path.extension() {
-                            let ext_str = ext.to_string_lossy().to_lowercase();
-                            if ["png", "jpg", "jpeg", "gif", "bmp"].contains(&ext_str.as_str()) {
-                                return path.to_str().map(|s| s.to_string());
-                            }
-                        }
+                    if path.is_file() && is_screenshot(&path) {
+                        return path.to_str().map(|s| s.to_string());
                     }
                     None
                 })
@@ -131,27 +157,36 @@ impl MinecraftPath {
     }
 
     // 扫描指定目录下的mod文件，指定location
+    // 被禁用的 mod 以 .jar.disabled 结尾
     fn scan_mods_directory(mods_path: &PathBuf, location: String) -> Vec<ModPath> {
         if let Ok(entries) = fs::read_dir(mods_path) {
             entries
                 .filter_map(|entry| entry.ok())
                 .filter_map(|entry| {
                     let path = entry.path();
-                    if path.is_file() {
-                        if let Some(ext) = path.extension() {
-                            let ext_str = ext.to_string_lossy().to_lowercase();
-                            if ext_str == "jar" {
-                                if let Some(name) = path.file_stem() {
-                                    return Some(ModPath {
-                                        path: path.to_string_lossy().to_string(),
-                                        name: name.to_string_lossy().to_string(),
-                                        location: location.clone(),
-                                    });
-                                }
-                            }
-                        }
+                    if !path.is_file() {
+                        return None;
                     }
-                    None
+                    let file_name = path.file_name()?.to_string_lossy().to_string();
+                    let lower = file_name.to_lowercase();
+                    let (stem, enabled) = if lower.ends_with(".jar") {
+                        (&file_name[..file_name.len() - ".jar".len()], true)
+                    } else if lower.ends_with(DISABLED_SUFFIX) {
+                        (&file_name[..file_name.len() - DISABLED_SUFFIX.len()], false)
+                    } else {
+                        return None;
+                    };
+                    let metadata = read_mod_metadata(&path);
+                    Some(ModPath {
+                        path: path.to_string_lossy().to_string(),
+                        name: metadata
+                            .as_ref()
+                            .map(|m| m.name.clone())
+                            .unwrap_or_else(|| stem.to_string()),
+                        location: location.clone(),
+                        enabled,
+                        metadata,
+                    })
                 })
                 .collect()
         } else {
@@ -167,6 +202,7 @@ impl MinecraftPath {
             return Vec::new();
         }
 
+        let isolation = IsolationSettings::load(path);
         if let Ok(entries) = fs::read_dir(&versions_path) {
             entries
                 .filter_map(|entry| entry.ok())
@@ -187,6 +223,7 @@ impl MinecraftPath {
 
                             return Some(VersionPath {
                                 root: path.clone(),
+                                isolated: isolation.is_isolated(versions_path.parent()?, &version_name),
                                 name: version_name,
                                 mods: version_mods,
                             });
@@ -200,6 +237,74 @@ impl MinecraftPath {
         }
     }
 
+    // 根据文件监听到的变化更新缓存，只重新扫描变化的部分
+    pub fn apply_change(&mut self, change: &RootChange) {
+        let root = self.root.clone();
+        let Some(data_path) = &mut self.data_path else {
+            return;
+        };
+        if let RootChange::Screenshot { path, .. } = change {
+            if !path.is_file() {
+                crate::screenshot::forget_dimensions(path);
+            }
+        }
+        match change {
+            RootChange::Mods { version: None } => data_path.mods = Self::init_root_mods(&root),
+            RootChange::Mods { version: Some(name) } => {
+                match data_path.version.iter_mut().find(|v| &v.name == name) {
+                    Some(version) => {
+                        version.mods = Self::scan_mods_directory(&version.root.join("mods"), name.clone())
+                    }
+                    None => self.update_versions(),
+                }
+            }
+            RootChange::Versions => self.update_versions(),
+            RootChange::Screenshot { version: None, path } => {
+                let Some(file_name) = path.file_name() else {
+                    return;
+                };
+                // 与 init_screenshots 中的路径格式保持一致
+                let screenshot = root.join("screenshots").join(file_name).to_string_lossy().to_string();
+                data_path.screenshots.retain(|s| s != &screenshot);
+                if path.is_file() {
+                    data_path.screenshots.push(screenshot);
+                }
+            }
+            // 隔离版本的截图不缓存，由 get_version_screenshots 扫描
+            RootChange::Screenshot { version: Some(_), .. } => {}
+        }
+    }
+
+    // 重新读取版本列表与隔离设置，已有版本保留缓存的 mods，只扫描新增的版本
+    fn update_versions(&mut self) {
+        let root = self.root.clone();
+        let Some(data_path) = &mut self.data_path else {
+            return;
+        };
+        let mut previous = std::mem::take(&mut data_path.version);
+        let Ok(entries) = fs::read_dir(root.join("versions")) else {
+            return;
+        };
+        let isolation = IsolationSettings::load(&root);
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let mods = match previous.iter().position(|v| v.name == name) {
+                Some(index) => previous.swap_remove(index).mods,
+                None => Self::scan_mods_directory(&path.join("mods"), name.clone()),
+            };
+            data_path.version.push(VersionPath {
+                root: path,
+                isolated: isolation.is_isolated(&root, &name),
+                name,
+                mods,
+            });
+        }
+    }
+
     pub fn get_path(&self) -> &Path {
         self.root.as_path()
     }
@@ -229,21 +334,20 @@ impl MinecraftPath {
         }
     }
 
-    // 获取特定版本的所有可用mods（包括根目录和版本特定的）
+    // 获取特定版本的所有可用mods（包括根目录和版本特定的），隔离的版本不包括根目录的mods
     pub fn get_all_available_mods(&self, version_name: &str) -> Result<Vec<ModPath>, &'static str> {
         if let Some(data_path) = &self.data_path {
             let mut all_mods = Vec::new();
+            let version = data_path.version.iter().find(|v| v.name == version_name);
             
-            // Always add global mods
-            all_mods.extend(data_path.mods.clone());
+            if !version.is_some_and(|v| v.isolated) {
+                all_mods.extend(data_path.mods.clone());
+            }
             
             // For non-global versions, also add version-specific mods
             if version_name != "global" {
-                for version in &data_path.version {
-                    if version.name == version_name {
-                        all_mods.extend(version.mods.clone());
-                        break;
-                    }
+                if let Some(version) = version {
+                    all_mods.extend(version.mods.clone());
                 }
             }
             
@@ -281,4 +385,231 @@ impl MinecraftPath {
             Err("数据路径模块还没有被初始化")
         }
     }
+
+    // 获取版本的游戏目录，global 或未隔离的版本为根目录
+    pub fn get_game_directory(&self, version_name: &str) -> PathBuf {
+        crate::isolation::version_game_directory(&self.root, version_name)
+    }
+
+    // 根目录与 versions 下各版本的目录，版本名为空表示根目录
+    pub fn get_game_directories(&self) -> Vec<(Option<String>, PathBuf)> {
+        crate::isolation::game_directories(&self.root)
+    }
+
+    // 获取版本游戏目录下的截图
+    pub fn get_version_screenshots(&self, version_name: &str) -> (PathBuf, Vec<String>) {
+        let game_directory = self.get_game_directory(version_name);
+        let screenshots = Self::init_screenshots(&game_directory);
+        (game_directory.join("screenshots"), screenshots)
+    }
+
+    // 获取版本游戏目录下的存档，只包括含有 level.dat 的目录
+    pub fn get_version_saves(&self, version_name: &str) -> (PathBuf, Vec<String>) {
+        let saves_path = self.get_game_directory(version_name).join("saves");
+        let mut saves: Vec<String> = fs::read_dir(&saves_path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .filter(|entry| entry.path().join("level.dat").is_file())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        saves.sort();
+        (saves_path, saves)
+    }
+
+    // 获取 mods 目录，global 为根目录下的 mods
+    pub fn get_mods_directory(&self, version_name: &str) -> PathBuf {
+        if version_name == "global" {
+            self.root.join("mods")
+        } else {
+            self.root.join("versions").join(version_name).join("mods")
+        }
+    }
+
+    // 查找已扫描到的 mod，只允许操作属于该目录的 mod
+    fn find_mod(&self, mod_path: &str) -> Result<&ModPath, &'static str> {
+        let data_path = self.data_path.as_ref().ok_or("数据路径模块还没有被初始化")?;
+        data_path
+            .mods
+            .iter()
+            .chain(data_path.version.iter().flat_map(|v| v.mods.iter()))
+            .find(|m| m.path == mod_path)
+            .ok_or("未找到指定mod")
+    }
+
+    // 启用或禁用 mod，通过重命名为 .jar / .jar.disabled 实现，返回新的路径
+    pub fn set_mod_enabled(&mut self, mod_path: &str, enabled: bool) -> Result<PathBuf, &'static str> {
+        let current = self.find_mod(mod_path)?;
+        if current.enabled == enabled {
+            return Ok(PathBuf::from(mod_path));
+        }
+
+        let path = PathBuf::from(mod_path);
+        let file_name = path.file_name().ok_or("无效的mod路径")?.to_string_lossy().to_string();
+        let target_name = if enabled {
+            file_name[..file_name.len() - DISABLED_SUFFIX.len()].to_string() + ".jar"
+        } else {
+            file_name[..file_name.len() - ".jar".len()].to_string() + DISABLED_SUFFIX
+        };
+        let target = path.with_file_name(target_name);
+        if target.exists() {
+            return Err("目标文件已存在");
+        }
+
+        fs::rename(&path, &target).map_err(|_| "重命名mod文件失败")?;
+        self.refresh();
+        Ok(target)
+    }
+
+    // 删除 mod，移动到根目录下的 .trash 目录而不是直接删除，返回回收站中的路径
+    pub fn delete_mod(&mut self, mod_path: &str) -> Result<PathBuf, &'static str> {
+        self.find_mod(mod_path)?;
+
+        let path = PathBuf::from(mod_path);
+        let file_name = path.file_name().ok_or("无效的mod路径")?.to_string_lossy().to_string();
+        let trash = self.root.join(".trash").join("mods");
+        fs::create_dir_all(&trash).map_err(|_| "创建回收站目录失败")?;
+
+        // 加上时间戳避免同名文件互相覆盖
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let target = trash.join(format!("{}-{}", timestamp, file_name));
+
+        if fs::rename(&path, &target).is_err() {
+            // 跨设备时无法重命名，复制后再删除
+            fs::copy(&path, &target).map_err(|_| "移动mod到回收站失败")?;
+            fs::remove_file(&path).map_err(|_| "删除mod文件失败")?;
+        }
+        self.refresh();
+        Ok(target)
+    }
+
+    // 复制 mod 文件到全局或指定版本的 mods 目录，返回复制后的路径
+    pub fn add_mod(&mut self, source: &Path, version_name: &str) -> Result<PathBuf, &'static str> {
+        if !source.is_file() {
+            return Err("mod文件不存在");
+        }
+        let file_name = source.file_name().ok_or("无效的mod路径")?;
+        if !file_name.to_string_lossy().to_lowercase().ends_with(".jar") {
+            return Err("mod文件必须是jar");
+        }
+        if version_name != "global" && !self.root.join("versions").join(version_name).is_dir() {
+            return Err("未找到指定版本");
+        }
+
+        let mods_directory = self.get_mods_directory(version_name);
+        fs::create_dir_all(&mods_directory).map_err(|_| "创建mods目录失败")?;
+        let target = mods_directory.join(file_name);
+        if target.exists() {
+            return Err("目标文件已存在");
+        }
+
+        fs::copy(source, &target).map_err(|_| "复制mod文件失败")?;
+        self.refresh();
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manage_mods() {
+        let root = std::env::temp_dir().join("mcl-manage-mods");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("mods")).unwrap();
+        fs::create_dir_all(root.join("versions/1.21/mods")).unwrap();
+        fs::write(root.join("mods/a.jar"), b"").unwrap();
+        fs::write(root.join("versions/1.21/mods/b.jar.disabled"), b"").unwrap();
+        let source = std::env::temp_dir().join("mcl-manage-mods-c.jar");
+        fs::write(&source, b"").unwrap();
+
+        let mut minecraft_path = MinecraftPath::new(root.to_str().unwrap());
+        let mods = minecraft_path.get_all_available_mods("1.21").unwrap();
+        assert_eq!(mods.len(), 2);
+        let b = mods.iter().find(|m| m.name == "b").unwrap();
+        assert!(!b.enabled);
+
+        let enabled = minecraft_path.set_mod_enabled(&b.path, true).unwrap();
+        assert!(enabled.ends_with("b.jar"));
+        let a = root.join("mods/a.jar").to_string_lossy().to_string();
+        let disabled = minecraft_path.set_mod_enabled(&a, false).unwrap();
+        assert!(disabled.ends_with("a.jar.disabled"));
+        assert!(!minecraft_path.get_root_mods().unwrap()[0].enabled);
+
+        let added = minecraft_path.add_mod(&source, "1.21").unwrap();
+        assert_eq!(minecraft_path.get_version_mods("1.21").unwrap().len(), 2);
+        assert!(minecraft_path.add_mod(&source, "1.21").is_err());
+
+        let trashed = minecraft_path.delete_mod(&added.to_string_lossy()).unwrap();
+        assert!(trashed.starts_with(root.join(".trash/mods")));
+        assert_eq!(minecraft_path.get_version_mods("1.21").unwrap().len(), 1);
+        assert!(minecraft_path.delete_mod(&added.to_string_lossy()).is_err());
+    }
+
+    #[test]
+    fn test_version_isolation() {
+        let root = std::env::temp_dir().join("mcl-version-isolation");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("mods")).unwrap();
+        fs::create_dir_all(root.join("versions/1.21/mods")).unwrap();
+        fs::create_dir_all(root.join("versions/1.21/saves/world")).unwrap();
+        fs::create_dir_all(root.join("versions/1.21/screenshots")).unwrap();
+        fs::write(root.join("mods/a.jar"), b"").unwrap();
+        fs::write(root.join("versions/1.21/mods/b.jar"), b"").unwrap();
+        fs::write(root.join("versions/1.21/saves/world/level.dat"), b"").unwrap();
+        fs::write(root.join("versions/1.21/screenshots/a.png"), b"").unwrap();
+
+        let mut settings = IsolationSettings::default();
+        settings.versions.insert("1.21".to_string(), true);
+        settings.save(&root).unwrap();
+
+        let minecraft_path = MinecraftPath::new(root.to_str().unwrap());
+        assert!(minecraft_path.get_versions().unwrap()[0].isolated);
+        let mods = minecraft_path.get_all_available_mods("1.21").unwrap();
+        assert_eq!(vec!["b"], mods.iter().map(|m| m.name.as_str()).collect::<Vec<_>>());
+        assert_eq!(root.join("versions/1.21"), minecraft_path.get_game_directory("1.21"));
+        assert_eq!(vec!["world"], minecraft_path.get_version_saves("1.21").1);
+        assert_eq!(1, minecraft_path.get_version_screenshots("1.21").1.len());
+        assert!(minecraft_path.get_version_screenshots("global").1.is_empty());
+    }
+
+    #[test]
+    fn test_apply_change() {
+        let root = std::env::temp_dir().join("mcl-apply-change");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("mods")).unwrap();
+        fs::create_dir_all(root.join("screenshots")).unwrap();
+        fs::create_dir_all(root.join("versions/1.21/mods")).unwrap();
+
+        let mut minecraft_path = MinecraftPath::new(root.to_str().unwrap());
+        fs::write(root.join("mods/a.jar"), b"").unwrap();
+        minecraft_path.apply_change(&RootChange::Mods { version: None });
+        assert_eq!(1, minecraft_path.get_root_mods().unwrap().len());
+
+        fs::write(root.join("versions/1.21/mods/b.jar"), b"").unwrap();
+        minecraft_path.apply_change(&RootChange::Mods { version: Some("1.21".to_string()) });
+        assert_eq!(1, minecraft_path.get_version_mods("1.21").unwrap().len());
+
+        fs::create_dir_all(root.join("versions/fabric/mods")).unwrap();
+        fs::write(root.join("versions/fabric/mods/c.jar"), b"").unwrap();
+        minecraft_path.apply_change(&RootChange::Versions);
+        assert_eq!(2, minecraft_path.get_versions().unwrap().len());
+        assert_eq!(1, minecraft_path.get_version_mods("fabric").unwrap().len());
+
+        let screenshot = root.join("screenshots/a.png");
+        fs::write(&screenshot, b"").unwrap();
+        let change = RootChange::Screenshot { version: None, path: screenshot.clone() };
+        minecraft_path.apply_change(&change);
+        minecraft_path.apply_change(&change);
+        assert_eq!(1, minecraft_path.get_sceenshots().unwrap().1.len());
+        fs::remove_file(&screenshot).unwrap();
+        minecraft_path.apply_change(&change);
+        assert!(minecraft_path.get_sceenshots().unwrap().1.is_empty());
+    }
 }
@@ -0,0 +1,537 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::minecraft_path::ModPath;
+use crate::mod_metadata::{DependencyKind, FABRIC, FORGE, NEOFORGE, QUILT};
+
+// 检查 mod 时目标版本的运行环境
+pub struct ModEnvironment {
+    pub game_version: String,
+    // fabric / quilt / forge / neoforge，原版为 None
+    pub loader: Option<String>,
+    pub loader_version: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ModCheckReport {
+    pub issues: Vec<ModIssue>,
+}
+
+impl ModCheckReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ModIssue {
+    // 多个文件提供了同一个 mod id
+    DuplicateId {
+        id: String,
+        paths: Vec<String>,
+    },
+    // 缺少必需的前置
+    MissingDependency {
+        id: String,
+        path: String,
+        dependency: String,
+        versions: Vec<String>,
+    },
+    // 前置存在但版本不在要求的范围内
+    DependencyVersionMismatch {
+        id: String,
+        path: String,
+        dependency: String,
+        versions: Vec<String>,
+        found: String,
+    },
+    // 与已安装的 mod 冲突
+    Incompatible {
+        id: String,
+        path: String,
+        dependency: String,
+        found: String,
+    },
+    // mod 的加载器与版本的加载器不一致，例如 Fabric 版本中的 Forge mod
+    LoaderMismatch {
+        id: String,
+        path: String,
+        mod_loader: String,
+        loader: String,
+    },
+    // mod 不支持当前的游戏版本
+    GameVersionMismatch {
+        id: String,
+        path: String,
+        versions: Vec<String>,
+        game_version: String,
+    },
+}
+
+// 由游戏或加载器本身提供，不会出现在 mods 目录中
+const PLATFORM_IDS: [&str; 8] = [
+    "minecraft",
+    "java",
+    "fabricloader",
+    "quilt_loader",
+    "forge",
+    "neoforge",
+    "fml",
+    "javafml",
+];
+
+// 加载器自身在依赖中使用的 id
+fn loader_id(loader: &str) -> &str {
+    match loader {
+        FABRIC => "fabricloader",
+        QUILT => "quilt_loader",
+        other => other,
+    }
+}
+
+// 检查已启用的 mod 之间的依赖与冲突，以及与目标版本的兼容性
+pub fn check_mods(mods: &[ModPath], environment: &ModEnvironment) -> ModCheckReport {
+    let mut issues = Vec::new();
+    let mods: Vec<_> = mods
+        .iter()
+        .filter(|m| m.enabled)
+        .filter_map(|m| Some((m, m.metadata.as_ref()?)))
+        .collect();
+
+    // 重复的 mod id，按首次出现的顺序输出
+    let mut by_id: Vec<(&str, Vec<String>)> = Vec::new();
+    for (mod_path, metadata) in &mods {
+        match by_id.iter_mut().find(|(id, _)| *id == metadata.id) {
+            Some((_, paths)) => paths.push(mod_path.path.clone()),
+            None => by_id.push((&metadata.id, vec![mod_path.path.clone()])),
+        }
+    }
+    for (id, paths) in by_id {
+        if paths.len() > 1 {
+            issues.push(ModIssue::DuplicateId {
+                id: id.to_string(),
+                paths,
+            });
+        }
+    }
+
+    // 所有已安装 mod 提供的 id 及版本
+    let mut provided: HashMap<&str, &str> = HashMap::new();
+    for (_, metadata) in &mods {
+        provided.insert(&metadata.id, &metadata.version);
+        for id in &metadata.provides {
+            provided.entry(id).or_insert(&metadata.version);
+        }
+    }
+
+    for (mod_path, metadata) in &mods {
+        let id = metadata.id.clone();
+        let path = mod_path.path.clone();
+
+        if let Some(loader) = &environment.loader {
+            if !loader_accepts(loader, &metadata.loader, &environment.game_version) {
+                issues.push(ModIssue::LoaderMismatch {
+                    id: id.clone(),
+                    path: path.clone(),
+                    mod_loader: metadata.loader.clone(),
+                    loader: loader.clone(),
+                });
+                // 加载器都不对，依赖检查已经没有意义
+                continue;
+            }
+        }
+
+        if !range_matches(
+            &metadata.loader,
+            &metadata.game_versions,
+            &environment.game_version,
+        ) {
+            issues.push(ModIssue::GameVersionMismatch {
+                id: id.clone(),
+                path: path.clone(),
+                versions: metadata.game_versions.clone(),
+                game_version: environment.game_version.clone(),
+            });
+        }
+
+        for dependency in &metadata.dependencies {
+            let found = if PLATFORM_IDS.contains(&dependency.id.as_str()) {
+                // 只检查当前加载器自身的版本，其余由游戏或加载器提供
+                match (&environment.loader, &environment.loader_version) {
+                    (Some(loader), Some(version)) if loader_id(loader) == dependency.id => {
+                        Some(version.as_str())
+                    }
+                    _ => continue,
+                }
+            } else {
+                provided.get(dependency.id.as_str()).copied()
+            };
+
+            match (dependency.kind, found) {
+                (DependencyKind::Required, None) => issues.push(ModIssue::MissingDependency {
+                    id: id.clone(),
+                    path: path.clone(),
+                    dependency: dependency.id.clone(),
+                    versions: dependency.versions.clone(),
+                }),
+                (DependencyKind::Required, Some(found))
+                    if !range_matches(&metadata.loader, &dependency.versions, found) =>
+                {
+                    issues.push(ModIssue::DependencyVersionMismatch {
+                        id: id.clone(),
+                        path: path.clone(),
+                        dependency: dependency.id.clone(),
+                        versions: dependency.versions.clone(),
+                        found: found.to_string(),
+                    })
+                }
+                (DependencyKind::Incompatible, Some(found))
+                    if range_matches(&metadata.loader, &dependency.versions, found) =>
+                {
+                    issues.push(ModIssue::Incompatible {
+                        id: id.clone(),
+                        path: path.clone(),
+                        dependency: dependency.id.clone(),
+                        found: found.to_string(),
+                    })
+                }
+                _ => {}
+            }
+        }
+    }
+
+    ModCheckReport { issues }
+}
+
+// Quilt 可以加载 Fabric mod，1.20.5 之前的 NeoForge 仍使用 mods.toml
+fn loader_accepts(loader: &str, mod_loader: &str, game_version: &str) -> bool {
+    loader == mod_loader
+        || (loader == QUILT && mod_loader == FABRIC)
+        || (loader == NEOFORGE
+            && mod_loader == FORGE
+            && semver_matches("<1.20.5", game_version) != Some(false))
+}
+
+// 任意一个范围满足即可，无法解析的范围视为满足，避免误报
+fn range_matches(mod_loader: &str, ranges: &[String], version: &str) -> bool {
+    if ranges.is_empty() {
+        return true;
+    }
+    ranges.iter().any(|range| {
+        let matches = match mod_loader {
+            FABRIC | QUILT => semver_matches(range, version),
+            _ => maven_matches(range, version),
+        };
+        matches.unwrap_or(true)
+    })
+}
+
+// 宽松的版本号：数字段加可选的预发布标识，忽略 + 之后的构建信息
+#[derive(Debug)]
+struct Version {
+    parts: Vec<u64>,
+    pre: Option<String>,
+}
+
+impl Version {
+    fn parse(text: &str) -> Option<Version> {
+        let text = text.trim();
+        let text = text.split('+').next()?;
+        let (core, pre) = match text.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (text, None),
+        };
+        let parts = core
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some(Version { parts, pre })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let len = self.parts.len().max(other.parts.len());
+        for i in 0..len {
+            let a = self.parts.get(i).copied().unwrap_or(0);
+            let b = other.parts.get(i).copied().unwrap_or(0);
+            if a != b {
+                return a.cmp(&b);
+            }
+        }
+        // 正式版大于预发布版
+        match (&self.pre, &other.pre) {
+            (None, None) => Ordering::Equal,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(_), None) => Ordering::Less,
+            (Some(a), Some(b)) => a.cmp(b),
+        }
+    }
+}
+
+// 1.20 与 1.20.0 视为相等
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// fabric.mod.json 中的版本谓词，空格分隔的多个谓词需要同时满足
+// 支持 *、=、>、>=、<、<=、~、^ 以及 1.20.x 这种通配写法
+fn semver_matches(predicate: &str, version: &str) -> Option<bool> {
+    let version = Version::parse(version)?;
+    for term in predicate.split_whitespace() {
+        if term == "*" {
+            continue;
+        }
+        let (op, rest) = [">=", "<=", ">", "<", "=", "~", "^"]
+            .iter()
+            .find_map(|op| term.strip_prefix(op).map(|rest| (*op, rest)))
+            .unwrap_or(("", term));
+
+        // 通配写法只比较给出的前几段
+        let wildcard = rest.split('.').position(|p| matches!(p, "x" | "X" | "*"));
+        if let Some(position) = wildcard {
+            let prefix = rest
+                .split('.')
+                .take(position)
+                .map(|p| p.parse().ok())
+                .collect::<Option<Vec<u64>>>()?;
+            if !prefix
+                .iter()
+                .enumerate()
+                .all(|(i, p)| version.parts.get(i).copied().unwrap_or(0) == *p)
+            {
+                return Some(false);
+            }
+            continue;
+        }
+
+        let target = Version::parse(rest)?;
+        let ordering = version.cmp(&target);
+        let matched = match op {
+            ">=" => ordering != Ordering::Less,
+            "<=" => ordering != Ordering::Greater,
+            ">" => ordering == Ordering::Greater,
+            "<" => ordering == Ordering::Less,
+            // ~1.2.3 要求主次版本相同，^1.2.3 要求主版本相同
+            "~" => {
+                let fixed = if target.parts.len() > 1 { 2 } else { 1 };
+                ordering != Ordering::Less
+                    && version
+                        .parts
+                        .iter()
+                        .take(fixed)
+                        .eq(target.parts.iter().take(fixed))
+            }
+            "^" => ordering != Ordering::Less && version.parts.first() == target.parts.first(),
+            _ => ordering == Ordering::Equal,
+        };
+        if !matched {
+            return Some(false);
+        }
+    }
+    Some(true)
+}
+
+// mods.toml 中的 Maven 版本范围，例如 [1.20.1,1.21)、[47,)、(,1.0]、[1.0]
+// 多个区间以逗号连接时满足任意一个即可，没有括号的版本号按相等处理
+fn maven_matches(range: &str, version: &str) -> Option<bool> {
+    let range = range.trim();
+    let version = Version::parse(version)?;
+    if !range.starts_with(['[', '(']) {
+        return Some(version == Version::parse(range)?);
+    }
+
+    let mut rest = range;
+    while let Some(start) = rest.find(['[', '(']) {
+        let end = start + rest[start..].find([']', ')'])?;
+        let inclusive_lower = rest[start..].starts_with('[');
+        let inclusive_upper = rest[end..].starts_with(']');
+        let inner = &rest[start + 1..end];
+
+        let matched = match inner.split_once(',') {
+            None => version == Version::parse(inner)?,
+            Some((lower, upper)) => {
+                let lower_ok = match lower.trim() {
+                    "" => true,
+                    lower => match version.cmp(&Version::parse(lower)?) {
+                        Ordering::Greater => true,
+                        Ordering::Equal => inclusive_lower,
+                        Ordering::Less => false,
+                    },
+                };
+                let upper_ok = match upper.trim() {
+                    "" => true,
+                    upper => match version.cmp(&Version::parse(upper)?) {
+                        Ordering::Less => true,
+                        Ordering::Equal => inclusive_upper,
+                        Ordering::Greater => false,
+                    },
+                };
+                lower_ok && upper_ok
+            }
+        };
+        if matched {
+            return Some(true);
+        }
+        rest = &rest[end + 1..];
+    }
+    Some(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mod_metadata::{ModDependency, ModMetadata};
+
+    fn mod_path(
+        id: &str,
+        version: &str,
+        loader: &str,
+        dependencies: Vec<ModDependency>,
+    ) -> ModPath {
+        ModPath {
+            path: format!("mods/{}.jar", id),
+            name: id.to_string(),
+            location: "global".to_string(),
+            enabled: true,
+            metadata: Some(ModMetadata {
+                id: id.to_string(),
+                name: id.to_string(),
+                version: version.to_string(),
+                loader: loader.to_string(),
+                description: None,
+                authors: Vec::new(),
+                provides: Vec::new(),
+                game_versions: Vec::new(),
+                dependencies,
+                icon: None,
+            }),
+        }
+    }
+
+    fn required(id: &str, versions: &[&str]) -> ModDependency {
+        ModDependency {
+            id: id.to_string(),
+            versions: versions.iter().map(|v| v.to_string()).collect(),
+            kind: DependencyKind::Required,
+        }
+    }
+
+    #[test]
+    fn test_semver_matches() {
+        assert_eq!(semver_matches(">=0.15.0", "0.15.11"), Some(true));
+        assert_eq!(semver_matches(">=1.20 <1.21", "1.21"), Some(false));
+        assert_eq!(semver_matches("1.20.x", "1.20.4"), Some(true));
+        assert_eq!(semver_matches("~1.20", "1.21"), Some(false));
+        assert_eq!(semver_matches("^0.5.0", "0.5.8+mc1.20.1"), Some(true));
+        assert_eq!(semver_matches("1.20", "1.20.0"), Some(true));
+        assert_eq!(semver_matches(">=1.0.0", "1.0.0-beta.1"), Some(false));
+        assert_eq!(semver_matches(">=1.20", "24w14a"), None);
+    }
+
+    #[test]
+    fn test_maven_matches() {
+        assert_eq!(maven_matches("[1.20.1,1.21)", "1.20.4"), Some(true));
+        assert_eq!(maven_matches("[1.20.1,1.21)", "1.21"), Some(false));
+        assert_eq!(maven_matches("[47,)", "47.2.0"), Some(true));
+        assert_eq!(maven_matches("(,1.0]", "1.0"), Some(true));
+        assert_eq!(maven_matches("[1.0]", "1.0.1"), Some(false));
+        assert_eq!(maven_matches("[1,2),[3,4)", "3.5"), Some(true));
+        assert_eq!(maven_matches("1.7.10", "1.7.10"), Some(true));
+        assert_eq!(maven_matches("[1.20]", "1.20.0"), Some(true));
+    }
+
+    #[test]
+    fn test_check_mods() {
+        let environment = ModEnvironment {
+            game_version: "1.20.1".to_string(),
+            loader: Some(FABRIC.to_string()),
+            loader_version: Some("0.14.0".to_string()),
+        };
+        let mut sodium = mod_path(
+            "sodium",
+            "0.5.8",
+            FABRIC,
+            vec![
+                required("fabricloader", &[">=0.15.0"]),
+                required("fabric-api", &[]),
+            ],
+        );
+        sodium.metadata.as_mut().unwrap().game_versions = vec!["1.21".to_string()];
+        let mut duplicate = mod_path(
+            "iris",
+            "1.7.0",
+            FABRIC,
+            vec![required("sodium", &["<0.5.0"])],
+        );
+        duplicate.path = "versions/1.20.1/mods/iris.jar".to_string();
+        let mut disabled = mod_path("fabric-api", "0.92.0", FABRIC, Vec::new());
+        disabled.enabled = false;
+        let mods = vec![
+            sodium,
+            mod_path("iris", "1.7.0", FABRIC, Vec::new()),
+            duplicate,
+            mod_path("jei", "15.3.0.4", FORGE, Vec::new()),
+            disabled,
+        ];
+
+        let report = check_mods(&mods, &environment);
+        assert!(!report.is_ok());
+        assert_eq!(
+            report.issues,
+            vec![
+                ModIssue::DuplicateId {
+                    id: "iris".to_string(),
+                    paths: vec![
+                        "mods/iris.jar".to_string(),
+                        "versions/1.20.1/mods/iris.jar".to_string()
+                    ],
+                },
+                ModIssue::GameVersionMismatch {
+                    id: "sodium".to_string(),
+                    path: "mods/sodium.jar".to_string(),
+                    versions: vec!["1.21".to_string()],
+                    game_version: "1.20.1".to_string(),
+                },
+                ModIssue::DependencyVersionMismatch {
+                    id: "sodium".to_string(),
+                    path: "mods/sodium.jar".to_string(),
+                    dependency: "fabricloader".to_string(),
+                    versions: vec![">=0.15.0".to_string()],
+                    found: "0.14.0".to_string(),
+                },
+                ModIssue::MissingDependency {
+                    id: "sodium".to_string(),
+                    path: "mods/sodium.jar".to_string(),
+                    dependency: "fabric-api".to_string(),
+                    versions: Vec::new(),
+                },
+                ModIssue::DependencyVersionMismatch {
+                    id: "iris".to_string(),
+                    path: "versions/1.20.1/mods/iris.jar".to_string(),
+                    dependency: "sodium".to_string(),
+                    versions: vec!["<0.5.0".to_string()],
+                    found: "0.5.8".to_string(),
+                },
+                ModIssue::LoaderMismatch {
+                    id: "jei".to_string(),
+                    path: "mods/jei.jar".to_string(),
+                    mod_loader: FORGE.to_string(),
+                    loader: FABRIC.to_string(),
+                },
+            ]
+        );
+    }
+}
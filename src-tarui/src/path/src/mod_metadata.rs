@@ -0,0 +1,665 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+// 依据 jar 内元数据文件判断出的加载器
+pub const FABRIC: &str = "fabric";
+pub const QUILT: &str = "quilt";
+pub const FORGE: &str = "forge";
+pub const NEOFORGE: &str = "neoforge";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModMetadata {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    // fabric / quilt / forge / neoforge
+    pub loader: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    // 额外提供的 mod id (fabric/quilt 的 provides)
+    pub provides: Vec<String>,
+    // 对游戏版本的约束，任意一个满足即可，为空表示不限制
+    pub game_versions: Vec<String>,
+    pub dependencies: Vec<ModDependency>,
+    // base64 编码的图标，前端可直接作为 data URL 使用
+    pub icon: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ModDependency {
+    pub id: String,
+    // 版本范围，任意一个满足即可，为空表示不限制
+    // fabric/quilt 为 SemVer 谓词，forge/neoforge 为 Maven 范围
+    pub versions: Vec<String>,
+    pub kind: DependencyKind,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyKind {
+    Required,
+    Optional,
+    Incompatible,
+}
+
+// 读取 mod jar 的元数据，没有或无法解析时返回 None
+pub fn read_mod_metadata(path: &Path) -> Option<ModMetadata> {
+    let file = File::open(path).ok()?;
+    let mut archive = ZipArchive::new(file).ok()?;
+
+    if let Some(text) = read_entry(&mut archive, "quilt.mod.json") {
+        if let Some(metadata) = parse_quilt(&text, &mut archive) {
+            return Some(metadata);
+        }
+    }
+    if let Some(text) = read_entry(&mut archive, "fabric.mod.json") {
+        if let Some(metadata) = parse_fabric(&text, &mut archive) {
+            return Some(metadata);
+        }
+    }
+    if let Some(text) = read_entry(&mut archive, "META-INF/neoforge.mods.toml") {
+        if let Some(metadata) = parse_mods_toml(&text, NEOFORGE, &mut archive) {
+            return Some(metadata);
+        }
+    }
+    if let Some(text) = read_entry(&mut archive, "META-INF/mods.toml") {
+        if let Some(metadata) = parse_mods_toml(&text, FORGE, &mut archive) {
+            return Some(metadata);
+        }
+    }
+    if let Some(text) = read_entry(&mut archive, "mcmod.info") {
+        if let Some(metadata) = parse_mcmod_info(&text, &mut archive) {
+            return Some(metadata);
+        }
+    }
+    None
+}
+
+pub(crate) fn read_entry<R: std::io::Read + std::io::Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Option<Vec<u8>> {
+    let mut entry = archive.by_name(name.trim_start_matches('/')).ok()?;
+    let mut buffer = Vec::new();
+    entry.read_to_end(&mut buffer).ok()?;
+    Some(buffer)
+}
+
+// 不少 mod 的 json 字符串里直接写了换行，先按标准解析，失败后把控制字符替换成空格再试一次
+pub(crate) fn parse_json(bytes: &[u8]) -> Option<Value> {
+    let text = String::from_utf8_lossy(bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    serde_json::from_str(text).ok().or_else(|| {
+        let sanitized: String = text
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        serde_json::from_str(&sanitized).ok()
+    })
+}
+
+fn string(value: Option<&Value>) -> Option<String> {
+    value.and_then(Value::as_str).map(|s| s.to_string())
+}
+
+// 字符串或字符串数组
+fn strings(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::String(s)) => vec![s.clone()],
+        Some(Value::Array(array)) => array
+            .iter()
+            .filter_map(|v| v.as_str().map(|s| s.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+// 作者可以是字符串，也可以是带 name 的对象
+fn people(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Array(array)) => array
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                Value::Object(o) => string(o.get("name")),
+                _ => None,
+            })
+            .collect(),
+        Some(Value::Object(map)) => map.keys().cloned().collect(),
+        Some(Value::String(s)) => vec![s.clone()],
+        _ => Vec::new(),
+    }
+}
+
+// 图标以 base64 传给前端，数字数组形式的 JSON 比文件本身大数倍
+pub(crate) fn encode_icon(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+// 图标可以是路径，也可以是 尺寸 -> 路径 的映射，取最大的一个
+fn icon<R: std::io::Read + std::io::Seek>(
+    value: Option<&Value>,
+    archive: &mut ZipArchive<R>,
+) -> Option<String> {
+    let path = match value? {
+        Value::String(s) => s.clone(),
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(size, path)| Some((size.parse::<u32>().ok()?, path.as_str()?)))
+            .max_by_key(|(size, _)| *size)
+            .map(|(_, path)| path.to_string())?,
+        _ => return None,
+    };
+    read_entry(archive, &path).map(|bytes| encode_icon(&bytes))
+}
+
+fn parse_fabric<R: std::io::Read + std::io::Seek>(
+    bytes: &[u8],
+    archive: &mut ZipArchive<R>,
+) -> Option<ModMetadata> {
+    let json = parse_json(bytes)?;
+    let id = string(json.get("id"))?;
+
+    let mut game_versions = Vec::new();
+    let mut dependencies = Vec::new();
+    for (key, kind) in [
+        ("depends", DependencyKind::Required),
+        ("recommends", DependencyKind::Optional),
+        ("suggests", DependencyKind::Optional),
+        ("breaks", DependencyKind::Incompatible),
+        ("conflicts", DependencyKind::Incompatible),
+    ] {
+        let Some(Value::Object(map)) = json.get(key) else {
+            continue;
+        };
+        for (dependency, versions) in map {
+            let versions: Vec<String> = strings(Some(versions))
+                .into_iter()
+                .filter(|v| v != "*")
+                .collect();
+            if dependency == "minecraft" && kind == DependencyKind::Required {
+                game_versions = versions;
+                continue;
+            }
+            dependencies.push(ModDependency {
+                id: dependency.clone(),
+                versions,
+                kind,
+            });
+        }
+    }
+
+    Some(ModMetadata {
+        name: string(json.get("name")).unwrap_or_else(|| id.clone()),
+        version: string(json.get("version")).unwrap_or_default(),
+        loader: FABRIC.to_string(),
+        description: string(json.get("description")),
+        authors: people(json.get("authors")),
+        provides: strings(json.get("provides")),
+        game_versions,
+        dependencies,
+        icon: icon(json.get("icon"), archive),
+        id,
+    })
+}
+
+/*
+
+  {
+    "schema_version": 1,
+    "quilt_loader": {
+      "id": "example",
+      "version": "1.0.0",
+      "metadata": { "name": "Example", "contributors": { "Someone": "Owner" }, "icon": "assets/example/icon.png" },
+      "depends": [ "quilt_loader", { "id": "minecraft", "versions": ">=1.20" } ]
+    }
+  }
+
+*/
+fn parse_quilt<R: std::io::Read + std::io::Seek>(
+    bytes: &[u8],
+    archive: &mut ZipArchive<R>,
+) -> Option<ModMetadata> {
+    let json = parse_json(bytes)?;
+    let loader = json.get("quilt_loader")?;
+    let id = string(loader.get("id"))?;
+    let metadata = loader.get("metadata");
+
+    let mut game_versions = Vec::new();
+    let mut dependencies = Vec::new();
+    for (key, kind) in [
+        ("depends", DependencyKind::Required),
+        ("breaks", DependencyKind::Incompatible),
+    ] {
+        let Some(Value::Array(array)) = loader.get(key) else {
+            continue;
+        };
+        for entry in array {
+            let (dependency, versions, optional) = match entry {
+                Value::String(s) => (s.clone(), Vec::new(), false),
+                Value::Object(o) => {
+                    let Some(dependency) = string(o.get("id")) else {
+                        continue;
+                    };
+                    // versions 也可以是 { "any": [...] } / { "all": [...] }，这里只保留 any 的语义
+                    let versions = match o.get("versions") {
+                        Some(Value::Object(v)) => strings(v.get("any").or(v.get("all"))),
+                        versions => strings(versions),
+                    };
+                    let optional = o.get("optional").and_then(Value::as_bool) == Some(true);
+                    (dependency, versions, optional)
+                }
+                _ => continue,
+            };
+            let versions: Vec<String> = versions.into_iter().filter(|v| v != "*").collect();
+            if dependency == "minecraft" && kind == DependencyKind::Required {
+                game_versions = versions;
+                continue;
+            }
+            dependencies.push(ModDependency {
+                id: dependency,
+                versions,
+                kind: if optional && kind == DependencyKind::Required {
+                    DependencyKind::Optional
+                } else {
+                    kind
+                },
+            });
+        }
+    }
+
+    let provides = match loader.get("provides") {
+        Some(Value::Array(array)) => array
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.clone()),
+                Value::Object(o) => string(o.get("id")),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    Some(ModMetadata {
+        name: string(metadata.and_then(|m| m.get("name"))).unwrap_or_else(|| id.clone()),
+        version: string(loader.get("version")).unwrap_or_default(),
+        loader: QUILT.to_string(),
+        description: string(metadata.and_then(|m| m.get("description"))),
+        authors: people(metadata.and_then(|m| m.get("contributors"))),
+        provides,
+        game_versions,
+        dependencies,
+        icon: icon(metadata.and_then(|m| m.get("icon")), archive),
+        id,
+    })
+}
+
+// 从 META-INF/MANIFEST.MF 读取 Implementation-Version，用于替换 ${file.jarVersion}
+fn jar_version<R: std::io::Read + std::io::Seek>(archive: &mut ZipArchive<R>) -> Option<String> {
+    let manifest = read_entry(archive, "META-INF/MANIFEST.MF")?;
+    String::from_utf8_lossy(&manifest)
+        .lines()
+        .find_map(|line| line.strip_prefix("Implementation-Version:"))
+        .map(|v| v.trim().to_string())
+}
+
+/*
+
+  modLoader = "javafml"
+  loaderVersion = "[47,)"
+
+  [[mods]]
+  modId = "example"
+  version = "${file.jarVersion}"
+  displayName = "Example"
+  logoFile = "logo.png"
+
+  [[dependencies.example]]
+  modId = "minecraft"
+  mandatory = true        # neoforge 使用 type = "required"
+  versionRange = "[1.20.1,1.21)"
+
+*/
+fn parse_mods_toml<R: std::io::Read + std::io::Seek>(
+    bytes: &[u8],
+    loader: &str,
+    archive: &mut ZipArchive<R>,
+) -> Option<ModMetadata> {
+    let text = String::from_utf8_lossy(bytes);
+    let toml: toml::Table = text.trim_start_matches('\u{feff}').parse().ok()?;
+    let mods = toml.get("mods")?.as_array()?;
+    let first = mods.first()?.as_table()?;
+    let id = first.get("modId")?.as_str()?.to_string();
+    let str_of = |table: &toml::Table, key: &str| {
+        table
+            .get(key)
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().to_string())
+    };
+
+    let mut version = str_of(first, "version").unwrap_or_default();
+    if version.contains("${file.jarVersion}") {
+        if let Some(jar_version) = jar_version(archive) {
+            version = version.replace("${file.jarVersion}", &jar_version);
+        }
+    }
+
+    let mut game_versions = Vec::new();
+    let mut dependencies = Vec::new();
+    let entries = toml
+        .get("dependencies")
+        .and_then(|d| d.get(&id))
+        .and_then(|d| d.as_array());
+    for entry in entries.into_iter().flatten() {
+        let Some(entry) = entry.as_table() else {
+            continue;
+        };
+        let Some(dependency) = str_of(entry, "modId") else {
+            continue;
+        };
+        let kind = match str_of(entry, "type").as_deref() {
+            Some("required") => DependencyKind::Required,
+            Some("incompatible") => DependencyKind::Incompatible,
+            Some(_) => DependencyKind::Optional,
+            None => match entry.get("mandatory").and_then(|v| v.as_bool()) {
+                Some(false) => DependencyKind::Optional,
+                _ => DependencyKind::Required,
+            },
+        };
+        let versions: Vec<String> = str_of(entry, "versionRange")
+            .filter(|v| !v.is_empty() && v != "*")
+            .into_iter()
+            .collect();
+        if dependency == "minecraft" && kind == DependencyKind::Required {
+            game_versions = versions;
+            continue;
+        }
+        dependencies.push(ModDependency {
+            id: dependency,
+            versions,
+            kind,
+        });
+    }
+
+    let logo = str_of(first, "logoFile").or_else(|| str_of(&toml, "logoFile"));
+    let icon = logo
+        .and_then(|logo| read_entry(archive, &logo))
+        .map(|bytes| encode_icon(&bytes));
+
+    Some(ModMetadata {
+        name: str_of(first, "displayName").unwrap_or_else(|| id.clone()),
+        version,
+        loader: loader.to_string(),
+        description: str_of(first, "description"),
+        authors: str_of(first, "authors")
+            .or_else(|| str_of(&toml, "authors"))
+            .map(|authors| {
+                authors
+                    .split(',')
+                    .map(|a| a.trim().to_string())
+                    .filter(|a| !a.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        provides: Vec::new(),
+        game_versions,
+        dependencies,
+        icon,
+        id,
+    })
+}
+
+// 旧版 forge 的 mcmod.info，可能是数组，也可能是 { "modList": [...] }
+fn parse_mcmod_info<R: std::io::Read + std::io::Seek>(
+    bytes: &[u8],
+    archive: &mut ZipArchive<R>,
+) -> Option<ModMetadata> {
+    let json = parse_json(bytes)?;
+    let first = match &json {
+        Value::Array(array) => array.first()?,
+        Value::Object(o) => o.get("modList")?.as_array()?.first()?,
+        _ => return None,
+    };
+    let id = string(first.get("modid"))?;
+
+    // requiredMods 为必需的前置，dependencies 只决定加载顺序，视为可选；
+    // 旧版中 Forge 自身写作 Forge 或 FML，统一为小写以便识别为加载器
+    let ids = |key: &str| -> Vec<String> {
+        strings(first.get(key))
+            .into_iter()
+            .map(|id| match id.as_str() {
+                "Forge" | "FML" => id.to_lowercase(),
+                _ => id,
+            })
+            .collect()
+    };
+    let required = ids("requiredMods");
+    let optional: Vec<String> = ids("dependencies")
+        .into_iter()
+        .filter(|dependency| !required.contains(dependency))
+        .collect();
+    let dependencies = required
+        .into_iter()
+        .map(|dependency| (dependency, DependencyKind::Required))
+        .chain(
+            optional
+                .into_iter()
+                .map(|dependency| (dependency, DependencyKind::Optional)),
+        )
+        .map(|(id, kind)| ModDependency {
+            id,
+            versions: Vec::new(),
+            kind,
+        })
+        .collect();
+
+    Some(ModMetadata {
+        name: string(first.get("name")).unwrap_or_else(|| id.clone()),
+        version: string(first.get("version")).unwrap_or_default(),
+        loader: FORGE.to_string(),
+        description: string(first.get("description")),
+        authors: people(first.get("authorList").or(first.get("authors"))),
+        provides: Vec::new(),
+        game_versions: string(first.get("mcversion"))
+            .filter(|v| !v.is_empty())
+            .into_iter()
+            .collect(),
+        dependencies,
+        icon: icon(first.get("logoFile"), archive),
+        id,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use zip::write::SimpleFileOptions;
+
+    fn jar(name: &str, entries: &[(&str, &[u8])]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("mcl-mod-metadata-{}.jar", name));
+        let mut writer = zip::ZipWriter::new(File::create(&path).unwrap());
+        for (entry, content) in entries {
+            writer
+                .start_file(*entry, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn test_fabric() {
+        let path = jar(
+            "fabric",
+            &[
+                (
+                    "fabric.mod.json",
+                    br#"{
+                        "schemaVersion": 1,
+                        "id": "sodium",
+                        "version": "0.5.8",
+                        "name": "Sodium",
+                        "description": "line one
+line two",
+                        "authors": ["JellySquid", { "name": "IMS" }],
+                        "icon": { "32": "small.png", "128": "large.png" },
+                        "depends": { "fabricloader": ">=0.12.0", "minecraft": ["1.20.x", "1.21"] },
+                        "breaks": { "optifabric": "*" }
+                    }"#,
+                ),
+                ("small.png", b"small"),
+                ("large.png", b"large"),
+            ],
+        );
+        let metadata = read_mod_metadata(&path).unwrap();
+        assert_eq!("sodium", metadata.id);
+        assert_eq!("Sodium", metadata.name);
+        assert_eq!(FABRIC, metadata.loader);
+        assert_eq!(vec!["JellySquid", "IMS"], metadata.authors);
+        assert_eq!(vec!["1.20.x", "1.21"], metadata.game_versions);
+        assert_eq!(Some("bGFyZ2U=".to_string()), metadata.icon);
+        let loader = metadata
+            .dependencies
+            .iter()
+            .find(|d| d.id == "fabricloader")
+            .unwrap();
+        assert_eq!(DependencyKind::Required, loader.kind);
+        assert_eq!(vec![">=0.12.0"], loader.versions);
+        let optifabric = metadata
+            .dependencies
+            .iter()
+            .find(|d| d.id == "optifabric")
+            .unwrap();
+        assert_eq!(DependencyKind::Incompatible, optifabric.kind);
+        assert!(optifabric.versions.is_empty());
+    }
+
+    #[test]
+    fn test_quilt() {
+        let path = jar(
+            "quilt",
+            &[(
+                "quilt.mod.json",
+                br#"{
+                    "schema_version": 1,
+                    "quilt_loader": {
+                        "id": "qsl",
+                        "version": "7.0.0",
+                        "metadata": { "name": "QSL", "contributors": { "Quilt": "Owner" } },
+                        "provides": ["quilted_fabric_api"],
+                        "depends": [
+                            "quilt_loader",
+                            { "id": "minecraft", "versions": ">=1.20" },
+                            { "id": "modmenu", "optional": true }
+                        ]
+                    }
+                }"#,
+            )],
+        );
+        let metadata = read_mod_metadata(&path).unwrap();
+        assert_eq!("qsl", metadata.id);
+        assert_eq!(QUILT, metadata.loader);
+        assert_eq!(vec!["Quilt"], metadata.authors);
+        assert_eq!(vec!["quilted_fabric_api"], metadata.provides);
+        assert_eq!(vec![">=1.20"], metadata.game_versions);
+        assert_eq!(2, metadata.dependencies.len());
+        assert_eq!(DependencyKind::Optional, metadata.dependencies[1].kind);
+    }
+
+    #[test]
+    fn test_mods_toml() {
+        let path = jar(
+            "forge",
+            &[
+                (
+                    "META-INF/mods.toml",
+                    br#"
+                    modLoader = "javafml"
+                    loaderVersion = "[47,)"
+                    logoFile = "logo.png"
+
+                    [[mods]]
+                    modId = "jei"
+                    version = "${file.jarVersion}"
+                    displayName = "Just Enough Items"
+                    authors = "mezz, Ranger"
+
+                    [[dependencies.jei]]
+                    modId = "minecraft"
+                    mandatory = true
+                    versionRange = "[1.20.1,1.20.2)"
+
+                    [[dependencies.jei]]
+                    modId = "forge"
+                    mandatory = true
+                    versionRange = "[47.0.0,)"
+                    "#,
+                ),
+                (
+                    "META-INF/MANIFEST.MF",
+                    b"Manifest-Version: 1.0\r\nImplementation-Version: 15.3.0.4\r\n",
+                ),
+                ("logo.png", b"logo"),
+            ],
+        );
+        let metadata = read_mod_metadata(&path).unwrap();
+        assert_eq!("jei", metadata.id);
+        assert_eq!(FORGE, metadata.loader);
+        assert_eq!("15.3.0.4", metadata.version);
+        assert_eq!(vec!["mezz", "Ranger"], metadata.authors);
+        assert_eq!(vec!["[1.20.1,1.20.2)"], metadata.game_versions);
+        assert_eq!("forge", metadata.dependencies[0].id);
+        assert_eq!(Some("bG9nbw==".to_string()), metadata.icon);
+    }
+
+    #[test]
+    fn test_mcmod_info() {
+        let path = jar(
+            "legacy",
+            &[(
+                "mcmod.info",
+                br#"{ "modListVersion": 2, "modList": [{ "modid": "ic2", "name": "IndustrialCraft 2", "version": "2.2", "mcversion": "1.7.10", "authorList": ["Player"], "requiredMods": ["Forge"], "dependencies": ["Forge", "NotEnoughItems"] }] }"#,
+            )],
+        );
+        let metadata = read_mod_metadata(&path).unwrap();
+        assert_eq!("ic2", metadata.id);
+        assert_eq!(FORGE, metadata.loader);
+        assert_eq!(vec!["1.7.10"], metadata.game_versions);
+        assert_eq!(2, metadata.dependencies.len());
+        assert_eq!(DependencyKind::Required, metadata.dependencies[0].kind);
+        assert_eq!("NotEnoughItems", metadata.dependencies[1].id);
+        assert_eq!(DependencyKind::Optional, metadata.dependencies[1].kind);
+
+        // dependencies 中的 mod 未安装时不报告缺少前置
+        let mod_path = crate::minecraft_path::ModPath {
+            path: path.to_string_lossy().to_string(),
+            name: metadata.name.clone(),
+            location: "global".to_string(),
+            enabled: true,
+            metadata: Some(metadata),
+        };
+        let environment = crate::mod_check::ModEnvironment {
+            game_version: "1.7.10".to_string(),
+            loader: Some(FORGE.to_string()),
+            loader_version: Some("10.13.4.1614".to_string()),
+        };
+        assert!(crate::mod_check::check_mods(&[mod_path], &environment).is_ok());
+    }
+
+    #[test]
+    fn test_malformed() {
+        let path = jar("malformed", &[("fabric.mod.json", b"{ not json")]);
+        assert!(read_mod_metadata(&path).is_none());
+        let path = std::env::temp_dir().join("mcl-mod-metadata-not-a-jar.jar");
+        std::fs::write(&path, b"not a zip").unwrap();
+        assert!(read_mod_metadata(&path).is_none());
+    }
+}
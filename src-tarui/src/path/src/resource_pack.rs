@@ -0,0 +1,538 @@
+use std::fs::{self, File};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::minecraft_path::MinecraftPath;
+use crate::mod_metadata::{encode_icon, parse_json, read_entry};
+
+const OPTIONS_FILE: &str = "options.txt";
+// options.txt 中已启用的资源包，靠后的优先级更高
+const RESOURCE_PACKS_KEY: &str = "resourcePacks";
+// 版本不匹配但仍被用户启用的资源包，不在其中的不兼容资源包会被游戏移除
+const INCOMPATIBLE_PACKS_KEY: &str = "incompatibleResourcePacks";
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PackKind {
+    Resource,
+    Shader,
+}
+
+impl PackKind {
+    // 游戏目录下存放该类包的目录
+    pub fn directory(&self) -> &'static str {
+        match self {
+            PackKind::Resource => "resourcepacks",
+            PackKind::Shader => "shaderpacks",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PackCompatibility {
+    Compatible,
+    // 为更旧的游戏版本制作
+    TooOld,
+    // 为更新的游戏版本制作
+    TooNew,
+    // 没有 pack_format、光影包或无法判断游戏版本对应的格式
+    Unknown,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct PackInfo {
+    pub path: String,
+    pub file_name: String,
+    pub kind: PackKind,
+    // 解压后的文件夹为 true，压缩包为 false
+    pub is_directory: bool,
+    // 资源包是否在 options.txt 中启用，光影包的选择由 OptiFine/Iris 管理，为 None
+    pub enabled: Option<bool>,
+    pub pack_format: Option<u32>,
+    // 支持的 pack_format 范围，1.20.2 起的 supported_formats 或 1.21.9 起的 min_format/max_format
+    pub supported_formats: Option<(u32, u32)>,
+    // 文本组件会被转换为纯文本，保留 § 格式代码
+    pub description: Option<String>,
+    // base64 编码的 pack.png
+    pub icon: Option<String>,
+    pub compatibility: PackCompatibility,
+}
+
+// 各游戏版本资源包的 pack_format，按版本从新到旧排列，(起始版本, pack_format)
+const RESOURCE_PACK_FORMATS: [((u64, u64), u32); 21] = [
+    ((21, 7), 64),
+    ((21, 6), 63),
+    ((21, 5), 55),
+    ((21, 4), 46),
+    ((21, 2), 42),
+    ((21, 0), 34),
+    ((20, 5), 32),
+    ((20, 3), 22),
+    ((20, 2), 18),
+    ((20, 0), 15),
+    ((19, 4), 13),
+    ((19, 3), 12),
+    ((19, 0), 9),
+    ((18, 0), 8),
+    ((17, 0), 7),
+    ((16, 2), 6),
+    ((15, 0), 5),
+    ((13, 0), 4),
+    ((11, 0), 3),
+    ((9, 0), 2),
+    ((6, 1), 1),
+];
+
+// 表中最后一个明确的版本，更新的版本无法确定格式
+const LATEST_KNOWN_VERSION: (u64, u64) = (21, 8);
+
+// 游戏版本对应的资源包 pack_format，快照与表中没有的版本返回 None
+pub fn resource_pack_format(game_version: &str) -> Option<u32> {
+    let release = game_version.split(['-', ' ']).next()?;
+    let mut parts = release.split('.').map(|part| part.parse::<u64>().ok());
+    if parts.next()?? != 1 {
+        return None;
+    }
+    let minor = parts.next()??;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if (minor, patch) > LATEST_KNOWN_VERSION || (minor, patch) < (6, 1) {
+        return None;
+    }
+    RESOURCE_PACK_FORMATS
+        .iter()
+        .find(|(since, _)| (minor, patch) >= *since)
+        .map(|(_, format)| *format)
+}
+
+// 比较资源包支持的格式与游戏版本的格式
+pub fn check_pack_format(supported: Option<(u32, u32)>, game_version: &str) -> PackCompatibility {
+    let (Some((min, max)), Some(format)) = (supported, resource_pack_format(game_version)) else {
+        return PackCompatibility::Unknown;
+    };
+    if format < min {
+        PackCompatibility::TooNew
+    } else if format > max {
+        PackCompatibility::TooOld
+    } else {
+        PackCompatibility::Compatible
+    }
+}
+
+// 文本组件转换为纯文本，可以是字符串、数组或带 text/extra 的对象
+fn component_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(array) => array.iter().map(component_text).collect(),
+        Value::Object(object) => {
+            let mut text = object
+                .get("text")
+                .or_else(|| object.get("translate"))
+                .map(component_text)
+                .unwrap_or_default();
+            if let Some(Value::Array(extra)) = object.get("extra") {
+                text.extend(extra.iter().map(component_text));
+            }
+            text
+        }
+        Value::Number(number) => number.to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Null => String::new(),
+    }
+}
+
+// 格式可以是整数，1.21.9 起也可以是 [主版本, 次版本]，只取主版本
+fn format_number(value: &Value) -> Option<u32> {
+    match value {
+        Value::Array(array) => array.first().and_then(format_number),
+        _ => value.as_u64().and_then(|n| u32::try_from(n).ok()),
+    }
+}
+
+// supported_formats 可以是整数、[最小, 最大] 或 { min_inclusive, max_inclusive }
+fn format_range(value: &Value) -> Option<(u32, u32)> {
+    match value {
+        Value::Array(array) if array.len() == 2 => {
+            Some((format_number(&array[0])?, format_number(&array[1])?))
+        }
+        Value::Object(object) => Some((
+            format_number(object.get("min_inclusive")?)?,
+            format_number(object.get("max_inclusive")?)?,
+        )),
+        _ => format_number(value).map(|n| (n, n)),
+    }
+}
+
+struct PackMeta {
+    pack_format: Option<u32>,
+    supported_formats: Option<(u32, u32)>,
+    description: Option<String>,
+}
+
+fn parse_pack_mcmeta(bytes: &[u8]) -> Option<PackMeta> {
+    let json = parse_json(bytes)?;
+    let pack = json.get("pack")?;
+    let pack_format = pack.get("pack_format").and_then(format_number);
+    let supported_formats = pack
+        .get("supported_formats")
+        .and_then(format_range)
+        .or_else(|| {
+            let min = pack.get("min_format").and_then(format_number)?;
+            let max = pack.get("max_format").and_then(format_number)?;
+            Some((min, max))
+        });
+    Some(PackMeta {
+        pack_format: pack_format.or(supported_formats.map(|(_, max)| max)),
+        supported_formats,
+        description: pack.get("description").map(component_text),
+    })
+}
+
+// 读取包的 pack.mcmeta 与 pack.png，光影包通常没有这两个文件
+fn read_pack_files(path: &Path) -> (Option<PackMeta>, Option<String>) {
+    if path.is_dir() {
+        let meta = fs::read(path.join("pack.mcmeta"))
+            .ok()
+            .and_then(|bytes| parse_pack_mcmeta(&bytes));
+        let icon = fs::read(path.join("pack.png")).ok();
+        (meta, icon.map(|bytes| encode_icon(&bytes)))
+    } else {
+        let Some(mut archive) = File::open(path)
+            .ok()
+            .and_then(|file| ZipArchive::new(file).ok())
+        else {
+            return (None, None);
+        };
+        let meta =
+            read_entry(&mut archive, "pack.mcmeta").and_then(|bytes| parse_pack_mcmeta(&bytes));
+        let icon = read_entry(&mut archive, "pack.png");
+        (meta, icon.map(|bytes| encode_icon(&bytes)))
+    }
+}
+
+// 读取 options.txt 中的资源包列表，值为 JSON 字符串数组
+fn read_pack_list(options: &str, key: &str) -> Option<Vec<String>> {
+    options.lines().find_map(|line| {
+        let value = line.strip_prefix(key)?.strip_prefix(':')?;
+        serde_json::from_str(value.trim()).ok()
+    })
+}
+
+// 替换 options.txt 中的一项，没有时添加到末尾，其他行保持不变
+fn write_pack_list(options: &str, key: &str, list: &[String]) -> String {
+    let line = format!(
+        "{}:{}",
+        key,
+        serde_json::to_string(list).unwrap_or_default()
+    );
+    let mut found = false;
+    let mut lines: Vec<String> = options
+        .lines()
+        .map(|existing| {
+            if existing
+                .strip_prefix(key)
+                .is_some_and(|rest| rest.starts_with(':'))
+            {
+                found = true;
+                line.clone()
+            } else {
+                existing.to_string()
+            }
+        })
+        .collect();
+    if !found {
+        lines.push(line);
+    }
+    lines.join("\n") + "\n"
+}
+
+// options.txt 中资源包的名称
+fn pack_id(file_name: &str) -> String {
+    format!("file/{}", file_name)
+}
+
+// 列出游戏目录下的资源包或光影包，指定游戏版本时检查资源包的兼容性
+pub fn list_packs(
+    game_directory: &Path,
+    kind: PackKind,
+    game_version: Option<&str>,
+) -> Vec<PackInfo> {
+    let enabled = match kind {
+        PackKind::Resource => Some(
+            fs::read_to_string(game_directory.join(OPTIONS_FILE))
+                .ok()
+                .and_then(|options| read_pack_list(&options, RESOURCE_PACKS_KEY))
+                .unwrap_or_default(),
+        ),
+        PackKind::Shader => None,
+    };
+    let mut packs: Vec<PackInfo> = fs::read_dir(game_directory.join(kind.directory()))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_dir()
+                        || path
+                            .extension()
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+                })
+                .filter_map(|path| {
+                    let file_name = path.file_name()?.to_string_lossy().to_string();
+                    let (meta, icon) = read_pack_files(&path);
+                    let supported_formats = meta.as_ref().and_then(|meta| {
+                        meta.supported_formats.or(meta.pack_format.map(|f| (f, f)))
+                    });
+                    Some(PackInfo {
+                        path: path.to_string_lossy().to_string(),
+                        is_directory: path.is_dir(),
+                        kind,
+                        enabled: enabled
+                            .as_ref()
+                            .map(|enabled| enabled.contains(&pack_id(&file_name))),
+                        pack_format: meta.as_ref().and_then(|meta| meta.pack_format),
+                        supported_formats: meta.as_ref().and_then(|meta| meta.supported_formats),
+                        description: meta.and_then(|meta| meta.description),
+                        icon,
+                        compatibility: match (kind, game_version) {
+                            (PackKind::Resource, Some(game_version)) => {
+                                check_pack_format(supported_formats, game_version)
+                            }
+                            _ => PackCompatibility::Unknown,
+                        },
+                        file_name,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    packs.sort_by_key(|pack| pack.file_name.to_lowercase());
+    packs
+}
+
+// 在 options.txt 中启用或禁用资源包，启用时放到优先级最高的位置，
+// incompatible 为 true 时同时加入 incompatibleResourcePacks，否则游戏启动时会移除它
+pub fn set_resource_pack_enabled(
+    game_directory: &Path,
+    file_name: &str,
+    enabled: bool,
+    incompatible: bool,
+) -> Result<(), &'static str> {
+    let pack = game_directory
+        .join(PackKind::Resource.directory())
+        .join(file_name);
+    // 只接受资源包目录下的文件名，不允许空名称与 . 或 ..
+    if matches!(file_name, "" | "." | "..") || file_name.contains(['/', '\\']) || !pack.exists() {
+        return Err("未找到资源包");
+    }
+    let path = game_directory.join(OPTIONS_FILE);
+    let options = fs::read_to_string(&path).unwrap_or_default();
+    let id = pack_id(file_name);
+
+    // 游戏默认只启用 vanilla
+    let mut packs =
+        read_pack_list(&options, RESOURCE_PACKS_KEY).unwrap_or_else(|| vec!["vanilla".to_string()]);
+    let mut incompatible_packs =
+        read_pack_list(&options, INCOMPATIBLE_PACKS_KEY).unwrap_or_default();
+    packs.retain(|pack| *pack != id);
+    incompatible_packs.retain(|pack| *pack != id);
+    if enabled {
+        packs.push(id.clone());
+        if incompatible {
+            incompatible_packs.push(id);
+        }
+    }
+
+    let options = write_pack_list(&options, RESOURCE_PACKS_KEY, &packs);
+    let options = write_pack_list(&options, INCOMPATIBLE_PACKS_KEY, &incompatible_packs);
+    fs::write(&path, options).map_err(|_| "写入 options.txt 失败")
+}
+
+impl MinecraftPath {
+    // 版本游戏目录下的资源包或光影包，未隔离的版本为根目录下的
+    pub fn get_version_packs(
+        &self,
+        version_name: &str,
+        kind: PackKind,
+        game_version: Option<&str>,
+    ) -> Vec<PackInfo> {
+        list_packs(&self.get_game_directory(version_name), kind, game_version)
+    }
+
+    // 启用或禁用版本游戏目录下的资源包，启用与游戏版本不兼容的资源包时同时标记为不兼容
+    pub fn set_resource_pack_enabled(
+        &self,
+        version_name: &str,
+        file_name: &str,
+        enabled: bool,
+        game_version: Option<&str>,
+    ) -> Result<(), &'static str> {
+        let game_directory = self.get_game_directory(version_name);
+        let incompatible = enabled
+            && list_packs(&game_directory, PackKind::Resource, game_version)
+                .iter()
+                .find(|pack| pack.file_name == file_name)
+                .is_some_and(|pack| {
+                    matches!(
+                        pack.compatibility,
+                        PackCompatibility::TooOld | PackCompatibility::TooNew
+                    )
+                });
+        set_resource_pack_enabled(&game_directory, file_name, enabled, incompatible)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_resource_pack_format() {
+        assert_eq!(Some(1), resource_pack_format("1.8.9"));
+        assert_eq!(Some(3), resource_pack_format("1.12.2"));
+        assert_eq!(Some(6), resource_pack_format("1.16.5"));
+        assert_eq!(Some(5), resource_pack_format("1.16"));
+        assert_eq!(Some(15), resource_pack_format("1.20.1"));
+        assert_eq!(Some(32), resource_pack_format("1.20.5-pre1"));
+        assert_eq!(Some(34), resource_pack_format("1.21"));
+        assert_eq!(Some(64), resource_pack_format("1.21.8"));
+        assert_eq!(None, resource_pack_format("1.21.9"));
+        assert_eq!(None, resource_pack_format("24w14a"));
+        assert_eq!(None, resource_pack_format("1.5.2"));
+
+        assert_eq!(
+            PackCompatibility::Compatible,
+            check_pack_format(Some((15, 15)), "1.20.1")
+        );
+        assert_eq!(
+            PackCompatibility::TooOld,
+            check_pack_format(Some((15, 15)), "1.21")
+        );
+        assert_eq!(
+            PackCompatibility::TooNew,
+            check_pack_format(Some((34, 34)), "1.20.1")
+        );
+        assert_eq!(
+            PackCompatibility::Compatible,
+            check_pack_format(Some((15, 34)), "1.20.4")
+        );
+        assert_eq!(
+            PackCompatibility::Unknown,
+            check_pack_format(None, "1.20.1")
+        );
+    }
+
+    #[test]
+    fn test_parse_pack_mcmeta() {
+        let meta = parse_pack_mcmeta(
+            br#"{ "pack": { "pack_format": 15, "supported_formats": { "min_inclusive": 15, "max_inclusive": 34 },
+              "description": [{ "text": "Faithful ", "color": "gold" }, { "text": "x32", "extra": ["!"] }] } }"#,
+        )
+        .unwrap();
+        assert_eq!(Some(15), meta.pack_format);
+        assert_eq!(Some((15, 34)), meta.supported_formats);
+        assert_eq!(Some("Faithful x32!".to_string()), meta.description);
+
+        let meta = parse_pack_mcmeta(
+            br#"{ "pack": { "min_format": [69, 0], "max_format": 70, "description": { "translate": "pack.desc" } } }"#,
+        )
+        .unwrap();
+        assert_eq!(Some(70), meta.pack_format);
+        assert_eq!(Some((69, 70)), meta.supported_formats);
+        assert_eq!(Some("pack.desc".to_string()), meta.description);
+    }
+
+    #[test]
+    fn test_resource_packs() {
+        let root = std::env::temp_dir().join("mcl-resource-packs");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("resourcepacks/Folder Pack")).unwrap();
+        fs::create_dir_all(root.join("shaderpacks/Shader/shaders")).unwrap();
+        fs::write(
+            root.join("resourcepacks/Folder Pack/pack.mcmeta"),
+            r#"{ "pack": { "pack_format": 6, "description": "§6Old pack" } }"#,
+        )
+        .unwrap();
+        fs::write(root.join("resourcepacks/readme.txt"), b"readme").unwrap();
+        let mut writer =
+            ZipWriter::new(File::create(root.join("resourcepacks/Faithful.zip")).unwrap());
+        writer
+            .start_file("pack.mcmeta", SimpleFileOptions::default())
+            .unwrap();
+        writer
+            .write_all(br#"{ "pack": { "pack_format": 15, "description": "Faithful" } }"#)
+            .unwrap();
+        writer
+            .start_file("pack.png", SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"icon").unwrap();
+        writer.finish().unwrap();
+        fs::write(root.join("shaderpacks/BSL.zip"), b"").unwrap();
+        fs::write(root.join("shaderpacks/BSL.zip.txt"), b"options").unwrap();
+        fs::write(
+            root.join(OPTIONS_FILE),
+            "version:3465\nresourcePacks:[\"vanilla\",\"file/Faithful.zip\"]\nlang:zh_cn\n",
+        )
+        .unwrap();
+
+        let packs = list_packs(&root, PackKind::Resource, Some("1.20.1"));
+        assert_eq!(2, packs.len());
+        let faithful = &packs[0];
+        assert_eq!("Faithful.zip", faithful.file_name);
+        assert_eq!(Some(true), faithful.enabled);
+        assert_eq!(Some(15), faithful.pack_format);
+        assert_eq!(Some("Faithful".to_string()), faithful.description);
+        assert_eq!(Some("aWNvbg==".to_string()), faithful.icon);
+        assert_eq!(PackCompatibility::Compatible, faithful.compatibility);
+        let folder = &packs[1];
+        assert!(folder.is_directory);
+        assert_eq!(Some(false), folder.enabled);
+        assert_eq!(Some("§6Old pack".to_string()), folder.description);
+        assert_eq!(PackCompatibility::TooOld, folder.compatibility);
+
+        let shaders = list_packs(&root, PackKind::Shader, Some("1.20.1"));
+        assert_eq!(2, shaders.len());
+        assert!(shaders.iter().all(|pack| pack.enabled.is_none()));
+        assert!(shaders
+            .iter()
+            .all(|pack| pack.compatibility == PackCompatibility::Unknown));
+
+        // 启用不兼容的资源包时同时加入 incompatibleResourcePacks，其他设置保持不变
+        let minecraft_path = MinecraftPath::new(root.to_str().unwrap());
+        minecraft_path
+            .set_resource_pack_enabled("global", "Folder Pack", true, Some("1.20.1"))
+            .unwrap();
+        let options = fs::read_to_string(root.join(OPTIONS_FILE)).unwrap();
+        assert_eq!(
+            "version:3465\nresourcePacks:[\"vanilla\",\"file/Faithful.zip\",\"file/Folder Pack\"]\nlang:zh_cn\nincompatibleResourcePacks:[\"file/Folder Pack\"]\n",
+            options
+        );
+        minecraft_path
+            .set_resource_pack_enabled("global", "Folder Pack", false, Some("1.20.1"))
+            .unwrap();
+        minecraft_path
+            .set_resource_pack_enabled("global", "Faithful.zip", false, Some("1.20.1"))
+            .unwrap();
+        let options = fs::read_to_string(root.join(OPTIONS_FILE)).unwrap();
+        assert_eq!(
+            Some(vec!["vanilla".to_string()]),
+            read_pack_list(&options, RESOURCE_PACKS_KEY)
+        );
+        assert_eq!(
+            Some(Vec::new()),
+            read_pack_list(&options, INCOMPATIBLE_PACKS_KEY)
+        );
+
+        assert!(set_resource_pack_enabled(&root, "missing.zip", true, false).is_err());
+        assert!(set_resource_pack_enabled(&root, "../options.txt", true, false).is_err());
+        assert!(set_resource_pack_enabled(&root, "..", true, false).is_err());
+        assert!(set_resource_pack_enabled(&root, ".", true, false).is_err());
+        assert!(set_resource_pack_enabled(&root, "", true, false).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use std::fs;
+use std::path::Path;
+
+use nbt::Tag;
+use serde::{Deserialize, Serialize};
+
+use crate::minecraft_path::MinecraftPath;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GameMode {
+    Survival,
+    Creative,
+    Adventure,
+    Spectator,
+}
+
+impl GameMode {
+    fn from_id(id: i64) -> Option<Self> {
+        match id {
+            0 => Some(GameMode::Survival),
+            1 => Some(GameMode::Creative),
+            2 => Some(GameMode::Adventure),
+            3 => Some(GameMode::Spectator),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Peaceful,
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    fn from_id(id: i64) -> Option<Self> {
+        match id {
+            0 => Some(Difficulty::Peaceful),
+            1 => Some(Difficulty::Easy),
+            2 => Some(Difficulty::Normal),
+            3 => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+// level.dat 中的存档信息
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct WorldInfo {
+    // saves 下的目录名
+    pub folder_name: String,
+    pub path: String,
+    // 存档所在的根目录
+    pub root: String,
+    // 位于版本自己的游戏目录（隔离版本或实例）时为版本名
+    pub version: Option<String>,
+    pub level_name: String,
+    pub game_mode: Option<GameMode>,
+    pub hardcore: bool,
+    pub difficulty: Option<Difficulty>,
+    // 最后游玩时间，unix 时间戳（毫秒）
+    pub last_played: i64,
+    pub data_version: Option<i32>,
+    pub seed: Option<i64>,
+    // 最后保存存档的游戏版本，1.9 之前的存档没有
+    pub game_version: Option<String>,
+    // 存档图标 icon.png 的路径
+    pub icon: Option<String>,
+}
+
+// 读取存档目录下的 level.dat，损坏时尝试游戏保留的 level.dat_old
+pub fn read_world(world: &Path, root: &Path, version: Option<&str>) -> Option<WorldInfo> {
+    let (_, level) = nbt::read_file(&world.join("level.dat"))
+        .or_else(|_| nbt::read_file(&world.join("level.dat_old")))
+        .ok()?;
+    let data = level.get("Data")?;
+    let folder_name = world.file_name()?.to_string_lossy().to_string();
+    let icon = world.join("icon.png");
+
+    Some(WorldInfo {
+        path: world.to_string_lossy().to_string(),
+        root: root.to_string_lossy().to_string(),
+        version: version.map(str::to_string),
+        level_name: data
+            .get("LevelName")
+            .and_then(Tag::as_str)
+            .unwrap_or(&folder_name)
+            .to_string(),
+        folder_name,
+        game_mode: data
+            .get("GameType")
+            .and_then(Tag::as_i64)
+            .and_then(GameMode::from_id),
+        hardcore: data
+            .get("hardcore")
+            .and_then(Tag::as_bool)
+            .unwrap_or_default(),
+        difficulty: data
+            .get("Difficulty")
+            .and_then(Tag::as_i64)
+            .and_then(Difficulty::from_id),
+        last_played: data
+            .get("LastPlayed")
+            .and_then(Tag::as_i64)
+            .unwrap_or_default(),
+        data_version: data
+            .get("DataVersion")
+            .and_then(Tag::as_i64)
+            .map(|v| v as i32),
+        // 1.16 起种子位于 WorldGenSettings 中
+        seed: data
+            .get_path(&["WorldGenSettings", "seed"])
+            .or_else(|| data.get("RandomSeed"))
+            .and_then(Tag::as_i64),
+        game_version: data
+            .get_path(&["Version", "Name"])
+            .and_then(Tag::as_str)
+            .map(str::to_string),
+        icon: icon.is_file().then(|| icon.to_string_lossy().to_string()),
+    })
+}
+
+// 列出游戏目录下 saves 中的存档，按最后游玩时间从新到旧排序
+pub fn list_worlds(root: &Path, version: Option<&str>, game_directory: &Path) -> Vec<WorldInfo> {
+    let mut worlds: Vec<WorldInfo> = fs::read_dir(game_directory.join("saves"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| read_world(&entry.path(), root, version))
+                .collect()
+        })
+        .unwrap_or_default();
+    worlds.sort_by_key(|world| std::cmp::Reverse(world.last_played));
+    worlds
+}
+
+impl MinecraftPath {
+    // 根目录与所有版本游戏目录下的存档
+    pub fn get_all_worlds(&self) -> Vec<WorldInfo> {
+        let root = self.get_path();
+        let mut worlds: Vec<WorldInfo> = self
+            .get_game_directories()
+            .into_iter()
+            .flat_map(|(version, game_directory)| {
+                list_worlds(root, version.as_deref(), &game_directory)
+            })
+            .collect();
+        worlds.sort_by_key(|world| std::cmp::Reverse(world.last_played));
+        worlds
+    }
+
+    // 版本游戏目录下的存档，未隔离的版本为根目录下的存档
+    pub fn get_version_worlds(&self, version_name: &str) -> Vec<WorldInfo> {
+        let root = self.get_path();
+        let game_directory = self.get_game_directory(version_name);
+        let version = (game_directory != root).then_some(version_name);
+        list_worlds(root, version, &game_directory)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worlds() {
+        let root = std::env::temp_dir().join("mcl-worlds");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("saves/New World")).unwrap();
+        fs::create_dir_all(root.join("saves/Skyblock")).unwrap();
+        fs::create_dir_all(root.join("saves/broken")).unwrap();
+        fs::create_dir_all(root.join("versions/1.12.2/saves/Skyblock")).unwrap();
+        fs::write(
+            root.join("saves/New World/level.dat"),
+            include_bytes!("../../nbt/samples/level.dat"),
+        )
+        .unwrap();
+        fs::write(root.join("saves/New World/icon.png"), b"").unwrap();
+        // level.dat 损坏时读取 level.dat_old
+        fs::write(root.join("saves/Skyblock/level.dat"), b"broken").unwrap();
+        fs::write(
+            root.join("saves/Skyblock/level.dat_old"),
+            include_bytes!("../../nbt/samples/level_1.12.dat"),
+        )
+        .unwrap();
+        fs::write(root.join("saves/broken/level.dat"), b"broken").unwrap();
+        fs::write(
+            root.join("versions/1.12.2/saves/Skyblock/level.dat"),
+            include_bytes!("../../nbt/samples/level_1.12.dat"),
+        )
+        .unwrap();
+
+        let minecraft_path = MinecraftPath::new(root.to_str().unwrap());
+        let worlds = minecraft_path.get_version_worlds("global");
+        assert_eq!(2, worlds.len());
+
+        let modern = &worlds[0];
+        assert_eq!("New World", modern.level_name);
+        assert_eq!(Some(GameMode::Creative), modern.game_mode);
+        assert!(!modern.hardcore);
+        assert_eq!(Some(Difficulty::Normal), modern.difficulty);
+        assert_eq!(1717243200000, modern.last_played);
+        assert_eq!(Some(3953), modern.data_version);
+        assert_eq!(Some(-4172144997902289642), modern.seed);
+        assert_eq!(Some("1.21".to_string()), modern.game_version);
+        assert!(modern.icon.is_some());
+
+        let legacy = &worlds[1];
+        assert_eq!("Skyblock", legacy.level_name);
+        assert_eq!(Some(GameMode::Survival), legacy.game_mode);
+        assert!(legacy.hardcore);
+        assert_eq!(Some(Difficulty::Hard), legacy.difficulty);
+        assert_eq!(Some(123456789), legacy.seed);
+        assert_eq!(None, legacy.icon);
+
+        let all = minecraft_path.get_all_worlds();
+        assert_eq!(3, all.len());
+        assert_eq!(
+            Some("1.12.2".to_string()),
+            all.iter().find(|w| w.version.is_some()).unwrap().version
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, NaiveDateTime, TimeZone};
+use image::ImageFormat;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+use crate::isolation::{game_directories, version_game_directory};
+use crate::minecraft_path::{is_screenshot, MinecraftPath};
+use crate::watcher::RootChange;
+
+// 缩略图的最大边长
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+// 截图路径对应的修改时间与宽高
+type DimensionCache = HashMap<PathBuf, (SystemTime, (u32, u32))>;
+
+// 截图的宽高，按路径缓存，修改时间变化后重新读取图片头
+static DIMENSIONS: Lazy<Mutex<DimensionCache>> = Lazy::new(Default::default);
+
+// 缓存的截图数量上限，超过时先移除已不存在的截图，仍然超过则清空
+const MAX_CACHED_DIMENSIONS: usize = 4096;
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ScreenshotInfo {
+    pub path: String,
+    pub file_name: String,
+    // 截图所在的根目录
+    pub root: String,
+    // 位于版本自己的游戏目录（隔离版本或实例）时为版本名
+    pub version: Option<String>,
+    // 文件大小，单位字节
+    pub size: u64,
+    // 无法读取图片头时为 0
+    pub width: u32,
+    pub height: u32,
+    // 截图时间，unix 时间戳（秒），优先从文件名解析，否则使用修改时间
+    pub taken_at: i64,
+}
+
+#[derive(Clone, Copy, Default, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotSort {
+    #[default]
+    Time,
+    Name,
+    Size,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct ScreenshotQuery {
+    pub sort: ScreenshotSort,
+    pub descending: bool,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Default for ScreenshotQuery {
+    // 默认最新的在前，每页 50 张
+    fn default() -> Self {
+        Self {
+            sort: ScreenshotSort::Time,
+            descending: true,
+            offset: 0,
+            limit: 50,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ScreenshotPage {
+    // 排序分页前的总数
+    pub total: usize,
+    pub items: Vec<ScreenshotInfo>,
+}
+
+// 从 Minecraft 的截图文件名解析截图时间，文件名为本地时间，
+// 格式为 2024-05-01_12.34.56.png，同一秒内的截图带有 _1、_2 后缀
+pub fn parse_screenshot_time(file_name: &str) -> Option<i64> {
+    let time = NaiveDateTime::parse_from_str(file_name.get(..19)?, "%Y-%m-%d_%H.%M.%S").ok()?;
+    Local
+        .from_local_datetime(&time)
+        .earliest()
+        .map(|time| time.timestamp())
+}
+
+fn screenshot_dimensions(path: &Path, modified: Option<SystemTime>) -> (u32, u32) {
+    let Some(modified) = modified else {
+        return image::image_dimensions(path).unwrap_or_default();
+    };
+    let cached = DIMENSIONS
+        .lock()
+        .ok()
+        .and_then(|cache| cache.get(path).copied());
+    if let Some((time, dimensions)) = cached {
+        if time == modified {
+            return dimensions;
+        }
+    }
+    let dimensions = image::image_dimensions(path).unwrap_or_default();
+    if let Ok(mut cache) = DIMENSIONS.lock() {
+        if cache.len() >= MAX_CACHED_DIMENSIONS {
+            cache.retain(|path, _| path.is_file());
+            if cache.len() >= MAX_CACHED_DIMENSIONS {
+                cache.clear();
+            }
+        }
+        cache.insert(path.to_path_buf(), (modified, dimensions));
+    }
+    dimensions
+}
+
+// 截图被删除或移动后移除它缓存的宽高
+pub(crate) fn forget_dimensions(path: &Path) {
+    if let Ok(mut cache) = DIMENSIONS.lock() {
+        cache.remove(path);
+    }
+}
+
+// 读取截图信息，只读取图片头获取尺寸
+pub fn read_screenshot(path: &Path, root: &Path, version: Option<&str>) -> Option<ScreenshotInfo> {
+    let metadata = fs::metadata(path).ok()?;
+    if !metadata.is_file() || !is_screenshot(path) {
+        return None;
+    }
+    let file_name = path.file_name()?.to_string_lossy().to_string();
+    let (width, height) = screenshot_dimensions(path, metadata.modified().ok());
+    let taken_at = parse_screenshot_time(&file_name).unwrap_or_else(|| {
+        metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default()
+    });
+    Some(ScreenshotInfo {
+        path: path.to_string_lossy().to_string(),
+        file_name,
+        root: root.to_string_lossy().to_string(),
+        version: version.map(str::to_string),
+        size: metadata.len(),
+        width,
+        height,
+        taken_at,
+    })
+}
+
+// 列出游戏目录下 screenshots 中的截图
+pub fn list_screenshots(
+    root: &Path,
+    version: Option<&str>,
+    game_directory: &Path,
+) -> Vec<ScreenshotInfo> {
+    fs::read_dir(game_directory.join("screenshots"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter_map(|entry| read_screenshot(&entry.path(), root, version))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// 根目录下的截图，version_name 为空时包括根目录与所有版本的游戏目录，
+// 只需要根目录的路径，可以在释放 MinecraftPath 的锁之后调用
+pub fn root_screenshots(root: &Path, version_name: Option<&str>) -> Vec<ScreenshotInfo> {
+    match version_name {
+        Some(version_name) => {
+            let game_directory = version_game_directory(root, version_name);
+            let version = (game_directory != root).then_some(version_name);
+            list_screenshots(root, version, &game_directory)
+        }
+        None => game_directories(root)
+            .into_iter()
+            .flat_map(|(version, game_directory)| {
+                list_screenshots(root, version.as_deref(), &game_directory)
+            })
+            .collect(),
+    }
+}
+
+// 截图必须位于某个根目录或版本游戏目录的 screenshots 目录中，解析 .. 与符号链接后再比较
+pub fn screenshot_in_roots(roots: &[PathBuf], path: &Path) -> Result<PathBuf, &'static str> {
+    let path = path.canonicalize().map_err(|_| "截图文件不存在")?;
+    if !path.is_file() || !is_screenshot(&path) {
+        return Err("不是截图文件");
+    }
+    let directory = path.parent().ok_or("无效的截图路径")?;
+    let found = roots
+        .iter()
+        .flat_map(|root| game_directories(root))
+        .filter_map(|(_, game_directory)| game_directory.join("screenshots").canonicalize().ok())
+        .any(|screenshots| screenshots == directory);
+    if found {
+        Ok(path)
+    } else {
+        Err("截图不属于已添加的游戏目录")
+    }
+}
+
+// 排序后取出一页
+pub fn query_screenshots(
+    mut screenshots: Vec<ScreenshotInfo>,
+    query: &ScreenshotQuery,
+) -> ScreenshotPage {
+    screenshots.sort_by(|a, b| {
+        let ordering = match query.sort {
+            ScreenshotSort::Time => a.taken_at.cmp(&b.taken_at),
+            ScreenshotSort::Name => a.file_name.cmp(&b.file_name),
+            ScreenshotSort::Size => a.size.cmp(&b.size),
+        };
+        // 相同时按路径排序，保证分页稳定
+        let ordering = ordering.then_with(|| a.path.cmp(&b.path));
+        if query.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    ScreenshotPage {
+        total: screenshots.len(),
+        items: screenshots
+            .into_iter()
+            .skip(query.offset)
+            .take(query.limit)
+            .collect(),
+    }
+}
+
+// 生成缩小的 jpg 缩略图并缓存在 cache_dir 下，返回缩略图路径，
+// 文件名由原图路径、大小与修改时间生成，原图变化后会重新生成
+pub fn screenshot_thumbnail(path: &Path, cache_dir: &Path) -> Result<PathBuf, &'static str> {
+    let metadata = fs::metadata(path).map_err(|_| "截图文件不存在")?;
+    if !is_screenshot(path) {
+        return Err("不是截图文件");
+    }
+    // DefaultHasher 的结果在不同 Rust 版本间可能变化，最多导致重新生成缩略图
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    metadata.len().hash(&mut hasher);
+    metadata.modified().ok().hash(&mut hasher);
+    let target = cache_dir.join(format!("{:016x}.jpg", hasher.finish()));
+    if target.is_file() {
+        return Ok(target);
+    }
+
+    let image = image::open(path).map_err(|_| "读取截图失败")?;
+    fs::create_dir_all(cache_dir).map_err(|_| "创建缩略图目录失败")?;
+    // jpg 不支持透明通道
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgb8()
+        .save_with_format(&target, ImageFormat::Jpeg)
+        .map_err(|_| "写入缩略图失败")?;
+    Ok(target)
+}
+
+// 解码截图为 RGBA，返回像素与宽高，用于复制到剪贴板
+pub fn read_screenshot_rgba(path: &Path) -> Result<(Vec<u8>, u32, u32), &'static str> {
+    if !is_screenshot(path) {
+        return Err("不是截图文件");
+    }
+    let image = image::open(path).map_err(|_| "读取截图失败")?.to_rgba8();
+    let (width, height) = image.dimensions();
+    Ok((image.into_raw(), width, height))
+}
+
+// 复制截图到 target_dir，同名文件已存在时在文件名后加序号，返回复制后的路径
+pub fn export_screenshots(
+    paths: &[PathBuf],
+    target_dir: &Path,
+) -> Result<Vec<PathBuf>, &'static str> {
+    fs::create_dir_all(target_dir).map_err(|_| "创建导出目录失败")?;
+    let mut exported = Vec::new();
+    for path in paths {
+        if !path.is_file() || !is_screenshot(path) {
+            return Err("截图文件不存在");
+        }
+        let stem = path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let extension = path
+            .extension()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut target = target_dir.join(format!("{}.{}", stem, extension));
+        let mut index = 1;
+        while target.exists() {
+            target = target_dir.join(format!("{} ({}).{}", stem, index, extension));
+            index += 1;
+        }
+        fs::copy(path, &target).map_err(|_| "复制截图失败")?;
+        exported.push(target);
+    }
+    Ok(exported)
+}
+
+impl MinecraftPath {
+    // 根目录与各版本游戏目录下的截图目录，版本名为空表示根目录
+    pub fn get_screenshot_directories(&self) -> Vec<(Option<String>, PathBuf)> {
+        self.get_game_directories()
+            .into_iter()
+            .map(|(version, game_directory)| (version, game_directory.join("screenshots")))
+            .collect()
+    }
+
+    // 根目录与所有版本游戏目录下的截图
+    pub fn get_all_screenshots(&self) -> Vec<ScreenshotInfo> {
+        root_screenshots(self.get_path(), None)
+    }
+
+    // 版本游戏目录下的截图，未隔离的版本为根目录下的截图
+    pub fn get_version_screenshot_infos(&self, version_name: &str) -> Vec<ScreenshotInfo> {
+        root_screenshots(self.get_path(), Some(version_name))
+    }
+
+    // 删除截图，移动到根目录下的 .trash 目录，只允许删除截图目录中的文件，返回回收站中的路径
+    pub fn delete_screenshot(&mut self, path: &Path) -> Result<PathBuf, &'static str> {
+        let directory = path.parent().ok_or("无效的截图路径")?;
+        let version = self
+            .get_screenshot_directories()
+            .into_iter()
+            .find(|(_, screenshots)| screenshots == directory)
+            .ok_or("截图不属于该游戏目录")?
+            .0;
+        if !path.is_file() || !is_screenshot(path) {
+            return Err("截图文件不存在");
+        }
+
+        let file_name = path
+            .file_name()
+            .ok_or("无效的截图路径")?
+            .to_string_lossy()
+            .to_string();
+        let trash = self.get_path().join(".trash").join("screenshots");
+        fs::create_dir_all(&trash).map_err(|_| "创建回收站目录失败")?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default();
+        let target = trash.join(format!("{}-{}", timestamp, file_name));
+        if fs::rename(path, &target).is_err() {
+            // 跨设备时无法重命名，复制后再删除
+            fs::copy(path, &target).map_err(|_| "移动截图到回收站失败")?;
+            fs::remove_file(path).map_err(|_| "删除截图失败")?;
+        }
+
+        self.apply_change(&RootChange::Screenshot {
+            version,
+            path: path.to_path_buf(),
+        });
+        Ok(target)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{ImageBuffer, Rgba};
+
+    fn write_png(path: &Path, width: u32, height: u32) {
+        ImageBuffer::from_pixel(width, height, Rgba([255u8, 0, 0, 255]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_parse_screenshot_time() {
+        let a = parse_screenshot_time("2024-05-01_12.34.56.png").unwrap();
+        let b = parse_screenshot_time("2024-05-01_13.34.56_1.png").unwrap();
+        assert_eq!(3600, b - a);
+        assert_eq!(None, parse_screenshot_time("screenshot.png"));
+        assert_eq!(None, parse_screenshot_time("2024-13-01_12.34.56.png"));
+    }
+
+    #[test]
+    fn test_screenshots() {
+        let root = std::env::temp_dir().join("mcl-screenshots");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("screenshots")).unwrap();
+        fs::create_dir_all(root.join("versions/1.21/screenshots")).unwrap();
+        write_png(&root.join("screenshots/2024-05-01_12.34.56.png"), 640, 360);
+        write_png(&root.join("screenshots/custom.png"), 16, 16);
+        write_png(
+            &root.join("versions/1.21/screenshots/2023-01-01_00.00.00.png"),
+            32,
+            18,
+        );
+        fs::write(root.join("screenshots/notes.txt"), b"").unwrap();
+
+        let mut minecraft_path = MinecraftPath::new(root.to_str().unwrap());
+        let screenshots = minecraft_path.get_all_screenshots();
+        assert_eq!(3, screenshots.len());
+        let instance = screenshots.iter().find(|s| s.version.is_some()).unwrap();
+        assert_eq!((32, 18), (instance.width, instance.height));
+
+        let page = query_screenshots(
+            screenshots.clone(),
+            &ScreenshotQuery {
+                sort: ScreenshotSort::Time,
+                descending: false,
+                offset: 0,
+                limit: 2,
+            },
+        );
+        assert_eq!(3, page.total);
+        assert_eq!("2023-01-01_00.00.00.png", page.items[0].file_name);
+        assert_eq!("2024-05-01_12.34.56.png", page.items[1].file_name);
+        let page = query_screenshots(
+            screenshots,
+            &ScreenshotQuery {
+                sort: ScreenshotSort::Size,
+                offset: 2,
+                ..Default::default()
+            },
+        );
+        assert_eq!("custom.png", page.items[0].file_name);
+
+        // 缩略图缩小到 THUMBNAIL_SIZE 以内并被缓存
+        let source = root.join("screenshots/2024-05-01_12.34.56.png");
+        let cache = root.join("cache");
+        let thumbnail = screenshot_thumbnail(&source, &cache).unwrap();
+        assert_eq!(
+            (THUMBNAIL_SIZE, 180),
+            image::image_dimensions(&thumbnail).unwrap()
+        );
+        assert_eq!(thumbnail, screenshot_thumbnail(&source, &cache).unwrap());
+
+        let exported =
+            export_screenshots(&[source.clone(), source.clone()], &root.join("export")).unwrap();
+        assert!(exported[1].ends_with("2024-05-01_12.34.56 (1).png"));
+
+        // 只接受已添加根目录的截图目录中的截图
+        let roots = [root.clone()];
+        assert_eq!(
+            source.canonicalize().unwrap(),
+            screenshot_in_roots(&roots, &source).unwrap()
+        );
+        assert!(screenshot_in_roots(
+            &roots,
+            &root.join("versions/1.21/screenshots/2023-01-01_00.00.00.png")
+        )
+        .is_ok());
+        assert!(screenshot_in_roots(&roots, &exported[0]).is_err());
+        assert!(screenshot_in_roots(&roots, &root.join("screenshots/notes.txt")).is_err());
+        assert!(screenshot_in_roots(
+            &roots,
+            &root.join("screenshots/../export/2024-05-01_12.34.56.png")
+        )
+        .is_err());
+        assert!(screenshot_in_roots(&[root.join("versions")], &source).is_err());
+
+        assert!(minecraft_path
+            .delete_screenshot(&root.join("screenshots/notes.txt"))
+            .is_err());
+        assert!(minecraft_path.delete_screenshot(&exported[0]).is_err());
+        let trashed = minecraft_path.delete_screenshot(&source).unwrap();
+        assert!(trashed.starts_with(root.join(".trash/screenshots")));
+        assert_eq!(1, minecraft_path.get_sceenshots().unwrap().1.len());
+    }
+
+    #[test]
+    fn test_dimensions_cache() {
+        let root = std::env::temp_dir().join("mcl-screenshot-dimensions");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        let path = root.join("cached.png");
+        write_png(&path, 64, 32);
+        let modified = fs::metadata(&path).unwrap().modified().unwrap();
+        let info = read_screenshot(&path, &root, None).unwrap();
+        assert_eq!((64, 32), (info.width, info.height));
+
+        // 修改时间不变时使用缓存的尺寸，不再读取图片头
+        write_png(&path, 16, 16);
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(modified).unwrap();
+        let info = read_screenshot(&path, &root, None).unwrap();
+        assert_eq!((64, 32), (info.width, info.height));
+
+        file.set_modified(modified + std::time::Duration::from_secs(1))
+            .unwrap();
+        let info = read_screenshot(&path, &root, None).unwrap();
+        assert_eq!((16, 16), (info.width, info.height));
+
+        // 删除后移除缓存
+        drop(file);
+        fs::remove_file(&path).unwrap();
+        forget_dimensions(&path);
+        assert!(!DIMENSIONS.lock().unwrap().contains_key(&path));
+    }
+}
@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::Path;
+
+use nbt::{Compound, Tag};
+use serde::{Deserialize, Serialize};
+
+// 多人游戏的服务器列表，未压缩的 NBT
+const SERVERS_FILE: &str = "servers.dat";
+// 写入前保留的上一份服务器列表，与游戏的做法一致
+const SERVERS_BACKUP_FILE: &str = "servers.dat_old";
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct ServerInfo {
+    pub name: String,
+    // 服务器地址，可以带端口，例如 mc.example.com:25566
+    pub ip: String,
+    // base64 编码的 PNG，由游戏在刷新服务器列表时写入
+    #[serde(default)]
+    pub icon: Option<String>,
+    // 是否使用服务器资源包，为 None 时进入服务器时询问
+    #[serde(default)]
+    pub accept_textures: Option<bool>,
+    // 游戏写入的隐藏服务器，不在游戏的服务器列表中显示
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+fn read_server(tag: &Tag) -> ServerInfo {
+    let string = |key: &str| tag.get(key).and_then(Tag::as_str).map(str::to_string);
+    ServerInfo {
+        name: string("name").unwrap_or_default(),
+        ip: string("ip").unwrap_or_default(),
+        icon: string("icon"),
+        accept_textures: tag.get("acceptTextures").and_then(Tag::as_bool),
+        hidden: tag.get("hidden").and_then(Tag::as_bool).unwrap_or(false),
+    }
+}
+
+// 只修改这几个字段，保留游戏写入的其他字段
+fn write_server(compound: &mut Compound, server: &ServerInfo) {
+    compound.insert("name".to_string(), Tag::String(server.name.clone()));
+    compound.insert("ip".to_string(), Tag::String(server.ip.trim().to_string()));
+    match &server.icon {
+        Some(icon) => compound.insert("icon".to_string(), Tag::String(icon.clone())),
+        None => compound.remove("icon"),
+    };
+    match server.accept_textures {
+        Some(accept) => compound.insert("acceptTextures".to_string(), Tag::Byte(accept as i8)),
+        None => compound.remove("acceptTextures"),
+    };
+    compound.insert("hidden".to_string(), Tag::Byte(server.hidden as i8));
+}
+
+// 读取 servers.dat，文件不存在时为空的服务器列表
+fn load_servers(game_directory: &Path) -> Result<(String, Tag), &'static str> {
+    let path = game_directory.join(SERVERS_FILE);
+    if !path.exists() {
+        return Ok((String::new(), Tag::Compound(Compound::new())));
+    }
+    let (name, root) = nbt::read_file(&path).map_err(|_| "读取 servers.dat 失败")?;
+    if root
+        .get("servers")
+        .is_some_and(|servers| servers.as_list().is_none())
+    {
+        return Err("servers.dat 格式错误");
+    }
+    Ok((name, root))
+}
+
+// 修改服务器列表并写回 servers.dat
+fn edit_servers<T>(
+    game_directory: &Path,
+    f: impl FnOnce(&mut Vec<Tag>) -> Result<T, &'static str>,
+) -> Result<T, &'static str> {
+    let (name, mut root) = load_servers(game_directory)?;
+    let compound = root.as_compound_mut().ok_or("servers.dat 格式错误")?;
+    let servers = compound
+        .entry("servers".to_string())
+        .or_insert_with(|| Tag::List(Vec::new()))
+        .as_list_mut()
+        .ok_or("servers.dat 格式错误")?;
+    let result = f(servers)?;
+
+    let path = game_directory.join(SERVERS_FILE);
+    if path.exists() {
+        let _ = fs::copy(&path, game_directory.join(SERVERS_BACKUP_FILE));
+    }
+    fs::create_dir_all(game_directory).map_err(|_| "创建游戏目录失败")?;
+    nbt::write_file(&path, &name, &root).map_err(|_| "写入 servers.dat 失败")?;
+    Ok(result)
+}
+
+fn check_server(server: &ServerInfo) -> Result<(), &'static str> {
+    if server.ip.trim().is_empty() {
+        return Err("服务器地址不能为空");
+    }
+    Ok(())
+}
+
+// 游戏目录下的服务器列表，按文件中的顺序返回，包含隐藏的服务器，
+// 位置与 update_server、remove_server、move_server 使用的索引一致
+pub fn list_servers(game_directory: &Path) -> Result<Vec<ServerInfo>, &'static str> {
+    let (_, root) = load_servers(game_directory)?;
+    Ok(root
+        .get("servers")
+        .and_then(Tag::as_list)
+        .map(|servers| servers.iter().map(read_server).collect())
+        .unwrap_or_default())
+}
+
+// 添加服务器到列表末尾，返回它的位置
+pub fn add_server(game_directory: &Path, server: &ServerInfo) -> Result<usize, &'static str> {
+    check_server(server)?;
+    edit_servers(game_directory, |servers| {
+        let mut compound = Compound::new();
+        write_server(&mut compound, server);
+        servers.push(Tag::Compound(compound));
+        Ok(servers.len() - 1)
+    })
+}
+
+pub fn update_server(
+    game_directory: &Path,
+    index: usize,
+    server: &ServerInfo,
+) -> Result<(), &'static str> {
+    check_server(server)?;
+    edit_servers(game_directory, |servers| {
+        let compound = servers
+            .get_mut(index)
+            .and_then(Tag::as_compound_mut)
+            .ok_or("未找到服务器")?;
+        write_server(compound, server);
+        Ok(())
+    })
+}
+
+pub fn remove_server(game_directory: &Path, index: usize) -> Result<ServerInfo, &'static str> {
+    edit_servers(game_directory, |servers| {
+        if index >= servers.len() {
+            return Err("未找到服务器");
+        }
+        Ok(read_server(&servers.remove(index)))
+    })
+}
+
+// 把服务器从 from 移动到 to，其他服务器依次前移或后移
+pub fn move_server(game_directory: &Path, from: usize, to: usize) -> Result<(), &'static str> {
+    edit_servers(game_directory, |servers| {
+        if from >= servers.len() || to >= servers.len() {
+            return Err("未找到服务器");
+        }
+        let server = servers.remove(from);
+        servers.insert(to, server);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(name: &str, ip: &str) -> ServerInfo {
+        ServerInfo {
+            name: name.to_string(),
+            ip: ip.to_string(),
+            icon: None,
+            accept_textures: None,
+            hidden: false,
+        }
+    }
+
+    #[test]
+    fn test_servers() {
+        let root = std::env::temp_dir().join("mcl-servers");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+        assert_eq!(Vec::<ServerInfo>::new(), list_servers(&root).unwrap());
+
+        // 游戏写入的 servers.dat，包含隐藏的服务器
+        let mut hidden = Compound::new();
+        hidden.insert("name".to_string(), Tag::String("Hypixel".to_string()));
+        hidden.insert("ip".to_string(), Tag::String("mc.hypixel.net".to_string()));
+        hidden.insert("icon".to_string(), Tag::String("iVBORw0KGgo=".to_string()));
+        hidden.insert("acceptTextures".to_string(), Tag::Byte(1));
+        hidden.insert("hidden".to_string(), Tag::Byte(1));
+        hidden.insert("preventsChatReports".to_string(), Tag::Byte(1));
+        let mut compound = Compound::new();
+        compound.insert(
+            "servers".to_string(),
+            Tag::List(vec![Tag::Compound(hidden)]),
+        );
+        nbt::write_file(&root.join(SERVERS_FILE), "", &Tag::Compound(compound)).unwrap();
+
+        let servers = list_servers(&root).unwrap();
+        assert_eq!("Hypixel", servers[0].name);
+        assert_eq!(Some("iVBORw0KGgo=".to_string()), servers[0].icon);
+        assert_eq!(Some(true), servers[0].accept_textures);
+        assert!(servers[0].hidden);
+
+        assert_eq!(
+            1,
+            add_server(&root, &server("Local", " localhost:25566 ")).unwrap()
+        );
+        assert_eq!(
+            2,
+            add_server(&root, &server("Test", "test.example.com")).unwrap()
+        );
+        assert!(add_server(&root, &server("Empty", " ")).is_err());
+        move_server(&root, 2, 0).unwrap();
+        let mut edited = servers[0].clone();
+        edited.name = "Hypixel Network".to_string();
+        edited.accept_textures = Some(false);
+        update_server(&root, 1, &edited).unwrap();
+        assert_eq!("Local", remove_server(&root, 2).unwrap().name);
+        assert!(remove_server(&root, 2).is_err());
+        assert!(move_server(&root, 0, 2).is_err());
+
+        let servers = list_servers(&root).unwrap();
+        assert_eq!(
+            vec!["Test", "Hypixel Network"],
+            servers.iter().map(|s| s.name.as_str()).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(false), servers[1].accept_textures);
+        assert!(servers[1].hidden);
+        assert!(!servers[0].hidden);
+        // 未知的字段被保留，写入前保留上一份文件
+        let (_, root_tag) = nbt::read_file(&root.join(SERVERS_FILE)).unwrap();
+        let hidden = &root_tag.get("servers").and_then(Tag::as_list).unwrap()[1];
+        assert_eq!(
+            Some(true),
+            hidden.get("preventsChatReports").and_then(Tag::as_bool)
+        );
+        assert!(root.join(SERVERS_BACKUP_FILE).is_file());
+        assert!(!root.join("servers.dat_tmp").exists());
+
+        fs::write(root.join(SERVERS_FILE), b"broken").unwrap();
+        assert!(list_servers(&root).is_err());
+        assert!(add_server(&root, &server("Local", "localhost")).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::Duration;
+
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+
+use crate::isolation::ISOLATION_FILE;
+use crate::minecraft_path::{is_mod_file, is_screenshot};
+
+// 根目录下监听到的变化
+#[derive(Clone, Debug, PartialEq)]
+pub enum RootChange {
+    // version 为空时是根目录下的 mods
+    Mods {
+        version: Option<String>,
+    },
+    // 新增或删除版本、版本配置或隔离设置变化
+    Versions,
+    // 新增或删除截图，version 为空时是根目录下的 screenshots
+    Screenshot {
+        version: Option<String>,
+        path: PathBuf,
+    },
+}
+
+// 把变化的文件路径转换为对应的变化，与缓存无关的文件返回 None
+pub fn classify_change(root: &Path, path: &Path) -> Option<RootChange> {
+    let relative = path.strip_prefix(root).ok().or_else(|| {
+        // macOS 上事件中的路径是规范化后的路径
+        let root = root.canonicalize().ok()?;
+        path.strip_prefix(root).ok()
+    })?;
+    let components: Vec<String> = relative
+        .components()
+        .map(|c| match c {
+            Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect::<Option<_>>()?;
+    let components: Vec<&str> = components.iter().map(String::as_str).collect();
+
+    match components.as_slice() {
+        [name] if *name == ISOLATION_FILE => Some(RootChange::Versions),
+        ["mods"] => Some(RootChange::Mods { version: None }),
+        ["mods", file] if is_mod_file(file) => Some(RootChange::Mods { version: None }),
+        ["screenshots", _] if is_screenshot(path) => Some(RootChange::Screenshot {
+            version: None,
+            path: path.to_path_buf(),
+        }),
+        ["versions"] | ["versions", _] => Some(RootChange::Versions),
+        ["versions", _, file] if file.ends_with(".json") => Some(RootChange::Versions),
+        ["versions", id, "mods"] => Some(RootChange::Mods {
+            version: Some(id.to_string()),
+        }),
+        ["versions", id, "mods", file] if is_mod_file(file) => Some(RootChange::Mods {
+            version: Some(id.to_string()),
+        }),
+        ["versions", id, "screenshots", _] if is_screenshot(path) => Some(RootChange::Screenshot {
+            version: Some(id.to_string()),
+            path: path.to_path_buf(),
+        }),
+        _ => None,
+    }
+}
+
+// 需要监听的目录，只监听缓存相关的目录而不是递归监听整个根目录，
+// 避免 libraries、assets 等目录占用大量 inotify watch
+fn watch_targets(root: &Path) -> HashSet<PathBuf> {
+    let mut directories = vec![
+        root.to_path_buf(),
+        root.join("mods"),
+        root.join("screenshots"),
+        root.join("versions"),
+    ];
+    if let Ok(entries) = fs::read_dir(root.join("versions")) {
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            let path = entry.path();
+            directories.push(path.join("mods"));
+            directories.push(path.join("screenshots"));
+            directories.push(path);
+        }
+    }
+    directories
+        .into_iter()
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+struct WatchState {
+    root: PathBuf,
+    debouncer: Option<Debouncer<RecommendedWatcher>>,
+    watched: HashSet<PathBuf>,
+}
+
+impl WatchState {
+    // 目录新增或删除后同步需要监听的目录
+    fn sync(&mut self) {
+        let Some(debouncer) = &mut self.debouncer else {
+            return;
+        };
+        let targets = watch_targets(&self.root);
+        for path in self.watched.difference(&targets) {
+            let _ = debouncer.watcher().unwatch(path);
+        }
+        self.watched.retain(|path| targets.contains(path));
+        for path in targets {
+            if !self.watched.contains(&path)
+                && debouncer
+                    .watcher()
+                    .watch(&path, RecursiveMode::NonRecursive)
+                    .is_ok()
+            {
+                self.watched.insert(path);
+            }
+        }
+    }
+}
+
+// 监听根目录的文件变化，同一批变化在 delay 内合并后交给 handler，drop 时停止监听
+pub struct RootWatcher {
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl RootWatcher {
+    pub fn new(
+        root: &Path,
+        delay: Duration,
+        mut handler: impl FnMut(Vec<RootChange>) + Send + 'static,
+    ) -> Result<Self, &'static str> {
+        if !root.is_dir() {
+            return Err("游戏目录不存在");
+        }
+        let state = Arc::new(Mutex::new(WatchState {
+            root: root.to_path_buf(),
+            debouncer: None,
+            watched: HashSet::new(),
+        }));
+
+        // 回调中只持有弱引用，避免 debouncer 与状态互相引用无法释放
+        let weak: Weak<Mutex<WatchState>> = Arc::downgrade(&state);
+        let root = root.to_path_buf();
+        let debouncer = new_debouncer(delay, move |result: DebounceEventResult| {
+            let Ok(events) = result else {
+                return;
+            };
+            let mut changes: Vec<RootChange> = Vec::new();
+            for event in events {
+                if let Some(change) = classify_change(&root, &event.path) {
+                    if !changes.contains(&change) {
+                        changes.push(change);
+                    }
+                }
+            }
+            if let Some(state) = weak.upgrade() {
+                if let Ok(mut state) = state.lock() {
+                    state.sync();
+                }
+            }
+            if !changes.is_empty() {
+                handler(changes);
+            }
+        })
+        .map_err(|_| "创建文件监听失败")?;
+
+        {
+            let mut guard = state.lock().map_err(|_| "创建文件监听失败")?;
+            guard.debouncer = Some(debouncer);
+            guard.sync();
+        }
+        Ok(Self { state })
+    }
+
+    // 当前监听的目录数
+    pub fn watched_count(&self) -> usize {
+        self.state
+            .lock()
+            .map(|state| state.watched.len())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    #[test]
+    fn test_classify_change() {
+        let root = Path::new("/games/.minecraft");
+        let classify = |path: &str| classify_change(root, &root.join(path));
+
+        assert_eq!(
+            Some(RootChange::Mods { version: None }),
+            classify("mods/a.jar")
+        );
+        assert_eq!(
+            Some(RootChange::Mods { version: None }),
+            classify("mods/a.jar.disabled")
+        );
+        assert_eq!(None, classify("mods/a.jar.part"));
+        assert_eq!(
+            Some(RootChange::Mods {
+                version: Some("1.21".to_string())
+            }),
+            classify("versions/1.21/mods/b.jar")
+        );
+        assert_eq!(Some(RootChange::Versions), classify("versions/1.21"));
+        assert_eq!(
+            Some(RootChange::Versions),
+            classify("versions/1.21/1.21.json")
+        );
+        assert_eq!(Some(RootChange::Versions), classify(ISOLATION_FILE));
+        assert_eq!(
+            Some(RootChange::Screenshot {
+                version: Some("1.21".to_string()),
+                path: root.join("versions/1.21/screenshots/a.png"),
+            }),
+            classify("versions/1.21/screenshots/a.png")
+        );
+        assert_eq!(None, classify("screenshots/a.txt"));
+        assert_eq!(None, classify("versions/1.21/saves/world/level.dat"));
+        assert_eq!(None, classify("libraries/a.jar"));
+        assert_eq!(None, classify_change(root, Path::new("/other/mods/a.jar")));
+    }
+
+    #[test]
+    fn test_root_watcher() {
+        let root = std::env::temp_dir().join("mcl-root-watcher");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("versions/1.21")).unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let watcher = RootWatcher::new(&root, Duration::from_millis(100), move |changes| {
+            let _ = sender.send(changes);
+        })
+        .unwrap();
+        assert_eq!(3, watcher.watched_count());
+
+        // 新建的目录会被加入监听
+        fs::create_dir_all(root.join("versions/1.21/mods")).unwrap();
+        let changes = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(changes.contains(&RootChange::Mods {
+            version: Some("1.21".to_string())
+        }));
+        assert_eq!(4, watcher.watched_count());
+
+        fs::write(root.join("versions/1.21/mods/a.jar"), b"").unwrap();
+        let changes = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(
+            vec![RootChange::Mods {
+                version: Some("1.21".to_string())
+            }],
+            changes
+        );
+    }
+}
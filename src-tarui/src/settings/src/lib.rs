@@ -0,0 +1,344 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// 当前的配置版本，修改结构时加一并在 migrate 中添加对应的迁移
+pub const SETTINGS_VERSION: u32 = 1;
+
+const SETTINGS_DIR: &str = "rmcl";
+const SETTINGS_FILE: &str = "settings.json";
+
+// 启动器设置，保存在用户配置目录下的 rmcl/settings.json
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Settings {
+    pub version: u32,
+    #[serde(default)]
+    pub roots: Vec<Root>,
+    // 上次选中的根目录，启动时排在最前面
+    pub selected_root: Option<String>,
+    pub selected_version: Option<String>,
+    // 默认账户的用户名，启动游戏时未指定用户名则使用它
+    pub default_account: Option<String>,
+    // 监听根目录的文件变化，自动刷新版本、mod 与截图
+    #[serde(default)]
+    pub watch_roots: bool,
+    #[serde(default)]
+    pub java: JavaSettings,
+    #[serde(default)]
+    pub download: DownloadSettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
+}
+
+// 已添加的游戏根目录
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct Root {
+    pub path: String,
+    pub display_name: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+pub struct JavaSettings {
+    // 为空时使用 PATH 中的 java
+    pub default_path: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct DownloadSettings {
+    // CurseForge 接口的 API key，调用时未指定则使用它
+    pub curseforge_api_key: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+#[serde(default)]
+pub struct BackupSettings {
+    // 每个存档保留的备份数量，为 0 时不限制
+    pub max_backups: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self { max_backups: 10 }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: SETTINGS_VERSION,
+            roots: Vec::new(),
+            selected_root: None,
+            selected_version: None,
+            default_account: None,
+            watch_roots: false,
+            java: JavaSettings::default(),
+            download: DownloadSettings::default(),
+            backup: BackupSettings::default(),
+        }
+    }
+}
+
+// 默认的配置文件路径
+pub fn settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(SETTINGS_DIR).join(SETTINGS_FILE))
+}
+
+// 把旧版本的配置逐步迁移到当前版本
+fn migrate(mut value: Value) -> Result<Value, Box<dyn std::error::Error>> {
+    let version = value["version"].as_u64().unwrap_or(0) as u32;
+    if version > SETTINGS_VERSION {
+        return Err(format!(
+            "Settings version {} is newer than supported version {}",
+            version, SETTINGS_VERSION
+        )
+        .into());
+    }
+
+    value["version"] = Value::from(SETTINGS_VERSION);
+    Ok(value)
+}
+
+// 按 RFC 7386 合并 patch，null 表示删除字段
+fn merge(target: &mut Value, patch: Value) {
+    match patch {
+        Value::Object(patch) => {
+            if !target.is_object() {
+                *target = Value::Object(Default::default());
+            }
+            let object = target.as_object_mut().unwrap();
+            for (key, value) in patch {
+                if value.is_null() {
+                    object.remove(&key);
+                } else {
+                    merge(object.entry(key).or_insert(Value::Null), value);
+                }
+            }
+        }
+        patch => *target = patch,
+    }
+}
+
+impl Settings {
+    // 读取配置，文件不存在时使用默认值，旧版本的配置会被迁移
+    pub fn load_from(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let value: Value = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+        Ok(serde_json::from_value(migrate(value)?)?)
+    }
+
+    // 先写入临时文件再重命名，避免写入中断时损坏配置
+    pub fn save_to(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("json.tmp");
+        std::fs::write(&temp, serde_json::to_string_pretty(self)?)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        Self::load_from(&settings_path().ok_or("Config directory not found")?)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.save_to(&settings_path().ok_or("Config directory not found")?)
+    }
+
+    // 合并部分字段的修改，返回新的配置
+    pub fn update(&self, patch: Value) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut value = serde_json::to_value(self)?;
+        merge(&mut value, patch);
+        value["version"] = Value::from(SETTINGS_VERSION);
+        Ok(serde_json::from_value(value)?)
+    }
+
+    // 添加根目录，已存在时只更新显示名称
+    pub fn add_root(&mut self, path: &str, display_name: &str) {
+        match self.roots.iter_mut().find(|root| root.path == path) {
+            Some(root) => root.display_name = display_name.to_string(),
+            None => self.roots.push(Root {
+                path: path.to_string(),
+                display_name: display_name.to_string(),
+            }),
+        }
+    }
+
+    pub fn remove_root(&mut self, path: &str) {
+        self.roots.retain(|root| root.path != path);
+        if self.selected_root.as_deref() == Some(path) {
+            self.selected_root = None;
+            self.selected_version = None;
+        }
+    }
+
+    // 恢复根目录时的顺序，选中的根目录排在最前面
+    pub fn restore_order(&self) -> Vec<&Root> {
+        let (mut selected, others): (Vec<&Root>, Vec<&Root>) = self
+            .roots
+            .iter()
+            .partition(|root| self.selected_root.as_deref() == Some(root.path.as_str()));
+        selected.extend(others);
+        selected
+    }
+}
+
+// 串行化配置文件的读取-修改-写入，避免同时执行的命令互相覆盖对方的修改
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    lock: Mutex<()>,
+}
+
+impl Default for SettingsStore {
+    fn default() -> Self {
+        Self::new(settings_path())
+    }
+}
+
+impl SettingsStore {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    fn path(&self) -> Result<&Path, Box<dyn std::error::Error>> {
+        Ok(self.path.as_deref().ok_or("Config directory not found")?)
+    }
+
+    pub fn load(&self) -> Result<Settings, Box<dyn std::error::Error>> {
+        Settings::load_from(self.path()?)
+    }
+
+    // 在锁内读取配置、修改并保存，返回修改后的配置
+    pub fn modify(
+        &self,
+        f: impl FnOnce(&mut Settings) -> Result<(), Box<dyn std::error::Error>>,
+    ) -> Result<Settings, Box<dyn std::error::Error>> {
+        let _guard = self.lock.lock().map_err(|_| "Settings lock poisoned")?;
+        let path = self.path()?;
+        let mut settings = Settings::load_from(path)?;
+        f(&mut settings)?;
+        settings.save_to(path)?;
+        Ok(settings)
+    }
+
+    // 按 JSON merge patch 合并修改并保存
+    pub fn update(&self, patch: Value) -> Result<Settings, Box<dyn std::error::Error>> {
+        self.modify(|settings| {
+            *settings = settings.update(patch)?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate() {
+        let path = std::env::temp_dir().join("mcl-settings-migrate.json");
+        std::fs::write(
+            &path,
+            r#"{"version": 1, "roots": [{"path": "/games/.minecraft", "display_name": "Main"}]}"#,
+        )
+        .unwrap();
+
+        let settings = Settings::load_from(&path).unwrap();
+        assert_eq!(SETTINGS_VERSION, settings.version);
+        assert_eq!(
+            vec![Root {
+                path: "/games/.minecraft".to_string(),
+                display_name: "Main".to_string(),
+            }],
+            settings.roots
+        );
+        assert_eq!(10, settings.backup.max_backups);
+
+        std::fs::write(&path, r#"{"version": 999}"#).unwrap();
+        assert!(Settings::load_from(&path).is_err());
+    }
+
+    #[test]
+    fn test_save_and_update() {
+        let path = std::env::temp_dir().join("mcl-settings/settings.json");
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(Settings::default(), Settings::load_from(&path).unwrap());
+
+        let mut settings = Settings::default();
+        settings.add_root("/a", "A");
+        settings.add_root("/b", "B");
+        settings.add_root("/a", "Main");
+        settings.selected_root = Some("/b".to_string());
+        settings.java.default_path = Some("/usr/lib/jvm/17/bin/java".to_string());
+        settings.save_to(&path).unwrap();
+
+        let mut loaded = Settings::load_from(&path).unwrap();
+        assert_eq!(settings, loaded);
+        assert_eq!("Main", loaded.roots[0].display_name);
+        assert_eq!(
+            vec!["/b", "/a"],
+            loaded
+                .restore_order()
+                .iter()
+                .map(|root| root.path.as_str())
+                .collect::<Vec<_>>()
+        );
+
+        loaded.remove_root("/b");
+        assert_eq!(None, loaded.selected_root);
+
+        let updated = loaded
+            .update(serde_json::json!({
+                "default_account": "Steve",
+                "download": { "curseforge_api_key": "key" },
+                "java": { "default_path": null }
+            }))
+            .unwrap();
+        assert_eq!(Some("Steve".to_string()), updated.default_account);
+        assert_eq!(Some("key".to_string()), updated.download.curseforge_api_key);
+        assert_eq!(None, updated.java.default_path);
+        assert!(loaded.update(serde_json::json!({ "roots": 1 })).is_err());
+    }
+
+    #[test]
+    fn test_store() {
+        let path = std::env::temp_dir().join("mcl-settings-store/settings.json");
+        let _ = std::fs::remove_file(&path);
+        let store = SettingsStore::new(Some(path.clone()));
+
+        // 同时添加的根目录都被保存，不会互相覆盖
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let store = &store;
+                scope.spawn(move || {
+                    let root = format!("/root{}", i);
+                    store
+                        .modify(|settings| {
+                            settings.add_root(&root, &root);
+                            Ok(())
+                        })
+                        .unwrap();
+                });
+            }
+        });
+        assert_eq!(8, store.load().unwrap().roots.len());
+
+        let updated = store
+            .update(serde_json::json!({ "selected_root": "/root3" }))
+            .unwrap();
+        assert_eq!(updated, Settings::load_from(&path).unwrap());
+        assert!(store.modify(|_| Err("failed".into())).is_err());
+        assert_eq!(updated, store.load().unwrap());
+        assert!(SettingsStore::new(None).load().is_err());
+    }
+}